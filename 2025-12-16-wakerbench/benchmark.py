@@ -2,7 +2,7 @@
 """
 Benchmark comparing different approaches to waking up Python async tasks from Rust.
 
-Both approaches:
+All approaches:
 - Python drives the benchmark loop and waits for wakeups
 - Rust OS thread sends the notification
 - We measure the latency Python experiences
@@ -16,23 +16,41 @@ Approach 2: call_soon_threadsafe wakeup
     - Python provides a callback and event loop to Rust
     - Rust thread acquires GIL and calls loop.call_soon_threadsafe(callback)
     - Python event loop wakes up
+
+Approach 3: eventfd wakeup (Linux only)
+    - Python registers an eventfd with loop.add_reader()
+    - Rust thread adds 1 to the eventfd counter (NO GIL acquisition)
+    - Python event loop wakes up
+
+Approach 4: kqueue EVFILT_USER wakeup (macOS only)
+    - Python registers a kqueue FD with loop.add_reader()
+    - Rust thread triggers a user event on the kqueue (NO GIL acquisition)
+    - Python event loop wakes up
+
+Approach 5: socket wakeup (cross-platform)
+    - Python registers one end of a socket pair with loop.add_reader()
+      (or awaits loop.sock_recv() under Windows' ProactorEventLoop)
+    - Rust thread writes a byte to the other end (NO GIL acquisition)
+    - Python event loop wakes up
 """
 
+import argparse
 import asyncio
+import socket
+import sys
 import time
 import statistics
 import wakerbench
 
 
-async def bench_fd_wakeup(iterations: int) -> tuple[float, list[float]]:
+async def bench_reader_wakeup(waker, iterations: int) -> tuple[float, list[float]]:
     """
-    Benchmark FD-based wakeup.
+    Benchmark any FD-style waker (pipe, eventfd, socket, kqueue).
 
-    Rust thread writes to pipe -> Python event loop wakes up.
-    No GIL acquisition on Rust side.
+    Python registers the waker's read FD with loop.add_reader(); a Rust thread
+    makes it readable. No GIL acquisition on Rust side.
     """
     loop = asyncio.get_event_loop()
-    waker = wakerbench.create_fd_waker()
 
     latencies = []
     wakeup_event = asyncio.Event()
@@ -48,8 +66,8 @@ async def bench_fd_wakeup(iterations: int) -> tuple[float, list[float]]:
             wakeup_event.clear()
             start = time.perf_counter_ns()
 
-            # Rust OS thread will write to the pipe (no GIL)
-            wakerbench.fd_wakeup_from_thread(waker, 0)
+            # Rust OS thread will make the FD readable (no GIL)
+            wakerbench.wakeup_from_thread(waker, 0)
 
             await wakeup_event.wait()
             end = time.perf_counter_ns()
@@ -60,6 +78,54 @@ async def bench_fd_wakeup(iterations: int) -> tuple[float, list[float]]:
     return statistics.mean(latencies), latencies
 
 
+async def bench_fd_wakeup(iterations: int) -> tuple[float, list[float]]:
+    """Rust thread writes to pipe -> Python event loop wakes up."""
+    waker = wakerbench.create_fd_waker()
+    return await bench_reader_wakeup(waker, iterations)
+
+
+async def bench_eventfd_wakeup(iterations: int) -> tuple[float, list[float]]:
+    """Rust thread increments the eventfd counter -> Python event loop wakes up."""
+    waker = wakerbench.create_eventfd_waker()
+    return await bench_reader_wakeup(waker, iterations)
+
+
+async def bench_kqueue_wakeup(iterations: int) -> tuple[float, list[float]]:
+    """Rust thread triggers an EVFILT_USER event -> Python event loop wakes up."""
+    waker = wakerbench.create_kqueue_waker()
+    return await bench_reader_wakeup(waker, iterations)
+
+
+async def bench_socket_wakeup(iterations: int) -> tuple[float, list[float]]:
+    """Rust thread writes to a socket pair -> Python event loop wakes up."""
+    waker = wakerbench.create_socket_waker()
+    try:
+        return await bench_reader_wakeup(waker, iterations)
+    except NotImplementedError:
+        # ProactorEventLoop has no add_reader; await a completed recv instead
+        return await bench_socket_wakeup_proactor(waker, iterations)
+
+
+async def bench_socket_wakeup_proactor(waker, iterations: int) -> tuple[float, list[float]]:
+    """Socket-based wakeup for loops without add_reader (Windows ProactorEventLoop)."""
+    loop = asyncio.get_event_loop()
+    # Borrow the waker's socket; detach() below so Python doesn't close it
+    sock = socket.socket(fileno=waker.get_read_fd())
+    latencies = []
+
+    try:
+        for _ in range(iterations):
+            start = time.perf_counter_ns()
+            wakerbench.wakeup_from_thread(waker, 0)
+            await loop.sock_recv(sock, 64)
+            end = time.perf_counter_ns()
+            latencies.append(end - start)
+    finally:
+        sock.detach()
+
+    return statistics.mean(latencies), latencies
+
+
 async def bench_callback_wakeup(iterations: int) -> tuple[float, list[float]]:
     """
     Benchmark call_soon_threadsafe wakeup.
@@ -81,7 +147,7 @@ async def bench_callback_wakeup(iterations: int) -> tuple[float, list[float]]:
         start = time.perf_counter_ns()
 
         # Rust OS thread will acquire GIL and call call_soon_threadsafe
-        wakerbench.callback_wakeup_from_thread(waker, 0)
+        wakerbench.wakeup_from_thread(waker, 0)
 
         await wakeup_event.wait()
         end = time.perf_counter_ns()
@@ -115,6 +181,57 @@ async def bench_pure_python_wakeup(iterations: int) -> tuple[float, list[float]]
     return statistics.mean(latencies), latencies
 
 
+async def wait_for_acks(coordinator, expected: int):
+    """Yield to the event loop until `expected` wakes have been acknowledged."""
+    while coordinator.get_ack_count() < expected:
+        await asyncio.sleep(0.001)
+
+
+async def bench_roundtrip(iterations: int) -> list[tuple[str, "wakerbench.LatencyRecorder"]]:
+    """
+    Round-trip (ping-pong) benchmark, timed entirely in Rust.
+
+    The Rust thread wakes Python, waits for coordinator.ack() from the callback,
+    and records the full round trip before waking again.
+    """
+    loop = asyncio.get_event_loop()
+    results = []
+
+    # FD-based: acknowledge from the add_reader callback
+    if hasattr(wakerbench, "FdWaker"):
+        waker, roundtrip = wakerbench.create_fd_waker(), wakerbench.fd_wakeup_roundtrip
+    else:
+        waker, roundtrip = wakerbench.create_socket_waker(), wakerbench.socket_wakeup_roundtrip
+    coordinator = wakerbench.BenchCoordinator()
+    recorder = wakerbench.LatencyRecorder()
+
+    def on_readable():
+        waker.drain()
+        coordinator.ack()
+
+    try:
+        loop.add_reader(waker.get_read_fd(), on_readable)
+    except NotImplementedError:
+        pass  # ProactorEventLoop: no add_reader, skip the FD round trip
+    else:
+        try:
+            roundtrip(waker, coordinator, iterations, recorder)
+            await wait_for_acks(coordinator, iterations)
+        finally:
+            loop.remove_reader(waker.get_read_fd())
+        results.append(("FD-based (no GIL)", recorder))
+
+    # call_soon_threadsafe: the scheduled callback is the acknowledgment
+    cb_coordinator = wakerbench.BenchCoordinator()
+    cb_recorder = wakerbench.LatencyRecorder()
+    cb_waker = wakerbench.create_callback_waker(cb_coordinator.ack, loop)
+    wakerbench.callback_wakeup_roundtrip(cb_waker, cb_coordinator, iterations, cb_recorder)
+    await wait_for_acks(cb_coordinator, iterations)
+    results.append(("call_soon_threadsafe (GIL)", cb_recorder))
+
+    return results
+
+
 def print_stats(name: str, latencies: list[float]):
     """Print detailed statistics for a benchmark."""
     mean = statistics.mean(latencies)
@@ -137,38 +254,49 @@ def print_stats(name: str, latencies: list[float]):
 async def main():
     iterations = 100
 
+    # (label, benchmark) pairs; the first available one is the baseline
+    approaches = []
+    if hasattr(wakerbench, "FdWaker"):
+        approaches.append(("FD-based (no GIL)", bench_fd_wakeup))
+    if hasattr(wakerbench, "EventFdWaker"):
+        approaches.append(("eventfd (no GIL)", bench_eventfd_wakeup))
+    if hasattr(wakerbench, "KqueueUserWaker"):
+        approaches.append(("kqueue EVFILT_USER (no GIL)", bench_kqueue_wakeup))
+    approaches.append(("Socket-based (no GIL)", bench_socket_wakeup))
+    approaches.append(("call_soon_threadsafe (GIL)", bench_callback_wakeup))
+    approaches.append(("Pure Python (executor)", bench_pure_python_wakeup))
+
     print("=" * 60)
     print("Wakeup Latency Benchmark")
     print("=" * 60)
     print(f"\nIterations: {iterations}")
+    print(f"Event loop: {type(asyncio.get_running_loop()).__name__}")
+    info = wakerbench.runtime_info()
+    gil = "enabled" if info["gil_enabled"] else "disabled"
+    build = "free-threaded" if info["free_threaded_build"] else "default"
+    print(f"Python: {info['python_version'].split()[0]} ({build} build, GIL {gil})")
     print("\nScenario: Python waits, Rust OS thread sends notification")
 
     # Warmup
     print("\nWarming up...")
-    await bench_fd_wakeup(10)
-    await bench_callback_wakeup(10)
-    await bench_pure_python_wakeup(10)
+    for _, bench in approaches:
+        await bench(10)
 
     # Run benchmarks
-    print("\nRunning benchmarks...")
-
-    print("\n  FD-based (no GIL on Rust side)...")
-    fd_mean, fd_latencies = await bench_fd_wakeup(iterations)
-
-    print("  call_soon_threadsafe (GIL on Rust side)...")
-    cb_mean, cb_latencies = await bench_callback_wakeup(iterations)
-
-    print("  Pure Python (run_in_executor)...")
-    py_mean, py_latencies = await bench_pure_python_wakeup(iterations)
+    print("\nRunning benchmarks...\n")
+    results = []
+    for name, bench in approaches:
+        print(f"  {name}...")
+        mean, latencies = await bench(iterations)
+        results.append((name, mean, latencies))
 
     # Print results
     print("\n" + "=" * 60)
     print("Results")
     print("=" * 60)
 
-    print_stats("FD-based (no GIL)", fd_latencies)
-    print_stats("call_soon_threadsafe (GIL)", cb_latencies)
-    print_stats("Pure Python (executor)", py_latencies)
+    for name, _, latencies in results:
+        print_stats(name, latencies)
 
     # Summary
     print("\n" + "=" * 60)
@@ -176,15 +304,44 @@ async def main():
     print("=" * 60)
     print(f"\n{'Approach':<35} {'Mean':>10} {'Relative':>10}")
     print("-" * 55)
-    print(f"{'FD-based (no GIL)':<35} {fd_mean/1000:>8.1f} µs {'1.0x':>10}")
-    print(f"{'call_soon_threadsafe (GIL)':<35} {cb_mean/1000:>8.1f} µs {cb_mean/fd_mean:>9.1f}x")
-    print(f"{'Pure Python (executor)':<35} {py_mean/1000:>8.1f} µs {py_mean/fd_mean:>9.1f}x")
+    base_mean = results[0][1]
+    for name, mean, _ in results:
+        print(f"{name:<35} {mean/1000:>8.1f} µs {mean/base_mean:>9.1f}x")
 
+    by_name = {name: mean for name, mean, _ in results}
+    fd_mean = by_name.get("FD-based (no GIL)", by_name["Socket-based (no GIL)"])
+    cb_mean = by_name["call_soon_threadsafe (GIL)"]
     if cb_mean > fd_mean:
         print(f"\nFD-based is {cb_mean / fd_mean:.1f}x faster than call_soon_threadsafe")
     else:
         print(f"\ncall_soon_threadsafe is {fd_mean / cb_mean:.1f}x faster than FD-based")
 
+    # The floor under every number above: a no-op call into the extension
+    print(f"\n{'FFI baseline':<35} {'P50':>10} {'P99':>10}")
+    print("-" * 57)
+    for name, summary in wakerbench.measure_ffi_baselines(10_000).items():
+        print(f"{name:<35} {summary.p50:>7} ns {summary.p99:>7} ns")
+
+    # Round trip, timed in Rust
+    print("\n" + "=" * 60)
+    print("Round-trip (Rust wake -> Python ack -> Rust)")
+    print("=" * 60)
+    print(f"\n{'Approach':<35} {'P50':>10} {'P99':>10}")
+    print("-" * 57)
+    for name, recorder in await bench_roundtrip(iterations):
+        print(f"{name:<35} {recorder.p50()/1000:>7.1f} µs {recorder.p99()/1000:>7.1f} µs")
+
 
 if __name__ == "__main__":
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[1])
+    parser.add_argument(
+        "--selector-loop",
+        action="store_true",
+        help="On Windows, use SelectorEventLoop instead of the default ProactorEventLoop",
+    )
+    args = parser.parse_args()
+
+    if args.selector_loop and sys.platform == "win32":
+        asyncio.set_event_loop_policy(asyncio.WindowsSelectorEventLoopPolicy())
+
     asyncio.run(main())
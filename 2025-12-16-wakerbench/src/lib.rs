@@ -1,304 +1,15588 @@
+use hdrhistogram::Histogram;
 use pyo3::prelude::*;
-use std::os::fd::{FromRawFd, OwnedFd, RawFd};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::io::{Read, Write};
+#[cfg(unix)]
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawSocket, RawSocket};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+/// Trace the rest of the enclosing block as a span named `$name` (a no-op
+/// unless built with the `trace` feature; see `start_trace`)
+macro_rules! trace_scope {
+    ($name:literal) => {
+        #[cfg(feature = "trace")]
+        let _span = tracing::trace_span!($name).entered();
+    };
+}
+
+// =============================================================================
+// Clock and wake timestamps
+// =============================================================================
+
+/// Current monotonic time in nanoseconds. On Unix this reads CLOCK_MONOTONIC,
+/// the same clock as Python's `time.monotonic_ns()` on Linux.
+#[cfg(unix)]
+fn monotonic_ns() -> u64 {
+    clock_gettime_ns(libc::CLOCK_MONOTONIC)
+}
+
+/// Current monotonic time in nanoseconds, relative to the first call.
+#[cfg(not(unix))]
+fn monotonic_ns() -> u64 {
+    static ANCHOR: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
+    ANCHOR.get_or_init(Instant::now).elapsed().as_nanos() as u64
+}
+
+/// Read the monotonic clock, the default clock for wake timestamps
+#[pyfunction]
+#[pyo3(name = "monotonic_ns")]
+fn py_monotonic_ns() -> u64 {
+    monotonic_ns()
+}
+
+/// Read a POSIX clock in nanoseconds
+#[cfg(unix)]
+fn clock_gettime_ns(clock: libc::clockid_t) -> u64 {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe {
+        libc::clock_gettime(clock, &mut ts);
+    }
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+/// Clock used to take timestamps and measure latencies. Different clocks
+/// differ in resolution and read cost, which matters for sub-microsecond results.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ClockSource {
+    /// CLOCK_MONOTONIC (Python's `time.monotonic_ns()` on Linux)
+    Monotonic,
+    /// CLOCK_MONOTONIC_RAW: not slewed by NTP (Linux and macOS)
+    MonotonicRaw,
+    /// CLOCK_REALTIME: wall-clock time, may jump
+    Realtime,
+    /// The x86-64 timestamp counter, scaled to nanoseconds (x86-64 only)
+    Tsc,
+}
+
+impl ClockSource {
+    fn is_available(self) -> bool {
+        match self {
+            Self::Monotonic | Self::Realtime => true,
+            Self::MonotonicRaw => cfg!(any(target_os = "linux", target_os = "macos")),
+            Self::Tsc => cfg!(target_arch = "x86_64"),
+        }
+    }
+
+    /// Raise NotImplementedError if this clock can't be read on this platform
+    fn check(self) -> PyResult<Self> {
+        if !self.is_available() {
+            return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                format!("{} is not available on this platform", self.name()),
+            ));
+        }
+        Ok(self)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Monotonic => "ClockSource.Monotonic",
+            Self::MonotonicRaw => "ClockSource.MonotonicRaw",
+            Self::Realtime => "ClockSource.Realtime",
+            Self::Tsc => "ClockSource.Tsc",
+        }
+    }
+
+    /// Read the clock in nanoseconds. Unavailable clocks read the monotonic clock;
+    /// Python-facing entry points reject them with `check()` first.
+    fn read_ns(self) -> u64 {
+        match self {
+            Self::Monotonic => monotonic_ns(),
+            #[cfg(any(target_os = "linux", target_os = "macos"))]
+            Self::MonotonicRaw => clock_gettime_ns(libc::CLOCK_MONOTONIC_RAW),
+            #[cfg(unix)]
+            Self::Realtime => clock_gettime_ns(libc::CLOCK_REALTIME),
+            #[cfg(not(unix))]
+            Self::Realtime => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos() as u64),
+            #[cfg(target_arch = "x86_64")]
+            Self::Tsc => tsc_ns(),
+            #[allow(unreachable_patterns)]
+            _ => monotonic_ns(),
+        }
+    }
+}
+
+#[pymethods]
+impl ClockSource {
+    /// Whether this clock can be read on this platform
+    #[pyo3(name = "is_available")]
+    fn py_is_available(&self) -> bool {
+        self.is_available()
+    }
+
+    /// Read the clock in nanoseconds
+    fn now_ns(&self) -> PyResult<u64> {
+        Ok(self.check()?.read_ns())
+    }
+}
+
+/// Nanoseconds per TSC tick, measured once against CLOCK_MONOTONIC over ~10ms
+#[cfg(target_arch = "x86_64")]
+fn tsc_ns_per_tick() -> f64 {
+    static NS_PER_TICK: std::sync::OnceLock<f64> = std::sync::OnceLock::new();
+    *NS_PER_TICK.get_or_init(|| {
+        let (start_ns, start_ticks) = (monotonic_ns(), unsafe { std::arch::x86_64::_rdtsc() });
+        std::thread::sleep(Duration::from_millis(10));
+        let (end_ns, end_ticks) = (monotonic_ns(), unsafe { std::arch::x86_64::_rdtsc() });
+        (end_ns - start_ns) as f64 / (end_ticks - start_ticks).max(1) as f64
+    })
+}
+
+#[cfg(target_arch = "x86_64")]
+fn tsc_ns() -> u64 {
+    (unsafe { std::arch::x86_64::_rdtsc() } as f64 * tsc_ns_per_tick()) as u64
+}
+
+/// Clock used for wake timestamps, as a ClockSource discriminant
+static TIMESTAMP_CLOCK: std::sync::atomic::AtomicU8 =
+    std::sync::atomic::AtomicU8::new(ClockSource::Monotonic as u8);
+
+fn timestamp_clock_source() -> ClockSource {
+    match TIMESTAMP_CLOCK.load(Ordering::Relaxed) {
+        1 => ClockSource::MonotonicRaw,
+        2 => ClockSource::Realtime,
+        3 => ClockSource::Tsc,
+        _ => ClockSource::Monotonic,
+    }
+}
+
+/// Select the clock all wakers use for `last_wake_ns()`/`wake_timestamps()`.
+/// Compare against `clock.now_ns()` of the same clock.
+#[pyfunction]
+fn set_timestamp_clock(clock: ClockSource) -> PyResult<()> {
+    TIMESTAMP_CLOCK.store(clock.check()? as u8, Ordering::Relaxed);
+    Ok(())
+}
+
+/// The clock currently used for wake timestamps
+#[pyfunction]
+fn timestamp_clock() -> ClockSource {
+    timestamp_clock_source()
+}
+
+/// Read `clock` in nanoseconds, by default the timestamp clock selected with
+/// `set_timestamp_clock()`. The same read the Rust side stamps wakes with, so
+/// the result subtracts directly from `last_wake_ns()` without calibration.
+#[pyfunction]
+#[pyo3(signature = (clock=None))]
+fn now_ns(clock: Option<ClockSource>) -> PyResult<u64> {
+    Ok(clock
+        .unwrap_or_else(timestamp_clock_source)
+        .check()?
+        .read_ns())
+}
+
+/// Measure a clock's observed resolution (smallest non-zero step between
+/// consecutive reads) and read cost (mean time per read) over `samples` reads.
+/// Runs without the GIL. To compare a Rust clock with a Python one, use
+/// `clock_calibrate()`.
+#[pyfunction]
+#[pyo3(signature = (clock, samples=100_000))]
+fn clock_resolution<'py>(
+    py: Python<'py>,
+    clock: ClockSource,
+    samples: usize,
+) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+    let clock = clock.check()?;
+    if samples < 2 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "samples must be at least 2",
+        ));
+    }
+
+    let (resolution_ns, read_cost_ns) = py.allow_threads(|| {
+        // Warm up (and, for the TSC, calibrate) before measuring
+        clock.read_ns();
+        let mut resolution = u64::MAX;
+        let mut prev = clock.read_ns();
+        let start = clock.read_ns();
+        for _ in 0..samples {
+            let now = clock.read_ns();
+            if now > prev {
+                resolution = resolution.min(now - prev);
+            }
+            prev = now;
+        }
+        let total = prev.saturating_sub(start);
+        (resolution, total as f64 / samples as f64)
+    });
+
+    let info = pyo3::types::PyDict::new(py);
+    info.set_item("clock", clock)?;
+    info.set_item(
+        "resolution_ns",
+        (resolution_ns != u64::MAX).then_some(resolution_ns),
+    )?;
+    info.set_item("read_cost_ns", read_cost_ns)?;
+    info.set_item("samples", samples)?;
+    Ok(info)
+}
+
+/// Offset and drift between a Rust clock and a Python clock, measured by
+/// `clock_calibrate()`. Converts timestamps from one to the other, so a
+/// Rust-stamped send and a Python-stamped receipt can be subtracted.
+#[pyclass(frozen)]
+#[derive(Clone, Copy)]
+struct ClockCalibration {
+    /// The Rust-side clock
+    #[pyo3(get)]
+    clock: ClockSource,
+    /// Python time minus Rust time, as of `reference_ns`
+    #[pyo3(get)]
+    offset_ns: i64,
+    /// How fast the offset grows, in ns per ms (parts per million) of Rust time
+    #[pyo3(get)]
+    drift_ppm: f64,
+    /// Rust time at which `offset_ns` was measured
+    #[pyo3(get)]
+    reference_ns: u64,
+    /// Half the tightest round trip: how far off the offset can be
+    #[pyo3(get)]
+    uncertainty_ns: u64,
+}
+
+impl ClockCalibration {
+    /// Python time minus Rust time, at Rust time `rust_ns`
+    fn offset_at(self, rust_ns: u64) -> i128 {
+        let elapsed = rust_ns as i128 - self.reference_ns as i128;
+        self.offset_ns as i128 + (elapsed as f64 * self.drift_ppm * 1e-6).round() as i128
+    }
+
+    fn to_python(self, rust_ns: u64) -> i64 {
+        (rust_ns as i128 + self.offset_at(rust_ns)).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    fn to_rust(self, python_ns: i64) -> u64 {
+        // Drift is tiny, so evaluating it at the undrifted estimate is exact
+        // to well under a nanosecond
+        let estimate = (python_ns as i128 - self.offset_ns as i128).clamp(0, u64::MAX as i128);
+        (python_ns as i128 - self.offset_at(estimate as u64)).clamp(0, u64::MAX as i128) as u64
+    }
+}
+
+#[pymethods]
+impl ClockCalibration {
+    /// Convert a timestamp on the Rust clock to the Python clock
+    #[pyo3(name = "to_python_ns")]
+    fn py_to_python_ns(&self, rust_ns: u64) -> i64 {
+        self.to_python(rust_ns)
+    }
+
+    /// Convert a timestamp on the Python clock to the Rust clock
+    #[pyo3(name = "to_rust_ns")]
+    fn py_to_rust_ns(&self, python_ns: i64) -> u64 {
+        self.to_rust(python_ns)
+    }
+
+    #[pyo3(name = "to_dict")]
+    fn py_to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("clock", self.clock)?;
+        dict.set_item("offset_ns", self.offset_ns)?;
+        dict.set_item("drift_ppm", self.drift_ppm)?;
+        dict.set_item("reference_ns", self.reference_ns)?;
+        dict.set_item("uncertainty_ns", self.uncertainty_ns)?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ClockCalibration(clock={}, offset_ns={}, drift_ppm={:.3}, uncertainty_ns={})",
+            self.clock.name(),
+            self.offset_ns,
+            self.drift_ppm,
+            self.uncertainty_ns
+        )
+    }
+}
+
+/// The tightest of `samples` reads of `python_clock`, each bracketed by
+/// reads of `clock`: (Rust midpoint, Python minus midpoint, round trip), in ns
+fn clock_offset_sample(
+    clock: ClockSource,
+    python_clock: &Bound<'_, PyAny>,
+    samples: usize,
+) -> PyResult<(u64, i64, u64)> {
+    let mut best: Option<(u64, i64, u64)> = None;
+    for _ in 0..samples {
+        let before = clock.read_ns();
+        let python: i64 = python_clock.call0()?.extract()?;
+        let after = clock.read_ns();
+        let round_trip = after.saturating_sub(before);
+        if best.is_none_or(|(_, _, best)| round_trip < best) {
+            let midpoint = before + round_trip / 2;
+            best = Some((
+                midpoint,
+                (python as i128 - midpoint as i128) as i64,
+                round_trip,
+            ));
+        }
+    }
+    Ok(best.expect("samples is at least 1"))
+}
+
+/// Measure the offset between a Rust clock (`clock`) and a Python clock
+/// (`python_clock`, `time.monotonic_ns` by default) by round-tripping
+/// timestamps: of `samples` Python reads bracketed by Rust reads, the
+/// tightest pair gives the offset. A second measurement `duration_secs`
+/// later (slept without the GIL) gives the drift; pass 0 to skip it.
+///
+/// `clock_resolution()` measures a single clock's resolution and read cost.
+#[pyfunction]
+#[pyo3(signature = (clock=ClockSource::Monotonic, samples=1000, duration_secs=0.1, python_clock=None))]
+fn clock_calibrate(
+    py: Python<'_>,
+    clock: ClockSource,
+    samples: usize,
+    duration_secs: f64,
+    python_clock: Option<Bound<'_, PyAny>>,
+) -> PyResult<ClockCalibration> {
+    let clock = clock.check()?;
+    if samples == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "samples must be at least 1",
+        ));
+    }
+    let duration = Duration::try_from_secs_f64(duration_secs).map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "duration_secs must be a non-negative number of seconds",
+        )
+    })?;
+    let python_clock = match python_clock {
+        Some(python_clock) => python_clock,
+        None => py.import("time")?.getattr("monotonic_ns")?,
+    };
+
+    // Warm up (and, for the TSC, calibrate) before measuring
+    clock.read_ns();
+    let (reference_ns, offset_ns, round_trip) = clock_offset_sample(clock, &python_clock, samples)?;
+    let mut calibration = ClockCalibration {
+        clock,
+        offset_ns,
+        drift_ppm: 0.0,
+        reference_ns,
+        uncertainty_ns: round_trip / 2,
+    };
+    if !duration.is_zero() {
+        py.allow_threads(|| std::thread::sleep(duration));
+        let (later_ns, later_offset_ns, later_round_trip) =
+            clock_offset_sample(clock, &python_clock, samples)?;
+        let elapsed = later_ns.saturating_sub(reference_ns).max(1);
+        calibration.drift_ppm = (later_offset_ns as f64 - offset_ns as f64) / elapsed as f64 * 1e6;
+        calibration.uncertainty_ns = round_trip.max(later_round_trip) / 2;
+    }
+    Ok(calibration)
+}
+
+/// Number of most recent wake timestamps retained per waker
+const WAKE_TIMESTAMP_CAPACITY: usize = 4096;
+
+/// Ring buffer of the moments (ns on the timestamp clock) a waker's handles started a wake.
+/// Written lock-free by producer threads, read from Python.
+struct WakeTimestamps {
+    slots: Box<[AtomicU64]>,
+    next: AtomicU64,
+}
+
+impl WakeTimestamps {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            slots: (0..WAKE_TIMESTAMP_CAPACITY)
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+            next: AtomicU64::new(0),
+        })
+    }
+
+    /// Stamp the current time; call immediately before waking
+    fn stamp(&self) {
+        let now = timestamp_clock_source().read_ns();
+        let index = self.next.fetch_add(1, Ordering::Relaxed) as usize;
+        self.slots[index % WAKE_TIMESTAMP_CAPACITY].store(now, Ordering::Release);
+    }
+
+    fn count(&self) -> u64 {
+        self.next.load(Ordering::Acquire)
+    }
+
+    fn last(&self) -> Option<u64> {
+        let count = self.count() as usize;
+        if count == 0 {
+            return None;
+        }
+        Some(self.slots[(count - 1) % WAKE_TIMESTAMP_CAPACITY].load(Ordering::Acquire))
+    }
+
+    /// Retained timestamps, oldest first
+    fn snapshot(&self) -> Vec<u64> {
+        let count = self.count() as usize;
+        let start = count.saturating_sub(WAKE_TIMESTAMP_CAPACITY);
+        (start..count)
+            .map(|i| self.slots[i % WAKE_TIMESTAMP_CAPACITY].load(Ordering::Acquire))
+            .collect()
+    }
+}
+
+/// A callable implemented in Rust that records when it was called, for
+/// registering with `loop.add_reader()` or as a waker's callback. Timing a
+/// receipt this way runs no Python bytecode, so the measurement costs far
+/// less than the wake it measures.
+///
+/// Each call appends a receipt: the time (on the timestamp clock, like
+/// `wake_timestamps()`) and a sequence number, which is the call's first
+/// argument when that is an int (as passed by sequence-numbered wakes) and
+/// the receipt's index otherwise. Given an FdWaker, each call also drains
+/// its pipe, so the callable can be passed to `add_reader()` directly.
+#[pyclass]
+struct TimestampingCallback {
+    receipts: Mutex<Vec<(u64, u64)>>,
+    #[cfg(unix)]
+    pipe: Option<Arc<Pipe>>,
+}
+
+#[pymethods]
+impl TimestampingCallback {
+    #[new]
+    #[pyo3(signature = (waker=None, capacity=0))]
+    fn new(waker: Option<&Bound<'_, PyAny>>, capacity: usize) -> PyResult<Self> {
+        #[cfg(unix)]
+        let pipe = match waker {
+            Some(waker) => Some(
+                waker
+                    .downcast::<FdWaker>()
+                    .map_err(|_| {
+                        PyErr::new::<pyo3::exceptions::PyTypeError, _>("waker must be an FdWaker")
+                    })?
+                    .borrow()
+                    .pipe()?,
+            ),
+            None => None,
+        };
+        #[cfg(not(unix))]
+        if waker.is_some() {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "draining a waker is only supported on Unix",
+            ));
+        }
+        Ok(Self {
+            receipts: Mutex::new(Vec::with_capacity(capacity)),
+            #[cfg(unix)]
+            pipe,
+        })
+    }
+
+    #[pyo3(signature = (*args))]
+    fn __call__(&self, args: &Bound<'_, pyo3::types::PyTuple>) {
+        let now = timestamp_clock_source().read_ns();
+        #[cfg(unix)]
+        if let Some(pipe) = &self.pipe {
+            pipe.drain();
+        }
+        let sequence = match args.len() {
+            0 => None,
+            _ => args
+                .get_item(0)
+                .ok()
+                .and_then(|arg| arg.extract::<u64>().ok()),
+        };
+        let mut receipts = self.receipts.lock().unwrap();
+        let sequence = sequence.unwrap_or(receipts.len() as u64);
+        receipts.push((sequence, now));
+    }
+
+    fn __len__(&self) -> usize {
+        self.receipts.lock().unwrap().len()
+    }
+
+    /// Receipt times, in call order
+    fn timestamps(&self) -> Vec<u64> {
+        self.receipts.lock().unwrap().iter().map(|r| r.1).collect()
+    }
+
+    /// Receipt sequence numbers, in call order
+    fn sequences(&self) -> Vec<u64> {
+        self.receipts.lock().unwrap().iter().map(|r| r.0).collect()
+    }
+
+    /// `(sequence, timestamp_ns)` for every call, in call order
+    fn receipts(&self) -> Vec<(u64, u64)> {
+        self.receipts.lock().unwrap().clone()
+    }
+
+    /// Time of the most recent call, or None before the first
+    fn last_ns(&self) -> Option<u64> {
+        self.receipts.lock().unwrap().last().map(|r| r.1)
+    }
+
+    fn clear(&self) {
+        self.receipts.lock().unwrap().clear();
+    }
+}
+
+// =============================================================================
+// Producer thread placement and scheduling
+// =============================================================================
+
+/// How a producer thread is placed and scheduled, applied as the thread's
+/// first action. Validated on the Python thread so bad options raise there.
+#[derive(Clone, Copy, Default)]
+struct ThreadPlacement {
+    cpu: Option<usize>,
+    #[cfg(unix)]
+    realtime: Option<RealtimeScheduling>,
+    /// Stack size in bytes, overriding `set_thread_options()`
+    stack_size: Option<usize>,
+}
+
+impl ThreadPlacement {
+    fn new(pin_cpu: Option<usize>, rt_priority: Option<i32>, rt_policy: &str) -> PyResult<Self> {
+        if let Some(cpu) = pin_cpu {
+            check_cpu(cpu)?;
+        }
+        let policy = match rt_policy {
+            "fifo" => RealtimePolicy::Fifo,
+            "rr" => RealtimePolicy::RoundRobin,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "rt_policy must be 'fifo' or 'rr', got {other:?}"
+                )))
+            }
+        };
+        let realtime = rt_priority
+            .map(|priority| RealtimeScheduling::new(policy, priority))
+            .transpose()?;
+
+        #[cfg(unix)]
+        return Ok(Self {
+            cpu: pin_cpu,
+            realtime,
+            stack_size: None,
+        });
+        #[cfg(not(unix))]
+        {
+            let _ = realtime;
+            Ok(Self {
+                cpu: pin_cpu,
+                stack_size: None,
+            })
+        }
+    }
+
+    /// Give threads started with this placement a `bytes` stack, or the
+    /// configured default when None
+    fn with_stack_size(mut self, bytes: Option<usize>) -> Self {
+        self.stack_size = bytes;
+        self
+    }
+
+    /// Builder for a thread named `name` (see `thread_builder`) with this
+    /// placement's stack size. The thread must still call `apply()`.
+    fn thread(&self, name: &str, index: Option<usize>) -> std::thread::Builder {
+        thread_builder(name, index, self.stack_size)
+    }
+
+    /// Options as recorded in benchmark results
+    fn describe(&self) -> Vec<(&'static str, ConfigValue)> {
+        let cpu = self
+            .cpu
+            .map_or(ConfigValue::None, |cpu| ConfigValue::Int(cpu as i64));
+        #[cfg(unix)]
+        let (priority, policy) = match self.realtime {
+            Some(realtime) => (
+                ConfigValue::Int(realtime.priority as i64),
+                ConfigValue::Str(if realtime.policy == libc::SCHED_RR {
+                    "rr"
+                } else {
+                    "fifo"
+                }),
+            ),
+            None => (ConfigValue::None, ConfigValue::None),
+        };
+        #[cfg(not(unix))]
+        let (priority, policy) = (ConfigValue::None, ConfigValue::None);
+        vec![
+            ("pin_cpu", cpu),
+            ("rt_priority", priority),
+            ("rt_policy", policy),
+        ]
+    }
+
+    fn apply(&self) {
+        if let Some(cpu) = self.cpu {
+            // Already validated against the process's allowed set
+            let _ = pin_thread_to_cpu(cpu);
+        }
+        #[cfg(unix)]
+        if let Some(realtime) = self.realtime {
+            realtime.apply();
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum RealtimePolicy {
+    Fifo,
+    RoundRobin,
+}
+
+/// Real-time scheduling class requested for a producer thread
+#[derive(Clone, Copy)]
+struct RealtimeScheduling {
+    #[cfg(unix)]
+    policy: libc::c_int,
+    #[cfg(unix)]
+    priority: libc::c_int,
+}
+
+/// Producer threads that asked for real-time scheduling but were refused
+/// (usually for lack of CAP_SYS_NICE / RLIMIT_RTPRIO) and ran normally instead
+#[cfg(unix)]
+static RT_FALLBACKS: AtomicU64 = AtomicU64::new(0);
+
+impl RealtimeScheduling {
+    #[cfg(unix)]
+    fn new(policy: RealtimePolicy, priority: i32) -> PyResult<Self> {
+        let policy = match policy {
+            RealtimePolicy::Fifo => libc::SCHED_FIFO,
+            RealtimePolicy::RoundRobin => libc::SCHED_RR,
+        };
+        let (min, max) = unsafe {
+            (
+                libc::sched_get_priority_min(policy),
+                libc::sched_get_priority_max(policy),
+            )
+        };
+        if !(min..=max).contains(&priority) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "rt_priority must be between {min} and {max}, got {priority}"
+            )));
+        }
+        Ok(Self { policy, priority })
+    }
+
+    #[cfg(not(unix))]
+    fn new(_policy: RealtimePolicy, _priority: i32) -> PyResult<Self> {
+        Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+            "real-time scheduling is only supported on Unix",
+        ))
+    }
+
+    /// Switch the calling thread to the real-time class, falling back to
+    /// normal scheduling (and counting it) if the OS refuses
+    #[cfg(unix)]
+    fn apply(&self) {
+        // Zeroed first: some platforms have padding fields beyond sched_priority
+        let mut param: libc::sched_param = unsafe { std::mem::zeroed() };
+        param.sched_priority = self.priority;
+        let result =
+            unsafe { libc::pthread_setschedparam(libc::pthread_self(), self.policy, &param) };
+        if result != 0 {
+            RT_FALLBACKS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Number of producer threads that requested `rt_priority` but ran under
+/// normal scheduling because the OS refused
+#[cfg(unix)]
+#[pyfunction]
+fn rt_priority_fallbacks() -> u64 {
+    RT_FALLBACKS.load(Ordering::Relaxed)
+}
+
+/// Name prefix and default stack size for the threads this module starts
+struct ThreadOptions {
+    prefix: String,
+    stack_size: Option<usize>,
+}
+
+static THREAD_OPTIONS: Mutex<ThreadOptions> = Mutex::new(ThreadOptions {
+    prefix: String::new(),
+    stack_size: None,
+});
+
+/// Builder for a thread named `{prefix}{name}`, plus `-{index}` if given,
+/// with `stack_size` bytes of stack or else the configured default. Linux
+/// shows `perf`, `ps` and debuggers only the first 15 bytes of the name;
+/// Rust keeps all of it for panic messages.
+fn thread_builder(
+    name: &str,
+    index: Option<usize>,
+    stack_size: Option<usize>,
+) -> std::thread::Builder {
+    let options = THREAD_OPTIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut full_name = format!("{}{name}", options.prefix);
+    if let Some(index) = index {
+        full_name.push_str(&format!("-{index}"));
+    }
+    let builder = std::thread::Builder::new().name(full_name);
+    match stack_size.or(options.stack_size) {
+        Some(bytes) => builder.stack_size(bytes),
+        None => builder,
+    }
+}
+
+/// Set how the threads this module starts from now on are named and sized:
+/// producers, pool workers, noise generators and forwarders. `prefix` is
+/// prepended to every thread name (e.g. `"run3-"`), so a run's threads can
+/// be told apart in `perf`, `py-spy` and debuggers. `stack_size` (bytes)
+/// replaces Rust's 2 MiB default, for callbacks that recurse deeply. Each
+/// call sets both; None restores the default.
+#[pyfunction]
+#[pyo3(signature = (prefix=None, stack_size=None))]
+fn set_thread_options(prefix: Option<String>, stack_size: Option<usize>) -> PyResult<()> {
+    if stack_size == Some(0) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "stack_size must be positive",
+        ));
+    }
+    *THREAD_OPTIONS.lock().unwrap_or_else(|e| e.into_inner()) = ThreadOptions {
+        prefix: prefix.unwrap_or_default(),
+        stack_size,
+    };
+    Ok(())
+}
+
+/// The current `set_thread_options()`: `prefix` and `stack_size`
+#[pyfunction]
+fn thread_options(py: Python<'_>) -> PyResult<Bound<'_, pyo3::types::PyDict>> {
+    let options = THREAD_OPTIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let dict = pyo3::types::PyDict::new(py);
+    dict.set_item("prefix", &options.prefix)?;
+    dict.set_item("stack_size", options.stack_size)?;
+    Ok(dict)
+}
+
+/// Spawn a producer thread named `name` with the given placement. The body
+/// gets the thread's state and should stop once it is cancelled, which
+/// `shutdown()` does.
+fn spawn_producer<F>(placement: ThreadPlacement, name: &str, body: F) -> std::io::Result<()>
+where
+    F: FnOnce(&TaskState) + Send + 'static,
+{
+    let guard = track_thread();
+    spawn_placed(placement, name, move || {
+        run_as_task(&guard.0, || body(&guard.0));
+    })
+}
+
+/// Spawn a thread named `name` with the given placement, leaving it to
+/// `body` to hold a registered TaskState. Fails if the OS refuses the
+/// thread, e.g. for a `stack_size` it can't map.
+fn spawn_placed<F>(placement: ThreadPlacement, name: &str, body: F) -> std::io::Result<()>
+where
+    F: FnOnce() + Send + 'static,
+{
+    placement.thread(name, None).spawn(move || {
+        placement.apply();
+        body();
+    })?;
+    Ok(())
+}
+
+/// CPUs the process was allowed to run on before any thread was pinned.
+/// Captured once so pinning the Python thread doesn't narrow what producers
+/// may be pinned to.
+#[cfg(target_os = "linux")]
+fn allowed_cpus() -> PyResult<&'static [usize]> {
+    static ALLOWED: std::sync::OnceLock<Vec<usize>> = std::sync::OnceLock::new();
+    if let Some(cpus) = ALLOWED.get() {
+        return Ok(cpus);
+    }
+
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::sched_getaffinity(0, std::mem::size_of_val(&set), &mut set) };
+    if result != 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyOSError, _>(
+            std::io::Error::last_os_error().to_string(),
+        ));
+    }
+    let cpus = (0..libc::CPU_SETSIZE as usize)
+        .filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+        .collect();
+    Ok(ALLOWED.get_or_init(|| cpus))
+}
+
+#[cfg(target_os = "linux")]
+fn check_cpu(cpu: usize) -> PyResult<()> {
+    let allowed = allowed_cpus()?;
+    if !allowed.contains(&cpu) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "CPU {cpu} is not in the allowed set {allowed:?}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn check_cpu(_cpu: usize) -> PyResult<()> {
+    Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+        "CPU pinning is only supported on Linux",
+    ))
+}
+
+/// Restrict the calling thread to a single CPU
+#[cfg(target_os = "linux")]
+fn pin_thread_to_cpu(cpu: usize) -> std::io::Result<()> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    unsafe { libc::CPU_SET(cpu, &mut set) };
+    let result = unsafe { libc::sched_setaffinity(0, std::mem::size_of_val(&set), &set) };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_thread_to_cpu(_cpu: usize) -> std::io::Result<()> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+/// Pin the calling Python thread (e.g. the one running the event loop) to `cpu`
+#[cfg(target_os = "linux")]
+#[pyfunction]
+fn pin_current_thread(cpu: usize) -> PyResult<()> {
+    check_cpu(cpu)?;
+    pin_thread_to_cpu(cpu).map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(e.to_string()))
+}
+
+/// CPUs that threads may be pinned to
+#[cfg(target_os = "linux")]
+#[pyfunction]
+fn available_cpus() -> PyResult<Vec<usize>> {
+    Ok(allowed_cpus()?.to_vec())
+}
+
+/// CPU the calling thread is running on right now
+#[cfg(target_os = "linux")]
+#[pyfunction]
+fn current_cpu() -> PyResult<usize> {
+    let cpu = unsafe { libc::sched_getcpu() };
+    if cpu < 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyOSError, _>(
+            std::io::Error::last_os_error().to_string(),
+        ));
+    }
+    Ok(cpu as usize)
+}
+
+// =============================================================================
+// Precise delays
+// =============================================================================
+
+/// How a producer thread waits out a delay
+#[derive(Clone, Copy)]
+enum TimerBackend {
+    /// `std::thread::sleep`: portable, but overshoots by tens of microseconds
+    Sleep,
+    /// An absolute CLOCK_MONOTONIC deadline armed on a timerfd
+    #[cfg(target_os = "linux")]
+    TimerFd,
+}
+
+impl TimerBackend {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "sleep" => Ok(Self::Sleep),
+            #[cfg(target_os = "linux")]
+            "timerfd" => Ok(Self::TimerFd),
+            #[cfg(not(target_os = "linux"))]
+            "timerfd" => Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                "the timerfd backend is only available on Linux",
+            )),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "timer must be 'sleep' or 'timerfd', got {other:?}"
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sleep => "sleep",
+            #[cfg(target_os = "linux")]
+            Self::TimerFd => "timerfd",
+        }
+    }
+}
+
+/// Kernel resources held by a thread's timer
+enum TimerKind {
+    Sleep,
+    #[cfg(target_os = "linux")]
+    TimerFd(TrackedFd),
+}
+
+/// A timer owned by one thread. Deadlines closer than `spin_threshold` are
+/// busy-waited on the monotonic clock rather than slept, since OS timers can't
+/// resolve them.
+struct Timer {
+    kind: TimerKind,
+    spin_threshold_ns: u64,
+}
+
+impl Timer {
+    /// Create the timer; falls back to sleeping if the kernel object can't be created
+    fn new(backend: TimerBackend, spin_threshold: Duration) -> Self {
+        let kind = match backend {
+            TimerBackend::Sleep => TimerKind::Sleep,
+            #[cfg(target_os = "linux")]
+            TimerBackend::TimerFd => {
+                let fd = unsafe { libc::timerfd_create(libc::CLOCK_MONOTONIC, libc::TFD_CLOEXEC) };
+                if fd < 0 {
+                    TimerKind::Sleep
+                } else {
+                    TimerKind::TimerFd(TrackedFd::new(
+                        unsafe { OwnedFd::from_raw_fd(fd) },
+                        "timerfd",
+                    ))
+                }
+            }
+        };
+        Self {
+            kind,
+            spin_threshold_ns: spin_threshold.as_nanos() as u64,
+        }
+    }
+
+    /// Block until `monotonic_ns()` reaches `deadline_ns`: sleep until
+    /// `spin_threshold` before it, then spin the rest
+    fn sleep_until(&self, deadline_ns: u64) {
+        let now = monotonic_ns();
+        if deadline_ns <= now {
+            return;
+        }
+        if deadline_ns - now > self.spin_threshold_ns {
+            self.block_until(deadline_ns - self.spin_threshold_ns, now);
+        }
+        while monotonic_ns() < deadline_ns {
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Block in the OS until roughly `deadline_ns`
+    fn block_until(&self, deadline_ns: u64, now: u64) {
+        match &self.kind {
+            TimerKind::Sleep => std::thread::sleep(Duration::from_nanos(deadline_ns - now)),
+            #[cfg(target_os = "linux")]
+            TimerKind::TimerFd(fd) => {
+                let spec = libc::itimerspec {
+                    it_interval: libc::timespec {
+                        tv_sec: 0,
+                        tv_nsec: 0,
+                    },
+                    it_value: libc::timespec {
+                        tv_sec: (deadline_ns / 1_000_000_000) as libc::time_t,
+                        tv_nsec: (deadline_ns % 1_000_000_000) as libc::c_long,
+                    },
+                };
+                let mut expirations = 0u64;
+                unsafe {
+                    libc::timerfd_settime(
+                        fd.as_raw_fd(),
+                        libc::TFD_TIMER_ABSTIME,
+                        &spec,
+                        std::ptr::null_mut(),
+                    );
+                    libc::read(
+                        fd.as_raw_fd(),
+                        &mut expirations as *mut u64 as *mut libc::c_void,
+                        8,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Sleep for `delay` and return how long it actually took
+    fn sleep(&self, delay: Duration) -> Duration {
+        let start = monotonic_ns();
+        self.sleep_until(start + delay.as_nanos() as u64);
+        Duration::from_nanos(monotonic_ns() - start)
+    }
+}
+
+/// Draws are capped here so a heavy-tailed distribution can't park a
+/// producer thread indefinitely
+const MAX_DRAWN_DELAY_MICROS: f64 = 3_600_000_000.0;
+
+/// Shape of a `Delay`, in microseconds
+#[derive(Clone, Copy)]
+enum DelayDistribution {
+    Constant {
+        micros: f64,
+    },
+    Uniform {
+        low_micros: f64,
+        high_micros: f64,
+    },
+    /// Negative draws are clamped to zero
+    Normal {
+        mean_micros: f64,
+        stddev_micros: f64,
+    },
+    /// `median * exp(sigma * N(0, 1))`
+    LogNormal {
+        median_micros: f64,
+        sigma: f64,
+    },
+    /// `scale * U^(-1/shape)`, optionally capped at `max_micros`
+    Pareto {
+        scale_micros: f64,
+        shape: f64,
+        max_micros: Option<f64>,
+    },
+}
+
+impl DelayDistribution {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Constant { .. } => "constant",
+            Self::Uniform { .. } => "uniform",
+            Self::Normal { .. } => "normal",
+            Self::LogNormal { .. } => "lognormal",
+            Self::Pareto { .. } => "pareto",
+        }
+    }
+
+    /// Parameters as `(name, value)` pairs, for reprs and result dicts
+    fn params(self) -> Vec<(&'static str, Option<f64>)> {
+        match self {
+            Self::Constant { micros } => vec![("micros", Some(micros))],
+            Self::Uniform {
+                low_micros,
+                high_micros,
+            } => vec![
+                ("low_micros", Some(low_micros)),
+                ("high_micros", Some(high_micros)),
+            ],
+            Self::Normal {
+                mean_micros,
+                stddev_micros,
+            } => vec![
+                ("mean_micros", Some(mean_micros)),
+                ("stddev_micros", Some(stddev_micros)),
+            ],
+            Self::LogNormal {
+                median_micros,
+                sigma,
+            } => vec![
+                ("median_micros", Some(median_micros)),
+                ("sigma", Some(sigma)),
+            ],
+            Self::Pareto {
+                scale_micros,
+                shape,
+                max_micros,
+            } => vec![
+                ("scale_micros", Some(scale_micros)),
+                ("shape", Some(shape)),
+                ("max_micros", max_micros),
+            ],
+        }
+    }
+
+    fn sample(self, rng: &mut SplitMix64) -> f64 {
+        let micros = match self {
+            Self::Constant { micros } => micros,
+            Self::Uniform {
+                low_micros,
+                high_micros,
+            } => low_micros + (high_micros - low_micros) * (1.0 - rng.next_f64()),
+            Self::Normal {
+                mean_micros,
+                stddev_micros,
+            } => mean_micros + stddev_micros * rng.next_normal(),
+            Self::LogNormal {
+                median_micros,
+                sigma,
+            } => median_micros * (sigma * rng.next_normal()).exp(),
+            Self::Pareto {
+                scale_micros,
+                shape,
+                max_micros,
+            } => {
+                let micros = scale_micros * rng.next_f64().powf(-1.0 / shape);
+                max_micros.map_or(micros, |max| micros.min(max))
+            }
+        };
+        micros.clamp(0.0, MAX_DRAWN_DELAY_MICROS)
+    }
+}
+
+/// Reject a negative or non-finite delay parameter
+fn check_delay_param(name: &str, value: f64) -> PyResult<f64> {
+    if value >= 0.0 && value.is_finite() {
+        Ok(value)
+    } else {
+        Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "{name} must be a non-negative number"
+        )))
+    }
+}
+
+/// A distribution of producer delays, seeded so the sequence of draws is
+/// reproducible. Accepted anywhere `delay_micros` is; each use draws the next
+/// sample.
+#[pyclass(frozen)]
+struct Delay {
+    distribution: DelayDistribution,
+    seed: u64,
+    rng: Mutex<SplitMix64>,
+}
+
+impl Delay {
+    fn new(distribution: DelayDistribution, seed: u64) -> Self {
+        Self {
+            distribution,
+            seed,
+            rng: Mutex::new(SplitMix64::new(seed)),
+        }
+    }
+
+    fn draw(&self) -> f64 {
+        self.distribution.sample(&mut self.rng.lock().unwrap())
+    }
+
+    /// Distribution name, parameters and seed, for result dicts
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("distribution", self.distribution.name())?;
+        for (name, value) in self.distribution.params() {
+            dict.set_item(name, value)?;
+        }
+        dict.set_item("seed", self.seed)?;
+        Ok(dict)
+    }
+}
+
+#[pymethods]
+impl Delay {
+    /// Always `micros`
+    #[staticmethod]
+    fn constant(micros: f64) -> PyResult<Self> {
+        let micros = check_delay_param("micros", micros)?;
+        Ok(Self::new(DelayDistribution::Constant { micros }, 0))
+    }
+
+    /// Uniform between `low_micros` and `high_micros`
+    #[staticmethod]
+    #[pyo3(signature = (low_micros, high_micros, seed=0))]
+    fn uniform(low_micros: f64, high_micros: f64, seed: u64) -> PyResult<Self> {
+        let low_micros = check_delay_param("low_micros", low_micros)?;
+        let high_micros = check_delay_param("high_micros", high_micros)?;
+        if high_micros < low_micros {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "high_micros must be at least low_micros",
+            ));
+        }
+        Ok(Self::new(
+            DelayDistribution::Uniform {
+                low_micros,
+                high_micros,
+            },
+            seed,
+        ))
+    }
+
+    /// Normal around `mean_micros`; draws below zero are clamped to zero
+    #[staticmethod]
+    #[pyo3(signature = (mean_micros, stddev_micros, seed=0))]
+    fn normal(mean_micros: f64, stddev_micros: f64, seed: u64) -> PyResult<Self> {
+        Ok(Self::new(
+            DelayDistribution::Normal {
+                mean_micros: check_delay_param("mean_micros", mean_micros)?,
+                stddev_micros: check_delay_param("stddev_micros", stddev_micros)?,
+            },
+            seed,
+        ))
+    }
+
+    /// Log-normal with the given median; `sigma` is the standard deviation
+    /// of the underlying normal, so larger values stretch the right tail
+    #[staticmethod]
+    #[pyo3(signature = (median_micros, sigma, seed=0))]
+    fn lognormal(median_micros: f64, sigma: f64, seed: u64) -> PyResult<Self> {
+        Ok(Self::new(
+            DelayDistribution::LogNormal {
+                median_micros: check_delay_param("median_micros", median_micros)?,
+                sigma: check_delay_param("sigma", sigma)?,
+            },
+            seed,
+        ))
+    }
+
+    /// Pareto with minimum `scale_micros` and tail index `shape`; the
+    /// smaller `shape`, the heavier the tail. Draws above `max_micros` are
+    /// clamped to it.
+    #[staticmethod]
+    #[pyo3(signature = (scale_micros, shape, max_micros=None, seed=0))]
+    fn pareto(scale_micros: f64, shape: f64, max_micros: Option<f64>, seed: u64) -> PyResult<Self> {
+        let shape = check_delay_param("shape", shape)?;
+        if shape == 0.0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "shape must be positive",
+            ));
+        }
+        Ok(Self::new(
+            DelayDistribution::Pareto {
+                scale_micros: check_delay_param("scale_micros", scale_micros)?,
+                shape,
+                max_micros: max_micros
+                    .map(|max| check_delay_param("max_micros", max))
+                    .transpose()?,
+            },
+            seed,
+        ))
+    }
+
+    #[getter]
+    fn distribution(&self) -> &'static str {
+        self.distribution.name()
+    }
+
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Draw the next delay, in microseconds. Draws here advance the same
+    /// sequence producers draw from.
+    fn sample_micros(&self) -> f64 {
+        self.draw()
+    }
+
+    /// Restart the sequence of draws from the seed
+    fn reset(&self) {
+        *self.rng.lock().unwrap() = SplitMix64::new(self.seed);
+    }
+
+    fn __repr__(&self) -> String {
+        let params: Vec<String> = self
+            .distribution
+            .params()
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| format!("{name}={value}")))
+            .collect();
+        format!(
+            "Delay.{}({}, seed={})",
+            self.distribution.name(),
+            params.join(", "),
+            self.seed
+        )
+    }
+}
+
+/// A `delay_micros` argument: a fixed number of microseconds or a `Delay`
+enum DelaySource {
+    Fixed(u64),
+    Drawn(Py<Delay>),
+}
+
+impl DelaySource {
+    fn extract(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(delay) = obj.downcast::<Delay>() {
+            return Ok(Self::Drawn(delay.clone().unbind()));
+        }
+        obj.extract::<u64>().map(Self::Fixed).map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "delay_micros must be a non-negative int or a Delay",
+            )
+        })
+    }
+
+    /// The delay for one wake
+    fn draw(&self) -> DrawnDelay {
+        match self {
+            Self::Fixed(micros) => DrawnDelay {
+                duration: Duration::from_micros(*micros),
+                distribution: None,
+            },
+            Self::Drawn(delay) => {
+                let delay = delay.get();
+                DrawnDelay {
+                    duration: Duration::from_secs_f64(delay.draw() / 1e6),
+                    distribution: Some(delay.distribution.name()),
+                }
+            }
+        }
+    }
+
+    /// The micros as given, or the `Delay`'s `to_dict()`
+    fn describe(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match self {
+            Self::Fixed(micros) => Ok(micros.into_pyobject(py)?.into_any().unbind()),
+            Self::Drawn(delay) => Ok(delay.get().to_dict(py)?.into_any().unbind()),
+        }
+    }
+}
+
+impl std::fmt::Display for DelaySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed(micros) => write!(f, "{micros}"),
+            Self::Drawn(delay) => f.write_str(&delay.get().__repr__()),
+        }
+    }
+}
+
+/// One producer's delay, drawn from its `DelaySource`
+#[derive(Clone, Copy)]
+struct DrawnDelay {
+    duration: Duration,
+    /// The `Delay` distribution it came from, if not a fixed delay
+    distribution: Option<&'static str>,
+}
+
+/// Draw a single delay from a `delay_micros` argument
+fn draw_delay(obj: &Bound<'_, PyAny>) -> PyResult<DrawnDelay> {
+    Ok(DelaySource::extract(obj)?.draw())
+}
+
+// =============================================================================
+// Awaitable wakes: futures completed by the next wake
+// =============================================================================
+
+/// Futures handed out by a waker's `wait()`, all completed by the next wake
+#[derive(Default)]
+struct Waiters {
+    futures: Mutex<Vec<PyObject>>,
+}
+
+impl Waiters {
+    /// A new future on `event_loop` for the next wake to complete
+    fn wait<'py>(&self, event_loop: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let future = event_loop.call_method0("create_future")?;
+        self.futures.lock().unwrap().push(future.clone().unbind());
+        Ok(future)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.futures.lock().unwrap().is_empty()
+    }
+
+    /// Resolve every outstanding future, skipping ones already cancelled
+    fn complete_all(&self, py: Python<'_>) -> PyResult<()> {
+        let futures = std::mem::take(&mut *self.futures.lock().unwrap());
+        for future in futures {
+            if !future.call_method0(py, "done")?.is_truthy(py)? {
+                future.call_method1(py, "set_result", (py.None(),))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancel every outstanding future, for wakes that will never come
+    #[cfg(unix)]
+    fn cancel_all(&self, py: Python<'_>) {
+        for future in std::mem::take(&mut *self.futures.lock().unwrap()) {
+            let _ = future.call_method0(py, "cancel");
+        }
+    }
+
+    /// Forget outstanding futures whose event loop has been closed
+    fn clear(&self) {
+        self.futures.lock().unwrap().clear();
+    }
+}
+
+// =============================================================================
+// Fork safety: detecting wakers inherited across fork()
+// =============================================================================
+
+/// Bumped in the child after every fork(). Wakers record the generation they
+/// were created in, so a child can tell it holds the parent's pipe or loop.
+static FORK_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(unix)]
+extern "C" fn bump_fork_generation() {
+    FORK_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Install the atfork child handler (once per process)
+#[cfg(unix)]
+fn install_fork_handler() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| unsafe {
+        libc::pthread_atfork(None, None, Some(bump_fork_generation));
+    });
+}
+
+fn fork_generation() -> u64 {
+    FORK_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Error raised when using a waker created before fork() in the child
+fn forked_error(what: &str) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+        "{what} was created before fork(); call reinit_after_fork() in the child"
+    ))
+}
+
+// =============================================================================
+// FD accounting: which descriptors the module holds open
+// =============================================================================
+
+/// Every FD currently held open by a waker or timer, with what it is
+#[cfg(unix)]
+static OPEN_FDS: Mutex<std::collections::BTreeMap<RawFd, &'static str>> =
+    Mutex::new(std::collections::BTreeMap::new());
+
+/// An FD-owning value recorded in OPEN_FDS until it is dropped
+#[cfg(unix)]
+struct TrackedFd<T: AsRawFd = OwnedFd> {
+    inner: T,
+}
+
+#[cfg(unix)]
+impl<T: AsRawFd> TrackedFd<T> {
+    fn new(inner: T, kind: &'static str) -> Self {
+        OPEN_FDS.lock().unwrap().insert(inner.as_raw_fd(), kind);
+        Self { inner }
+    }
+}
+
+#[cfg(unix)]
+impl<T: AsRawFd> std::ops::Deref for TrackedFd<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[cfg(unix)]
+impl<T: AsRawFd> AsRawFd for TrackedFd<T> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl<T: AsRawFd> Drop for TrackedFd<T> {
+    // Runs before `inner` closes the FD, so its number can't be reused first
+    fn drop(&mut self) {
+        OPEN_FDS.lock().unwrap().remove(&self.inner.as_raw_fd());
+    }
+}
+
+// Sockets are read and written through shared references, like the streams they wrap
+#[cfg(unix)]
+impl<T: AsRawFd> Read for &TrackedFd<T>
+where
+    for<'a> &'a T: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        (&self.inner).read(buf)
+    }
+}
+
+#[cfg(unix)]
+impl<T: AsRawFd> Write for &TrackedFd<T>
+where
+    for<'a> &'a T: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        (&self.inner).write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        (&self.inner).flush()
+    }
+}
+
+/// Whether FD_CLOEXEC is set on `fd`, or None if it isn't open
+#[cfg(unix)]
+fn fd_cloexec(fd: RawFd) -> Option<bool> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    (flags >= 0).then_some(flags & libc::FD_CLOEXEC != 0)
+}
+
+/// Number of FDs this module currently holds open: waker pipes, eventfds,
+/// kqueues, socket pairs, cross-process sockets, and timerfds. With
+/// `by_kind=True`, a dict of counts per kind instead. FDs stay open until the
+/// waker is closed and every producer thread using it has exited.
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (by_kind=false))]
+fn active_fd_count(py: Python<'_>, by_kind: bool) -> PyResult<PyObject> {
+    let fds = OPEN_FDS.lock().unwrap();
+    if !by_kind {
+        return Ok(fds.len().into_pyobject(py)?.into_any().unbind());
+    }
+    let counts = pyo3::types::PyDict::new(py);
+    for kind in fds.values() {
+        let count = counts
+            .get_item(kind)?
+            .map_or(Ok(0), |count| count.extract::<usize>())?;
+        counts.set_item(kind, count + 1)?;
+    }
+    Ok(counts.into_any().unbind())
+}
+
+// =============================================================================
+// Approach 1: FD-based wakeup (no GIL acquisition on Rust side)
+// =============================================================================
+
+/// The two ends of an FdWaker's pipe. Shared between the waker and its handles
+/// so the FDs stay open (and their numbers can't be reused) while any producer
+/// thread still holds a handle, even after the waker has been closed.
+#[cfg(unix)]
+struct Pipe {
+    read: PipeEnd,
+    write: PipeEnd,
+    closed: AtomicBool,
+    // Fork generation the pipe was created in
+    generation: u64,
+    // Written once per wake
+    token: Box<[u8]>,
+    // Supplied by the caller rather than created by pipe()
+    external: bool,
+}
+
+/// One end of an FdWaker's pipe: either owned (closed with the pipe) or a
+/// caller's FD that the waker only borrows
+#[cfg(unix)]
+enum PipeEnd {
+    Owned(TrackedFd),
+    Borrowed(RawFd),
+}
+
+#[cfg(unix)]
+impl PipeEnd {
+    fn is_owned(&self) -> bool {
+        matches!(self, Self::Owned(_))
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for PipeEnd {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            Self::Owned(fd) => fd.as_raw_fd(),
+            Self::Borrowed(fd) => *fd,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Pipe {
+    /// A new pipe with both ends non-blocking: a full pipe must not stall
+    /// producers. Both ends are close-on-exec, except the write end when
+    /// `inheritable` is set.
+    fn new(inheritable: bool) -> PyResult<Self> {
+        let mut fds = [0 as RawFd; 2];
+        // pipe2 sets CLOEXEC atomically, so a concurrent fork+exec can't leak the FDs
+        #[cfg(target_os = "linux")]
+        let result = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) };
+        #[cfg(not(target_os = "linux"))]
+        let result = unsafe { libc::pipe(fds.as_mut_ptr()) };
+        if result != 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyOSError, _>(
+                "Failed to create pipe",
+            ));
+        }
+
+        for fd in fds {
+            unsafe {
+                let flags = libc::fcntl(fd, libc::F_GETFL);
+                libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK);
+                #[cfg(not(target_os = "linux"))]
+                libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+            }
+        }
+        if inheritable {
+            unsafe { libc::fcntl(fds[1], libc::F_SETFD, 0) };
+        }
+
+        Ok(Self {
+            read: PipeEnd::Owned(TrackedFd::new(
+                unsafe { OwnedFd::from_raw_fd(fds[0]) },
+                "pipe",
+            )),
+            write: PipeEnd::Owned(TrackedFd::new(
+                unsafe { OwnedFd::from_raw_fd(fds[1]) },
+                "pipe",
+            )),
+            closed: AtomicBool::new(false),
+            generation: fork_generation(),
+            token: Box::new([1]),
+            external: false,
+        })
+    }
+
+    /// A pipe over caller-supplied FDs (`read` may equal `write`, as for an
+    /// eventfd), made non-blocking. With `owned`, they're closed with the pipe.
+    fn from_fds(read: RawFd, write: RawFd, owned: bool, token: &[u8]) -> PyResult<Self> {
+        if token.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "token must not be empty",
+            ));
+        }
+        for fd in [read, write] {
+            let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+            if flags < 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) };
+        }
+        let end = |fd: RawFd, owned: bool| {
+            if owned {
+                PipeEnd::Owned(TrackedFd::new(
+                    unsafe { OwnedFd::from_raw_fd(fd) },
+                    "external",
+                ))
+            } else {
+                PipeEnd::Borrowed(fd)
+            }
+        };
+        Ok(Self {
+            read: end(read, owned),
+            // A single FD used for both ends is closed only once
+            write: end(write, owned && write != read),
+            closed: AtomicBool::new(false),
+            generation: fork_generation(),
+            token: token.into(),
+            external: true,
+        })
+    }
+
+    /// True in a child process that inherited this pipe from its parent
+    fn is_stale(&self) -> bool {
+        self.generation != fork_generation()
+    }
+
+    #[cfg(target_os = "linux")]
+    fn set_capacity(&self, bytes: usize) -> std::io::Result<usize> {
+        let bytes = libc::c_int::try_from(bytes)
+            .map_err(|_| std::io::Error::from_raw_os_error(libc::EINVAL))?;
+        let result = unsafe { libc::fcntl(self.write.as_raw_fd(), libc::F_SETPIPE_SZ, bytes) };
+        if result < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(result as usize)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn capacity(&self) -> std::io::Result<usize> {
+        let result = unsafe { libc::fcntl(self.write.as_raw_fd(), libc::F_GETPIPE_SZ) };
+        if result < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(result as usize)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn set_capacity(&self, _bytes: usize) -> std::io::Result<usize> {
+        Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn capacity(&self) -> std::io::Result<usize> {
+        Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+    }
+
+    /// Read until the pipe is empty; returns the number of bytes read
+    fn drain(&self) -> usize {
+        self.drain_with(DrainPolicy::default()).bytes
+    }
+
+    /// Read until the pipe is empty or `policy`'s limit is reached, in reads
+    /// of up to `policy.buffer_size` bytes
+    fn drain_with(&self, policy: DrainPolicy) -> DrainOutcome {
+        trace_scope!("fd.drain");
+        let read_fd = self.read.as_raw_fd();
+        let mut stack_buf = [0u8; DRAIN_STACK_BYTES];
+        let mut heap_buf = Vec::new();
+        let buf = if policy.buffer_size <= DRAIN_STACK_BYTES {
+            &mut stack_buf[..policy.buffer_size]
+        } else {
+            heap_buf.resize(policy.buffer_size, 0);
+            &mut heap_buf[..]
+        };
+        let limit = policy
+            .max_tokens
+            .map_or(usize::MAX, |tokens| tokens.saturating_mul(self.token.len()));
+        let mut outcome = DrainOutcome::default();
+        while outcome.bytes < limit {
+            let want = buf.len().min(limit - outcome.bytes);
+            outcome.reads += 1;
+            let result =
+                unsafe { libc::read(read_fd, buf.as_mut_ptr() as *mut libc::c_void, want) };
+            if result <= 0 {
+                return outcome;
+            }
+            outcome.bytes += result as usize;
+        }
+        outcome.capped = true;
+        outcome
+    }
+
+    /// Bytes waiting in the pipe (FIONREAD)
+    fn pending(&self) -> std::io::Result<usize> {
+        let mut pending: libc::c_int = 0;
+        let result = unsafe { libc::ioctl(self.read.as_raw_fd(), libc::FIONREAD, &mut pending) };
+        if result < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(pending as usize)
+    }
+}
+
+/// Error raised when using a waker after close()
+#[cfg(unix)]
+fn closed_error(what: &str) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{what} is closed"))
+}
+
+/// Outcomes of pipe writes, shared by an FdWaker and its handles
+#[cfg(unix)]
+#[derive(Default)]
+struct PipeWriteStats {
+    writes: AtomicU64,
+    /// Wakes discarded because the pipe stayed full (EAGAIN)
+    dropped: AtomicU64,
+    /// Writes retried under `FullPolicy.retry()`
+    retries: AtomicU64,
+    short_writes: AtomicU64,
+    /// Times a writer waited for the pipe to become writable
+    blocked: AtomicU64,
+    blocked_ns: AtomicU64,
+    errors: AtomicU64,
+    /// Bytes accepted by successful writes
+    bytes: AtomicU64,
+}
+
+/// Largest drain buffer kept on the stack; bigger ones are allocated per drain
+#[cfg(unix)]
+const DRAIN_STACK_BYTES: usize = 4096;
+
+/// Default read size for draining a pipe
+#[cfg(unix)]
+const DEFAULT_DRAIN_BUFFER_SIZE: usize = 64;
+
+/// How an FdWaker's drains read the pipe: `buffer_size` bytes per read(),
+/// stopping after `max_tokens` tokens (None = until empty). Whatever is left
+/// keeps the FD readable, so the loop calls the reader again.
+#[cfg(unix)]
+#[derive(Clone, Copy)]
+struct DrainPolicy {
+    buffer_size: usize,
+    max_tokens: Option<usize>,
+}
+
+#[cfg(unix)]
+impl Default for DrainPolicy {
+    fn default() -> Self {
+        Self {
+            buffer_size: DEFAULT_DRAIN_BUFFER_SIZE,
+            max_tokens: None,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl DrainPolicy {
+    fn new(max_drain_per_callback: Option<usize>, drain_buffer_size: usize) -> PyResult<Self> {
+        if drain_buffer_size == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "drain_buffer_size must be at least 1",
+            ));
+        }
+        if max_drain_per_callback == Some(0) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "max_drain_per_callback must be at least 1",
+            ));
+        }
+        Ok(Self {
+            buffer_size: drain_buffer_size,
+            max_tokens: max_drain_per_callback,
+        })
+    }
+}
+
+/// What one drain read from a pipe
+#[cfg(unix)]
+#[derive(Default)]
+struct DrainOutcome {
+    bytes: usize,
+    /// read() calls, including the one that found the pipe empty
+    reads: usize,
+    /// Stopped at the policy's limit rather than an empty pipe
+    capped: bool,
+}
+
+/// Outcomes of an FdWaker's drains, whether by `drain()`, `register()` or `wait()`
+#[cfg(unix)]
+#[derive(Default)]
+struct PipeDrainStats {
+    drains: AtomicU64,
+    /// Drains that found the pipe already empty
+    empty: AtomicU64,
+    tokens: AtomicU64,
+    bytes: AtomicU64,
+    max_coalesced: AtomicU64,
+    reads: AtomicU64,
+    /// Drains stopped by `max_drain_per_callback` with wakes still pending
+    capped: AtomicU64,
+}
+
+#[cfg(unix)]
+impl PipeDrainStats {
+    /// Drain `pipe` under `policy` and record the outcome; returns the tokens consumed
+    fn drain(&self, pipe: &Pipe, policy: DrainPolicy) -> usize {
+        let outcome = pipe.drain_with(policy);
+        let bytes = outcome.bytes;
+        let tokens = bytes / pipe.token.len();
+        self.reads
+            .fetch_add(outcome.reads as u64, Ordering::Relaxed);
+        if outcome.capped {
+            self.capped.fetch_add(1, Ordering::Relaxed);
+        }
+        self.drains.fetch_add(1, Ordering::Relaxed);
+        if bytes == 0 {
+            self.empty.fetch_add(1, Ordering::Relaxed);
+        }
+        self.tokens.fetch_add(tokens as u64, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.max_coalesced
+            .fetch_max(tokens as u64, Ordering::Relaxed);
+        tokens
+    }
+}
+
+/// What a pipe write does when the pipe is full
+#[cfg(unix)]
+#[derive(Clone, Copy)]
+enum FullPolicyKind {
+    /// Drop the wake and count it
+    Drop,
+    /// Retry up to `attempts` times, sleeping `backoff` and doubling it
+    /// after each attempt, then drop
+    Retry { attempts: u32, backoff: Duration },
+    /// Wait with poll() for the pipe to become writable, up to `timeout`
+    /// (forever when None), then drop
+    Block { timeout: Option<Duration> },
+}
+
+#[cfg(unix)]
+impl FullPolicyKind {
+    /// Combine `FdWaker(on_full=..., block_timeout_ms=...)`, where
+    /// `block_timeout_ms` alone is shorthand for `FullPolicy.block()`
+    fn resolve(on_full: Option<&FullPolicy>, block_timeout_ms: Option<u64>) -> PyResult<Self> {
+        match (on_full, block_timeout_ms) {
+            (Some(_), Some(_)) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "pass either on_full or block_timeout_ms, not both",
+            )),
+            (Some(policy), None) => Ok(policy.kind),
+            (None, Some(ms)) => Ok(Self::Block {
+                timeout: Some(Duration::from_millis(ms)),
+            }),
+            (None, None) => Ok(Self::Drop),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Drop => "drop",
+            Self::Retry { .. } => "retry",
+            Self::Block { .. } => "block",
+        }
+    }
+
+    /// The policy and its parameters, for a benchmark result's config
+    fn config(self) -> Vec<(&'static str, ConfigValue)> {
+        let mut config = vec![("on_full", ConfigValue::Str(self.name()))];
+        match self {
+            Self::Drop => {}
+            Self::Retry { attempts, backoff } => {
+                config.push(("retry_attempts", ConfigValue::Int(attempts as i64)));
+                config.push((
+                    "retry_backoff_us",
+                    ConfigValue::Int(backoff.as_micros() as i64),
+                ));
+            }
+            Self::Block { timeout } => config.push((
+                "block_timeout_ms",
+                timeout.map_or(ConfigValue::None, |timeout| {
+                    ConfigValue::Int(timeout.as_millis() as i64)
+                }),
+            )),
+        }
+        config
+    }
+}
+
+/// What an FdWaker's writers do when the pipe is full, built with one of the
+/// static constructors `drop`, `retry` or `block`. Pass it as
+/// `FdWaker(on_full=...)`.
+#[cfg(unix)]
+#[pyclass(frozen)]
+struct FullPolicy {
+    kind: FullPolicyKind,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl FullPolicy {
+    /// Drop the wake and count it in `dropped` (the reader is already woken)
+    #[staticmethod]
+    fn drop() -> Self {
+        Self {
+            kind: FullPolicyKind::Drop,
+        }
+    }
+
+    /// Retry up to `attempts` times, sleeping `backoff_us` before the first
+    /// retry and doubling the sleep each time; drop if the pipe is still full
+    #[staticmethod]
+    #[pyo3(signature = (attempts=8, backoff_us=10))]
+    fn retry(attempts: u32, backoff_us: u64) -> Self {
+        Self {
+            kind: FullPolicyKind::Retry {
+                attempts,
+                backoff: Duration::from_micros(backoff_us),
+            },
+        }
+    }
+
+    /// Wait with poll() for the pipe to become writable, for up to
+    /// `timeout_ms` (None waits until it is, or the waker is closed)
+    #[staticmethod]
+    #[pyo3(signature = (timeout_ms=None))]
+    fn block(timeout_ms: Option<u64>) -> Self {
+        Self {
+            kind: FullPolicyKind::Block {
+                timeout: timeout_ms.map(Duration::from_millis),
+            },
+        }
+    }
+
+    /// "drop", "retry" or "block"
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    fn __repr__(&self) -> String {
+        match self.kind {
+            FullPolicyKind::Drop => "FullPolicy.drop()".to_string(),
+            FullPolicyKind::Retry { attempts, backoff } => format!(
+                "FullPolicy.retry(attempts={attempts}, backoff_us={})",
+                backoff.as_micros()
+            ),
+            FullPolicyKind::Block { timeout: None } => "FullPolicy.block()".to_string(),
+            FullPolicyKind::Block {
+                timeout: Some(timeout),
+            } => format!("FullPolicy.block(timeout_ms={})", timeout.as_millis()),
+        }
+    }
+}
+
+/// A waker that uses a raw file descriptor to wake up the Python event loop.
+/// This avoids acquiring the GIL on the Rust side.
+///
+/// Both pipe ends are non-blocking. What a writer does when the pipe is full
+/// is set by `on_full`, a FullPolicy: by default the wake is dropped and
+/// counted (the reader is already woken). `block_timeout_ms=N` is shorthand
+/// for `on_full=FullPolicy.block(N)`.
+///
+/// Both FDs are close-on-exec. With `inheritable=True` the write end is left
+/// open across exec, so a subprocess can be handed it to wake this process.
+#[cfg(unix)]
+#[pyclass]
+struct FdWaker {
+    // None once close() has been called
+    pipe: std::sync::RwLock<Option<Arc<Pipe>>>,
+    stamps: Arc<WakeTimestamps>,
+    write_stats: Arc<PipeWriteStats>,
+    drain_stats: Arc<PipeDrainStats>,
+    drain_policy: DrainPolicy,
+    on_full: FullPolicyKind,
+    waiters: Arc<Waiters>,
+    // Loop the read FD is registered with for wait(), if any
+    wait_loop: Mutex<Option<PyObject>>,
+    // Capacity requested with set_pipe_capacity(), reapplied after fork; 0 = default
+    requested_capacity: std::sync::atomic::AtomicUsize,
+    // Loops of register() results; each is None once closed or replaced by wait()
+    readers: Mutex<Vec<RegisteredLoop>>,
+    // The pipe released by close(), to tell whether handles still hold it open
+    closed_pipe: Mutex<Weak<Pipe>>,
+    // Whether the write end survives exec, for pipes created by reinit_after_fork()
+    inheritable: bool,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl FdWaker {
+    /// `drain_buffer_size` is the bytes each read() takes when draining, and
+    /// `max_drain_per_callback` caps the tokens one drain consumes (None
+    /// drains until empty); both apply to `drain()`, `register()` and `wait()`.
+    #[new]
+    #[pyo3(signature = (block_timeout_ms=None, inheritable=false, max_drain_per_callback=None, drain_buffer_size=DEFAULT_DRAIN_BUFFER_SIZE, on_full=None))]
+    fn new(
+        block_timeout_ms: Option<u64>,
+        inheritable: bool,
+        max_drain_per_callback: Option<usize>,
+        drain_buffer_size: usize,
+        on_full: Option<&FullPolicy>,
+    ) -> PyResult<Self> {
+        let drain_policy = DrainPolicy::new(max_drain_per_callback, drain_buffer_size)?;
+        let on_full = FullPolicyKind::resolve(on_full, block_timeout_ms)?;
+        Ok(Self::with_pipe(
+            Pipe::new(inheritable)?,
+            on_full,
+            inheritable,
+            drain_policy,
+        ))
+    }
+
+    /// Wrap existing FDs instead of creating a pipe, to benchmark waking
+    /// infrastructure that's already there: an eventfd, a loop's self-pipe, an
+    /// FD received over SCM_RIGHTS. `write_fd` defaults to `read_fd`. Each
+    /// wake writes `token` (an eventfd needs 8 bytes, e.g.
+    /// `(1).to_bytes(8, "little")`). Both FDs are switched to non-blocking.
+    /// With `owned=True` the waker closes them like its own pipe; otherwise
+    /// they stay open and the caller must keep them open while it's in use.
+    /// The drain and `on_full` options are as for `FdWaker()`.
+    #[staticmethod]
+    #[pyo3(signature = (read_fd, write_fd=None, owned=false, token=b"\x01".as_slice(), block_timeout_ms=None, max_drain_per_callback=None, drain_buffer_size=DEFAULT_DRAIN_BUFFER_SIZE, on_full=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_fds(
+        read_fd: RawFd,
+        write_fd: Option<RawFd>,
+        owned: bool,
+        token: &[u8],
+        block_timeout_ms: Option<u64>,
+        max_drain_per_callback: Option<usize>,
+        drain_buffer_size: usize,
+        on_full: Option<&FullPolicy>,
+    ) -> PyResult<Self> {
+        let drain_policy = DrainPolicy::new(max_drain_per_callback, drain_buffer_size)?;
+        let on_full = FullPolicyKind::resolve(on_full, block_timeout_ms)?;
+        let pipe = Pipe::from_fds(read_fd, write_fd.unwrap_or(read_fd), owned, token)?;
+        Ok(Self::with_pipe(pipe, on_full, false, drain_policy))
+    }
+
+    /// The FullPolicy writers follow when the pipe is full
+    #[getter]
+    fn on_full(&self) -> FullPolicy {
+        FullPolicy { kind: self.on_full }
+    }
+
+    /// Most tokens one drain consumes, or None for no limit
+    #[getter]
+    fn max_drain_per_callback(&self) -> Option<usize> {
+        self.drain_policy.max_tokens
+    }
+
+    /// Bytes requested by each read() while draining
+    #[getter]
+    fn drain_buffer_size(&self) -> usize {
+        self.drain_policy.buffer_size
+    }
+
+    /// Resize the pipe's kernel buffer with F_SETPIPE_SZ (Linux only). The
+    /// kernel rounds `bytes` up to a power-of-two number of pages; returns the
+    /// capacity actually set. Unprivileged processes are limited to
+    /// /proc/sys/fs/pipe-max-size, and shrinking below the bytes currently
+    /// queued fails.
+    fn set_pipe_capacity(&self, bytes: usize) -> PyResult<usize> {
+        let capacity = self.pipe()?.set_capacity(bytes)?;
+        self.requested_capacity.store(bytes, Ordering::Relaxed);
+        Ok(capacity)
+    }
+
+    /// Current capacity of the pipe's kernel buffer in bytes (Linux only)
+    fn pipe_capacity(&self) -> PyResult<usize> {
+        Ok(self.pipe()?.capacity()?)
+    }
+
+    /// Bytes written but not yet drained. At the end of a run, 0 means no
+    /// wakes were left behind.
+    fn pending_bytes(&self) -> PyResult<usize> {
+        Ok(self.pipe()?.pending()?)
+    }
+
+    /// Pipe write outcomes: `writes`, `dropped` (pipe full, or the unwritten
+    /// tail of a short write), `short_writes`, `retries` (under
+    /// `FullPolicy.retry()`), `blocked`/`blocked_ns` (waits for space under
+    /// `FullPolicy.block()`), `errors`, `bytes_written`. Drain outcomes: `drains`, `empty` (drains
+    /// that found nothing), `drained_tokens`, `drained_bytes`,
+    /// `max_coalesced` (most tokens taken by one drain), `drain_reads`
+    /// (read() calls) and `capped` (drains stopped by `max_drain_per_callback`).
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        let counters = [
+            ("writes", &self.write_stats.writes),
+            ("dropped", &self.write_stats.dropped),
+            ("short_writes", &self.write_stats.short_writes),
+            ("retries", &self.write_stats.retries),
+            ("blocked", &self.write_stats.blocked),
+            ("blocked_ns", &self.write_stats.blocked_ns),
+            ("errors", &self.write_stats.errors),
+            ("bytes_written", &self.write_stats.bytes),
+            ("drains", &self.drain_stats.drains),
+            ("empty", &self.drain_stats.empty),
+            ("drained_tokens", &self.drain_stats.tokens),
+            ("drained_bytes", &self.drain_stats.bytes),
+            ("max_coalesced", &self.drain_stats.max_coalesced),
+            ("drain_reads", &self.drain_stats.reads),
+            ("capped", &self.drain_stats.capped),
+        ];
+        for (name, counter) in counters {
+            stats.set_item(name, counter.load(Ordering::Relaxed))?;
+        }
+        Ok(stats)
+    }
+
+    /// The state of the pipe's FDs, for tracking down leaks: `closed`,
+    /// `held_open` (closed, but producer threads or callbacks still keep the
+    /// FDs open), `read_fd`/`write_fd` and `read_cloexec`/`write_cloexec`
+    /// (None once closed), `forked` (inherited from a parent process),
+    /// `external` (built with `from_fds()`), `owned` (closed by the waker),
+    /// `readers` (results of `register()` not yet closed), `wait_registered`,
+    /// and `registered` (either of those). Readers added by hand with
+    /// `add_reader` aren't seen.
+    fn debug_info<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let pipe = self.pipe.read().unwrap().clone();
+        let readers = self.active_readers();
+        let wait_registered = self.wait_loop.lock().unwrap().is_some();
+        let fds = pipe
+            .as_ref()
+            .map(|pipe| (pipe.read.as_raw_fd(), pipe.write.as_raw_fd()));
+        let info = pyo3::types::PyDict::new(py);
+        info.set_item("closed", pipe.is_none())?;
+        info.set_item(
+            "held_open",
+            pipe.is_none() && self.closed_pipe.lock().unwrap().strong_count() > 0,
+        )?;
+        info.set_item("read_fd", fds.map(|(read, _)| read))?;
+        info.set_item("write_fd", fds.map(|(_, write)| write))?;
+        info.set_item("read_cloexec", fds.and_then(|(read, _)| fd_cloexec(read)))?;
+        info.set_item(
+            "write_cloexec",
+            fds.and_then(|(_, write)| fd_cloexec(write)),
+        )?;
+        info.set_item("forked", pipe.as_ref().is_some_and(|pipe| pipe.is_stale()))?;
+        info.set_item("external", pipe.as_ref().is_some_and(|pipe| pipe.external))?;
+        info.set_item(
+            "owned",
+            pipe.as_ref()
+                .is_some_and(|pipe| pipe.read.is_owned() || pipe.write.is_owned()),
+        )?;
+        info.set_item("readers", readers)?;
+        info.set_item("wait_registered", wait_registered)?;
+        info.set_item("registered", readers > 0 || wait_registered)?;
+        Ok(info)
+    }
+
+    /// Get the read file descriptor for registering with the event loop
+    fn get_read_fd(&self) -> PyResult<RawFd> {
+        Ok(self.pipe()?.read.as_raw_fd())
+    }
+
+    /// Get the write file descriptor, e.g. to pass to a subprocess created
+    /// with `inheritable=True`. Writing `token` to it is a wake.
+    fn get_write_fd(&self) -> PyResult<RawFd> {
+        Ok(self.pipe()?.write.as_raw_fd())
+    }
+
+    /// The bytes each wake writes: `b"\x01"` unless set by `from_fds()`
+    #[getter]
+    fn token<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyBytes>> {
+        Ok(pyo3::types::PyBytes::new(py, &self.pipe()?.token))
+    }
+
+    /// Send one wake from the calling Python thread through the same write
+    /// path as Rust producers: `stats()`, wake timestamps and
+    /// `block_timeout_ms` all apply, and a failed write is counted rather than
+    /// raised. The GIL is released for the write, as `os.write()` does.
+    fn wake_from_python(&self, py: Python<'_>) -> PyResult<()> {
+        let handle = self.handle()?;
+        py.allow_threads(|| handle.wake());
+        Ok(())
+    }
+
+    /// Send `n` events from the calling thread in as few writes as possible:
+    /// `n` copies of `token`, up to PIPE_BUF bytes per write. `drain()`
+    /// counts them all. A full pipe drops a whole write, as with single wakes.
+    fn wake_many(&self, py: Python<'_>, n: u64) -> PyResult<()> {
+        let handle = self.handle()?;
+        py.allow_threads(|| handle.wake_many(n));
+        Ok(())
+    }
+
+    /// Drain any pending bytes from the pipe (call this in the callback)
+    /// Returns the number of tokens consumed (bytes read per `token` length,
+    /// which counts every `wake_many()` event); `stats()` sums them.
+    fn drain(&self) -> PyResult<usize> {
+        let pipe = self.pipe()?;
+        Ok(self.drain_stats.drain(&pipe, self.drain_policy))
+    }
+
+    /// Register the read FD with `event_loop.add_reader()`. Each time it
+    /// becomes readable the pipe is drained and then `callback()` is called.
+    /// Returns a ReaderRegistration; closing it removes the reader.
+    fn register(
+        &self,
+        event_loop: &Bound<'_, PyAny>,
+        callback: PyObject,
+    ) -> PyResult<ReaderRegistration> {
+        let pipe = self.pipe()?;
+        let fd = pipe.read.as_raw_fd();
+        let (drain_stats, drain_policy) = (self.drain_stats.clone(), self.drain_policy);
+        let on_readable = pyo3::types::PyCFunction::new_closure(
+            event_loop.py(),
+            None,
+            None,
+            move |args, _kwargs| -> PyResult<PyObject> {
+                drain_stats.drain(&pipe, drain_policy);
+                callback.call0(args.py())
+            },
+        )?;
+        event_loop.call_method1("add_reader", (fd, on_readable))?;
+        let registered = Arc::new(Mutex::new(Some(event_loop.clone().unbind())));
+        let mut readers = self.readers.lock().unwrap();
+        readers.retain(|reader| reader.lock().unwrap().is_some());
+        readers.push(registered.clone());
+        Ok(ReaderRegistration {
+            event_loop: registered,
+            fds: vec![fd],
+        })
+    }
+
+    /// Awaitable that completes the next time the pipe becomes readable
+    /// (wakes already pending count), draining it. The first call registers
+    /// the read FD with the running loop's `add_reader`, replacing any reader
+    /// installed by `register()` or by hand; it stays registered until `close()`.
+    /// `register()` results on that loop become inactive, and closing them
+    /// leaves this reader in place.
+    fn wait<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let pipe = self.pipe()?;
+        let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+        let mut wait_loop = self.wait_loop.lock().unwrap();
+        let registered = wait_loop
+            .as_ref()
+            .is_some_and(|registered| registered.bind(py).is(&event_loop));
+        if !registered {
+            if let Some(previous) = wait_loop.as_ref() {
+                if !previous.call_method0(py, "is_closed")?.is_truthy(py)? {
+                    return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                        "FdWaker.wait() is already in use by another event loop",
+                    ));
+                }
+                self.waiters.clear();
+            }
+            let fd = pipe.read.as_raw_fd();
+            let waiters = self.waiters.clone();
+            let (drain_stats, drain_policy) = (self.drain_stats.clone(), self.drain_policy);
+            let on_readable = pyo3::types::PyCFunction::new_closure(
+                py,
+                None,
+                None,
+                move |args, _kwargs| -> PyResult<()> {
+                    drain_stats.drain(&pipe, drain_policy);
+                    waiters.complete_all(args.py())
+                },
+            )?;
+            event_loop.call_method1("add_reader", (fd, on_readable))?;
+            for reader in self.readers.lock().unwrap().iter() {
+                let mut reader = reader.lock().unwrap();
+                if reader.as_ref().is_some_and(|r| r.bind(py).is(&event_loop)) {
+                    *reader = None;
+                }
+            }
+            *wait_loop = Some(event_loop.clone().unbind());
+        }
+        self.waiters.wait(&event_loop)
+    }
+
+    /// Release the pipe. Wakes already in flight on producer threads are
+    /// discarded; starting new ones raises ValueError. Pending `wait()`
+    /// futures are cancelled. Idempotent.
+    fn close(&self, py: Python<'_>) {
+        let pipe = self.pipe.write().unwrap().take();
+        if let Some(event_loop) = self.wait_loop.lock().unwrap().take() {
+            if let Some(pipe) = &pipe {
+                let _ = event_loop.call_method1(py, "remove_reader", (pipe.read.as_raw_fd(),));
+            }
+            self.waiters.cancel_all(py);
+        }
+        if let Some(pipe) = pipe {
+            pipe.closed.store(true, Ordering::SeqCst);
+            *self.closed_pipe.lock().unwrap() = Arc::downgrade(&pipe);
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.pipe.read().unwrap().is_none()
+    }
+
+    /// In a child after fork(), replace the pipe inherited from the parent
+    /// with a fresh one (with the same `set_pipe_capacity()`, if any) and
+    /// forget the parent's `wait()` registration. Readers
+    /// added with `register()` or by hand must be added again with the new
+    /// read FD. Returns False (doing nothing) if the pipe is already this
+    /// process's own.
+    fn reinit_after_fork(&self) -> PyResult<bool> {
+        let mut pipe = self.pipe.write().unwrap();
+        match pipe.as_ref() {
+            None => return Err(closed_error("FdWaker")),
+            Some(current) if !current.is_stale() => return Ok(false),
+            Some(current) if current.external => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "an FdWaker built with from_fds() can't replace its FDs after fork()",
+                ))
+            }
+            Some(_) => {}
+        }
+        let fresh = Pipe::new(self.inheritable)?;
+        match self.requested_capacity.load(Ordering::Relaxed) {
+            0 => {}
+            bytes => {
+                fresh.set_capacity(bytes)?;
+            }
+        }
+        *pipe = Some(Arc::new(fresh));
+        self.wait_loop.lock().unwrap().take();
+        self.waiters.clear();
+        Ok(true)
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> bool {
+        self.close(py);
+        false
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+#[cfg(unix)]
+impl FdWaker {
+    fn with_pipe(
+        pipe: Pipe,
+        on_full: FullPolicyKind,
+        inheritable: bool,
+        drain_policy: DrainPolicy,
+    ) -> Self {
+        Self {
+            pipe: std::sync::RwLock::new(Some(Arc::new(pipe))),
+            stamps: WakeTimestamps::new(),
+            write_stats: Arc::new(PipeWriteStats::default()),
+            drain_stats: Arc::new(PipeDrainStats::default()),
+            drain_policy,
+            on_full,
+            waiters: Arc::new(Waiters::default()),
+            wait_loop: Mutex::new(None),
+            requested_capacity: std::sync::atomic::AtomicUsize::new(0),
+            readers: Mutex::new(Vec::new()),
+            closed_pipe: Mutex::new(Weak::new()),
+            inheritable,
+        }
+    }
+
+    /// `register()` results that are still active
+    fn active_readers(&self) -> usize {
+        let readers = self.readers.lock().unwrap();
+        readers
+            .iter()
+            .filter(|reader| reader.lock().unwrap().is_some())
+            .count()
+    }
+
+    fn pipe(&self) -> PyResult<Arc<Pipe>> {
+        let pipe = self
+            .pipe
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| closed_error("FdWaker"))?;
+        if pipe.is_stale() {
+            return Err(forked_error("FdWaker"));
+        }
+        Ok(pipe)
+    }
+
+    fn handle(&self) -> PyResult<FdWakerHandle> {
+        Ok(FdWakerHandle {
+            pipe: self.pipe()?,
+            stamps: self.stamps.clone(),
+            write_stats: self.write_stats.clone(),
+            on_full: self.on_full,
+        })
+    }
+
+    /// Record the pipe-full policy in `spec` and report the waker's drops
+    fn annotate(&self, spec: BenchSpec) -> BenchSpec {
+        let spec = self
+            .on_full
+            .config()
+            .into_iter()
+            .fold(spec, |spec, (name, value)| spec.config(name, value));
+        spec.drops(self.drop_counter())
+    }
+
+    /// Cumulative count of wakes dropped because the pipe was full
+    fn drop_counter(&self) -> DropCounter {
+        let stats = self.write_stats.clone();
+        Arc::new(move || stats.dropped.load(Ordering::Relaxed))
+    }
+}
+
+/// Most bytes a single `wake_many()` write sends: PIPE_BUF (4 KiB on Linux,
+/// 512 bytes on macOS), so a write to a pipe lands whole or fails whole
+#[cfg(unix)]
+const WAKE_MANY_BYTES: usize = libc::PIPE_BUF;
+
+/// Longest single poll() while a writer blocks under `FullPolicy.block()`
+/// without a timeout; between polls it checks whether the waker was closed
+#[cfg(unix)]
+const BLOCK_SLICE: Duration = Duration::from_millis(100);
+
+/// Holder for the write FD that can be sent across threads
+#[cfg(unix)]
+struct FdWakerHandle {
+    pipe: Arc<Pipe>,
+    stamps: Arc<WakeTimestamps>,
+    write_stats: Arc<PipeWriteStats>,
+    on_full: FullPolicyKind,
+}
+
+#[cfg(unix)]
+impl FdWakerHandle {
+    fn wake(&self) {
+        self.wake_with_payload(&self.pipe.token, 1);
+    }
+
+    /// Wake with `n` events: `n` copies of the token, as many per write() as
+    /// fit in WAKE_MANY_BYTES
+    fn wake_many(&self, n: u64) {
+        let token = &self.pipe.token;
+        if token.len() > WAKE_MANY_BYTES {
+            (0..n).for_each(|_| self.wake());
+            return;
+        }
+        let per_write = (WAKE_MANY_BYTES / token.len()) as u64;
+        let mut buf = [0u8; WAKE_MANY_BYTES];
+        for chunk in buf.chunks_exact_mut(token.len()) {
+            chunk.copy_from_slice(token);
+        }
+        let mut left = n;
+        while left > 0 {
+            let events = left.min(per_write);
+            self.wake_with_payload(&buf[..events as usize * token.len()], events);
+            left -= events;
+        }
+    }
+
+    /// Wake with `events` events by writing `payload` to the pipe in a single
+    /// write()
+    fn wake_with_payload(&self, payload: &[u8], events: u64) {
+        self.write_wake(payload.len(), events, |fd| unsafe {
+            libc::write(fd, payload.as_ptr() as *const libc::c_void, payload.len())
+        });
+    }
+
+    /// Wake with one token per entry of `iov`, gathered by a single writev()
+    fn wake_vectored(&self, iov: &[libc::iovec]) {
+        let len = iov.iter().map(|v| v.iov_len).sum();
+        self.write_wake(len, iov.len() as u64, |fd| unsafe {
+            libc::writev(fd, iov.as_ptr(), iov.len() as libc::c_int)
+        });
+    }
+
+    /// Issue `write` (expected to transfer `len` bytes carrying `events`
+    /// wakes), retrying on EINTR and applying the block timeout on a full
+    /// pipe. A write that fails whole drops all of its `events`; a short one
+    /// (possible on a `from_fds()` socket) drops those it didn't complete.
+    fn write_wake(&self, len: usize, events: u64, write: impl Fn(RawFd) -> isize) {
+        trace_scope!("fd.wake");
+        // The waker was closed while this thread was running
+        if self.pipe.closed.load(Ordering::Relaxed) {
+            return;
+        }
+        // Never write into a pipe shared with the parent after fork()
+        if self.pipe.is_stale() {
+            self.write_stats.errors.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.stamps.stamp();
+        let write_fd = self.pipe.write.as_raw_fd();
+        let deadline = match self.on_full {
+            FullPolicyKind::Block { timeout } => timeout.map(|timeout| Instant::now() + timeout),
+            _ => None,
+        };
+        let mut retries = 0;
+        let stats = &self.write_stats;
+        loop {
+            note_syscalls(1);
+            let result = write(write_fd);
+            if result >= 0 {
+                stats.writes.fetch_add(1, Ordering::Relaxed);
+                stats.bytes.fetch_add(result as u64, Ordering::Relaxed);
+                if (result as usize) < len {
+                    stats.short_writes.fetch_add(1, Ordering::Relaxed);
+                    let written = result as u64 * events / len as u64;
+                    stats.dropped.fetch_add(events - written, Ordering::Relaxed);
+                }
+                return;
+            }
+            match std::io::Error::last_os_error().raw_os_error() {
+                Some(libc::EINTR) => continue,
+                Some(libc::EAGAIN) => {
+                    let remaining = match self.on_full {
+                        FullPolicyKind::Drop => Duration::ZERO,
+                        FullPolicyKind::Retry { attempts, backoff } => {
+                            if retries < attempts {
+                                std::thread::sleep(backoff.saturating_mul(1 << retries.min(20)));
+                                retries += 1;
+                                stats.retries.fetch_add(1, Ordering::Relaxed);
+                                continue;
+                            }
+                            Duration::ZERO
+                        }
+                        // Wait in slices so a close() ends an unbounded wait
+                        FullPolicyKind::Block { .. } => deadline
+                            .map_or(BLOCK_SLICE, |d| d.saturating_duration_since(Instant::now())),
+                    };
+                    if remaining.is_zero() {
+                        stats.dropped.fetch_add(events, Ordering::Relaxed);
+                        return;
+                    }
+                    let wait_start = Instant::now();
+                    let mut pollfd = libc::pollfd {
+                        fd: write_fd,
+                        events: libc::POLLOUT,
+                        revents: 0,
+                    };
+                    let timeout_ms = remaining.as_millis().clamp(1, libc::c_int::MAX as u128);
+                    note_syscalls(1);
+                    unsafe { libc::poll(&mut pollfd, 1, timeout_ms as libc::c_int) };
+                    stats.blocked.fetch_add(1, Ordering::Relaxed);
+                    stats
+                        .blocked_ns
+                        .fetch_add(wait_start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+                    if self.pipe.closed.load(Ordering::Relaxed) {
+                        return;
+                    }
+                }
+                _ => {
+                    stats.errors.fetch_add(1, Ordering::Relaxed);
+                    report_os_error(std::io::Error::last_os_error());
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Read FDs registered with an event loop by `FdWaker.register()` or
+/// `PriorityFdWaker.register()`. The readers stay registered until `close()`
+/// (or leaving a `with` block), even if this object is garbage collected.
+#[cfg(unix)]
+#[pyclass]
+struct ReaderRegistration {
+    // None once close() has removed the readers (or FdWaker.wait() replaced
+    // them); shared with an FdWaker, which counts the active ones
+    event_loop: RegisteredLoop,
+    fds: Vec<RawFd>,
+}
+
+/// The loop a ReaderRegistration's readers are on, or None once inactive
+#[cfg(unix)]
+type RegisteredLoop = Arc<Mutex<Option<PyObject>>>;
+
+#[cfg(unix)]
+#[pymethods]
+impl ReaderRegistration {
+    /// The first (for a priority waker, the urgent) registered FD
+    #[getter]
+    fn fd(&self) -> RawFd {
+        self.fds[0]
+    }
+
+    #[getter]
+    fn fds(&self) -> Vec<RawFd> {
+        self.fds.clone()
+    }
+
+    /// Remove the readers from the event loop. Idempotent. If a removal
+    /// raises, the others are still attempted and the first error is raised;
+    /// the registration stays active so `close()` can be retried.
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        let mut event_loop = self.event_loop.lock().unwrap();
+        let Some(registered) = event_loop.as_ref() else {
+            return Ok(());
+        };
+        let mut first_error = None;
+        for fd in &self.fds {
+            if let Err(err) = registered.call_method1(py, "remove_reader", (fd,)) {
+                first_error.get_or_insert(err);
+            }
+        }
+        match first_error {
+            Some(err) => Err(err),
+            None => {
+                *event_loop = None;
+                Ok(())
+            }
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.event_loop.lock().unwrap().is_some()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.close(py)?;
+        Ok(false)
+    }
+}
+
+/// Create an FD-based waker
+#[cfg(unix)]
+#[pyfunction]
+fn create_fd_waker() -> PyResult<FdWaker> {
+    FdWaker::new(None, false, None, DEFAULT_DRAIN_BUFFER_SIZE, None)
+}
+
+// =============================================================================
+// Approach 2: call_soon_threadsafe wakeup (acquires GIL on Rust side)
+// =============================================================================
+
+/// Accumulated GIL timings for a CallbackWaker's wakes: how long each wake
+/// waited to acquire the GIL, and how long it then held it.
+#[derive(Default)]
+struct GilStats {
+    wakes: AtomicU64,
+    acquire_ns_total: AtomicU64,
+    acquire_ns_max: AtomicU64,
+    hold_ns_total: AtomicU64,
+    hold_ns_max: AtomicU64,
+}
+
+impl GilStats {
+    fn record(&self, acquire: Duration, hold: Duration) {
+        let acquire = acquire.as_nanos() as u64;
+        let hold = hold.as_nanos() as u64;
+        self.wakes.fetch_add(1, Ordering::Relaxed);
+        self.acquire_ns_total.fetch_add(acquire, Ordering::Relaxed);
+        self.acquire_ns_max.fetch_max(acquire, Ordering::Relaxed);
+        self.hold_ns_total.fetch_add(hold, Ordering::Relaxed);
+        self.hold_ns_max.fetch_max(hold, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        self.wakes.store(0, Ordering::Relaxed);
+        self.acquire_ns_total.store(0, Ordering::Relaxed);
+        self.acquire_ns_max.store(0, Ordering::Relaxed);
+        self.hold_ns_total.store(0, Ordering::Relaxed);
+        self.hold_ns_max.store(0, Ordering::Relaxed);
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let wakes = self.wakes.load(Ordering::Relaxed);
+        let acquire_total = self.acquire_ns_total.load(Ordering::Relaxed);
+        let hold_total = self.hold_ns_total.load(Ordering::Relaxed);
+        let mean = |total: u64| {
+            if wakes > 0 {
+                total as f64 / wakes as f64
+            } else {
+                0.0
+            }
+        };
+
+        let stats = pyo3::types::PyDict::new(py);
+        stats.set_item("wakes", wakes)?;
+        stats.set_item("acquire_ns_total", acquire_total)?;
+        stats.set_item("acquire_ns_mean", mean(acquire_total))?;
+        stats.set_item(
+            "acquire_ns_max",
+            self.acquire_ns_max.load(Ordering::Relaxed),
+        )?;
+        stats.set_item("hold_ns_total", hold_total)?;
+        stats.set_item("hold_ns_mean", mean(hold_total))?;
+        stats.set_item("hold_ns_max", self.hold_ns_max.load(Ordering::Relaxed))?;
+        Ok(stats)
+    }
+}
+
+/// Where a CallbackWaker's wake latency goes: from the start of the wake to
+/// call_soon_threadsafe returning (`scheduled`), and to the callback starting
+/// to run on the loop (`executed`). Both are measured on the monotonic clock.
+#[derive(Default)]
+struct PhaseStats {
+    scheduled: AtomicU64,
+    scheduled_ns_total: AtomicU64,
+    scheduled_ns_max: AtomicU64,
+    executed: AtomicU64,
+    executed_ns_total: AtomicU64,
+    executed_ns_max: AtomicU64,
+}
+
+impl PhaseStats {
+    fn record_scheduled(&self, start_ns: u64) {
+        let elapsed = monotonic_ns().saturating_sub(start_ns);
+        self.scheduled.fetch_add(1, Ordering::Relaxed);
+        self.scheduled_ns_total
+            .fetch_add(elapsed, Ordering::Relaxed);
+        self.scheduled_ns_max.fetch_max(elapsed, Ordering::Relaxed);
+    }
+
+    fn record_executed(&self, start_ns: u64) {
+        let elapsed = monotonic_ns().saturating_sub(start_ns);
+        self.executed.fetch_add(1, Ordering::Relaxed);
+        self.executed_ns_total.fetch_add(elapsed, Ordering::Relaxed);
+        self.executed_ns_max.fetch_max(elapsed, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        self.scheduled.store(0, Ordering::Relaxed);
+        self.scheduled_ns_total.store(0, Ordering::Relaxed);
+        self.scheduled_ns_max.store(0, Ordering::Relaxed);
+        self.executed.store(0, Ordering::Relaxed);
+        self.executed_ns_total.store(0, Ordering::Relaxed);
+        self.executed_ns_max.store(0, Ordering::Relaxed);
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let mean = |total: &AtomicU64, count: &AtomicU64| {
+            let count = count.load(Ordering::Relaxed);
+            if count > 0 {
+                total.load(Ordering::Relaxed) as f64 / count as f64
+            } else {
+                0.0
+            }
+        };
+
+        let stats = pyo3::types::PyDict::new(py);
+        stats.set_item("scheduled", self.scheduled.load(Ordering::Relaxed))?;
+        stats.set_item(
+            "scheduled_ns_total",
+            self.scheduled_ns_total.load(Ordering::Relaxed),
+        )?;
+        stats.set_item(
+            "scheduled_ns_mean",
+            mean(&self.scheduled_ns_total, &self.scheduled),
+        )?;
+        stats.set_item(
+            "scheduled_ns_max",
+            self.scheduled_ns_max.load(Ordering::Relaxed),
+        )?;
+        stats.set_item("executed", self.executed.load(Ordering::Relaxed))?;
+        stats.set_item(
+            "executed_ns_total",
+            self.executed_ns_total.load(Ordering::Relaxed),
+        )?;
+        stats.set_item(
+            "executed_ns_mean",
+            mean(&self.executed_ns_total, &self.executed),
+        )?;
+        stats.set_item(
+            "executed_ns_max",
+            self.executed_ns_max.load(Ordering::Relaxed),
+        )?;
+        Ok(stats)
+    }
+}
+
+/// Outcomes of a CallbackWaker's wakes: `errors` counts wakes whose
+/// call_soon_threadsafe raised (or found the loop collected), and
+/// `callback_errors` callbacks that raised when the loop ran them
+#[derive(Default)]
+struct CallbackOutcomes {
+    scheduled: AtomicU64,
+    errors: AtomicU64,
+    callback_errors: AtomicU64,
+    last_error: Mutex<Option<PyErr>>,
+}
+
+impl CallbackOutcomes {
+    /// Count `err` against `counter`, keep it as the last error and report
+    /// it to `thread_errors()`
+    fn record(&self, py: Python<'_>, counter: &AtomicU64, err: PyErr) {
+        counter.fetch_add(1, Ordering::Relaxed);
+        *self.last_error.lock().unwrap() = Some(err.clone_ref(py));
+        report_python_error(err);
+    }
+}
+
+/// A waker that uses call_soon_threadsafe to wake up the Python event loop.
+/// This DOES acquire the GIL on the Rust side.
+///
+/// The loop is held through a weak reference, so a waker doesn't keep a
+/// finished loop alive. Starting a producer or `wait()` on a loop that has
+/// been closed or collected raises RuntimeError.
+#[pyclass]
+struct CallbackWaker {
+    callback: PyObject,
+    // weakref.ref to the event loop
+    event_loop: Py<pyo3::types::PyWeakrefReference>,
+    stamps: Arc<WakeTimestamps>,
+    gil: Arc<GilStats>,
+    phases: Arc<PhaseStats>,
+    outcomes: Arc<CallbackOutcomes>,
+    // Scheduled in place of the callback: records when it starts running,
+    // then calls it
+    run_callback: PyObject,
+    sequence: Arc<AtomicU64>,
+    waiters: Arc<Waiters>,
+    // Scheduled alongside the callback while wait() futures are outstanding
+    complete_waiters: PyObject,
+    // Fork generation the event loop was bound in
+    generation: u64,
+}
+
+#[pymethods]
+impl CallbackWaker {
+    #[new]
+    fn new(py: Python<'_>, callback: PyObject, event_loop: PyObject) -> PyResult<Self> {
+        let waiters = Arc::new(Waiters::default());
+        let complete_waiters = {
+            let waiters = waiters.clone();
+            pyo3::types::PyCFunction::new_closure(
+                py,
+                None,
+                None,
+                move |args, _kwargs| -> PyResult<()> { waiters.complete_all(args.py()) },
+            )?
+        };
+        let phases = Arc::new(PhaseStats::default());
+        let outcomes = Arc::new(CallbackOutcomes::default());
+        // run_callback(start_ns, callback, *args)
+        let run_callback = {
+            let (phases, outcomes) = (phases.clone(), outcomes.clone());
+            pyo3::types::PyCFunction::new_closure(
+                py,
+                None,
+                None,
+                move |args, _kwargs| -> PyResult<PyObject> {
+                    phases.record_executed(args.get_item(0)?.extract()?);
+                    let rest = args.get_slice(2, args.len());
+                    // Still raised, so the loop's exception handler sees it
+                    args.get_item(1)?
+                        .call1(rest)
+                        .map(Bound::unbind)
+                        .inspect_err(|err| {
+                            let py = args.py();
+                            outcomes.record(py, &outcomes.callback_errors, err.clone_ref(py));
+                        })
+                },
+            )?
+        };
+        Ok(Self {
+            callback,
+            event_loop: pyo3::types::PyWeakrefReference::new(event_loop.bind(py))?.unbind(),
+            stamps: WakeTimestamps::new(),
+            gil: Arc::new(GilStats::default()),
+            phases,
+            outcomes,
+            run_callback: run_callback.into_any().unbind(),
+            sequence: Arc::new(AtomicU64::new(0)),
+            waiters,
+            complete_waiters: complete_waiters.into_any().unbind(),
+            generation: fork_generation(),
+        })
+    }
+
+    /// In a child after fork(), rebind the waker to `event_loop` (the child's
+    /// own loop) so wakes stop going to the loop inherited from the parent,
+    /// whose self-pipe the parent still reads. Outstanding `wait()` futures
+    /// are forgotten.
+    fn reinit_after_fork(&mut self, py: Python<'_>, event_loop: PyObject) -> PyResult<()> {
+        self.event_loop = pyo3::types::PyWeakrefReference::new(event_loop.bind(py))?.unbind();
+        self.waiters.clear();
+        self.generation = fork_generation();
+        Ok(())
+    }
+
+    /// Awaitable (a future on the waker's event loop) that completes on the
+    /// next wake, after the callback has been scheduled
+    fn wait<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.check_fork()?;
+        self.waiters.wait(&self.live_loop(py)?)
+    }
+
+    /// Whether the event loop still exists and isn't closed
+    fn loop_alive(&self, py: Python<'_>) -> bool {
+        self.live_loop(py).is_ok()
+    }
+
+    /// Wake outcomes: `scheduled` (call_soon_threadsafe succeeded), `errors`
+    /// (it raised, e.g. on a closed loop, or the loop was collected),
+    /// `callback_errors` (the callback raised on the loop), and `last_error`,
+    /// the most recent exception of either kind or None
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        let counters = [
+            ("scheduled", &self.outcomes.scheduled),
+            ("errors", &self.outcomes.errors),
+            ("callback_errors", &self.outcomes.callback_errors),
+        ];
+        for (name, counter) in counters {
+            stats.set_item(name, counter.load(Ordering::Relaxed))?;
+        }
+        let last_error = self.outcomes.last_error.lock().unwrap();
+        stats.set_item("last_error", last_error.as_ref().map(|err| err.value(py)))?;
+        Ok(stats)
+    }
+
+    /// Sequence number the next sequenced wake will pass to the callback
+    fn next_sequence(&self) -> u64 {
+        self.sequence.load(Ordering::SeqCst)
+    }
+
+    /// GIL timings across all wakes so far: `wakes`, plus total/mean/max
+    /// nanoseconds spent waiting to acquire the GIL (`acquire_ns_*`) and
+    /// holding it for call_soon_threadsafe (`hold_ns_*`)
+    fn gil_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        self.gil.to_dict(py)
+    }
+
+    fn reset_gil_stats(&self) {
+        self.gil.reset();
+    }
+
+    /// Wake latency split into time until scheduled (`scheduled_ns_*`: from
+    /// the start of the wake until call_soon_threadsafe returned) and time
+    /// until executed (`executed_ns_*`: until the callback started running
+    /// on the loop), as total/mean/max nanoseconds with their sample counts
+    fn phase_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        self.phases.to_dict(py)
+    }
+
+    fn reset_phase_stats(&self) {
+        self.phases.reset();
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+impl CallbackWaker {
+    fn check_fork(&self) -> PyResult<()> {
+        if self.generation != fork_generation() {
+            return Err(forked_error("CallbackWaker"));
+        }
+        Ok(())
+    }
+
+    /// The event loop, or RuntimeError if it has been closed or collected
+    fn live_loop<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let event_loop = upgrade_loop(self.event_loop.bind(py), "CallbackWaker")?;
+        if event_loop.call_method0("is_closed")?.is_truthy()? {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "CallbackWaker's event loop is closed",
+            ));
+        }
+        Ok(event_loop)
+    }
+
+    fn handle(&self, py: Python<'_>) -> PyResult<CallbackWakerHandle> {
+        self.check_fork()?;
+        self.live_loop(py)?;
+        Ok(CallbackWakerHandle {
+            callback: LoopHeld::new(&self.callback, py),
+            event_loop: LoopHeld::new(self.event_loop.as_any(), py),
+            stamps: self.stamps.clone(),
+            gil: self.gil.clone(),
+            phases: self.phases.clone(),
+            outcomes: self.outcomes.clone(),
+            run_callback: LoopHeld::new(&self.run_callback, py),
+            sequence: self.sequence.clone(),
+            waiters: self.waiters.clone(),
+            complete_waiters: LoopHeld::new(&self.complete_waiters, py),
+        })
+    }
+}
+
+/// A Python object owned by a handle that producer threads carry. It derefs
+/// to the object until `release_on_loop` takes it when the handle is dropped.
+struct LoopHeld(Option<Py<PyAny>>);
+
+impl LoopHeld {
+    fn new(object: &Py<PyAny>, py: Python<'_>) -> Self {
+        Self(Some(object.clone_ref(py)))
+    }
+}
+
+impl std::ops::Deref for LoopHeld {
+    type Target = Py<PyAny>;
+
+    fn deref(&self) -> &Py<PyAny> {
+        self.0.as_ref().expect("LoopHeld used after release")
+    }
+}
+
+/// Release a handle's Python objects when it's dropped. On a thread holding
+/// the GIL they're released in place. Otherwise, rather than leaving the
+/// DECREFs to whichever thread next takes the GIL, the GIL is taken once and
+/// the objects are passed to a no-op scheduled through `event_loop.schedule`
+/// (`call_soon_threadsafe` on an asyncio loop, `run_sync_soon` on a Trio
+/// token, `run_callback_threadsafe` on a gevent loop), so the last references
+/// (and any finalizers) go away on the loop's thread. If the loop is closed
+/// they're released here; after interpreter shutdown they're left to PyO3.
+fn release_on_loop<'a>(
+    event_loop: &mut LoopHeld,
+    schedule: &str,
+    objects: impl IntoIterator<Item = &'a mut LoopHeld>,
+) {
+    // Both only read interpreter state, so they may be called without the GIL
+    let gil_held = unsafe { pyo3::ffi::PyGILState_Check() } == 1;
+    if gil_held || unsafe { pyo3::ffi::Py_IsInitialized() } == 0 {
+        return;
+    }
+    let Some(event_loop) = event_loop.0.take() else {
+        return;
+    };
+    let objects: Vec<Py<PyAny>> = objects
+        .into_iter()
+        .filter_map(|held| held.0.take())
+        .collect();
+    Python::with_gil(|py| {
+        // A CallbackWaker's handle holds its loop weakly
+        let target = match event_loop.downcast_bound::<pyo3::types::PyWeakrefReference>(py) {
+            Ok(weak) => weak.upgrade(),
+            Err(_) => Some(event_loop.bind(py).clone()),
+        };
+        let Some(target) = target else {
+            return;
+        };
+        let release = pyo3::types::PyCFunction::new_closure(py, None, None, |_args, _kwargs| {});
+        let scheduled = release.and_then(|release| {
+            let objects = pyo3::types::PyTuple::new(py, objects)?;
+            target.call_method1(schedule, (release, objects))
+        });
+        // A closed loop raises; the objects were released with the GIL held
+        drop(scheduled);
+        drop(event_loop);
+    });
+}
+
+/// The loop a `waker`'s weak reference points to, or RuntimeError if it has
+/// been collected
+fn upgrade_loop<'py>(
+    event_loop: &Bound<'py, pyo3::types::PyWeakrefReference>,
+    waker: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    event_loop.upgrade().ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+            "{waker}'s event loop has been garbage collected"
+        ))
+    })
+}
+
+/// Holder for the callback waker that producer threads carry. Dropped off the
+/// loop's thread, it releases its Python objects on the loop. `event_loop` is
+/// the waker's weak reference to the loop.
+struct CallbackWakerHandle {
+    callback: LoopHeld,
+    event_loop: LoopHeld,
+    stamps: Arc<WakeTimestamps>,
+    gil: Arc<GilStats>,
+    phases: Arc<PhaseStats>,
+    outcomes: Arc<CallbackOutcomes>,
+    run_callback: LoopHeld,
+    sequence: Arc<AtomicU64>,
+    waiters: Arc<Waiters>,
+    complete_waiters: LoopHeld,
+}
+
+impl Drop for CallbackWakerHandle {
+    fn drop(&mut self) {
+        release_on_loop(
+            &mut self.event_loop,
+            "call_soon_threadsafe",
+            [
+                &mut self.callback,
+                &mut self.run_callback,
+                &mut self.complete_waiters,
+            ],
+        );
+    }
+}
+
+impl CallbackWakerHandle {
+    fn wake(&self) {
+        self.schedule(None);
+    }
+
+    /// Wake, passing the waker's next sequence number to the callback
+    fn wake_sequenced(&self) {
+        self.schedule(Some(self.sequence.fetch_add(1, Ordering::SeqCst)));
+    }
+
+    fn schedule(&self, seq: Option<u64>) {
+        trace_scope!("callback.wake");
+        // Stamp before the GIL wait so it counts towards wake latency
+        self.stamps.stamp();
+        let start_ns = monotonic_ns();
+        let wait_start = Instant::now();
+        #[cfg(feature = "trace")]
+        let acquiring = tracing::trace_span!("callback.gil_acquire").entered();
+        // This ACQUIRES THE GIL from the Rust thread
+        Python::with_gil(|py| {
+            let acquired = Instant::now();
+            #[cfg(feature = "trace")]
+            drop(acquiring);
+            trace_scope!("callback.gil_hold");
+            // Call event_loop.call_soon_threadsafe(callback[, seq]), via
+            // run_callback so the loop records when the callback starts
+            let event_loop = match self
+                .event_loop
+                .downcast_bound(py)
+                .map_err(PyErr::from)
+                .and_then(|event_loop| upgrade_loop(event_loop, "CallbackWaker"))
+            {
+                Ok(event_loop) => event_loop,
+                Err(err) => {
+                    self.outcomes.record(py, &self.outcomes.errors, err);
+                    self.gil.record(acquired - wait_start, acquired.elapsed());
+                    return;
+                }
+            };
+            let scheduled = match seq {
+                None => event_loop.call_method1(
+                    "call_soon_threadsafe",
+                    (&*self.run_callback, start_ns, &*self.callback),
+                ),
+                Some(seq) => event_loop.call_method1(
+                    "call_soon_threadsafe",
+                    (&*self.run_callback, start_ns, &*self.callback, seq),
+                ),
+            };
+            match scheduled {
+                Ok(_) => {
+                    self.outcomes.scheduled.fetch_add(1, Ordering::Relaxed);
+                    self.phases.record_scheduled(start_ns);
+                }
+                Err(err) => self.outcomes.record(py, &self.outcomes.errors, err),
+            }
+            if !self.waiters.is_empty() {
+                // Fails only when the wake itself did, which is already counted
+                let _ = event_loop.call_method1("call_soon_threadsafe", (&*self.complete_waiters,));
+            }
+            self.gil.record(acquired - wait_start, acquired.elapsed());
+        });
+    }
+}
+
+/// Create a callback-based waker
+#[pyfunction]
+fn create_callback_waker(
+    py: Python<'_>,
+    callback: PyObject,
+    event_loop: PyObject,
+) -> PyResult<CallbackWaker> {
+    CallbackWaker::new(py, callback, event_loop)
+}
+
+// =============================================================================
+// GIL cost decomposition: the parts of a callback wake, measured separately
+// =============================================================================
+
+/// Run `measure` on a new Rust thread while the caller releases the GIL, and
+/// summarize the nanoseconds it records. `measure` runs the iterations itself,
+/// so it can hold the GIL across them.
+fn measure_off_thread(
+    py: Python<'_>,
+    name: &str,
+    iterations: usize,
+    measure: impl FnOnce(&mut Histogram<u64>) + Send + 'static,
+) -> PyResult<LatencySummary> {
+    if iterations == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "iterations must be at least 1",
+        ));
+    }
+    let thread = thread_builder(name, None, None).spawn(move || {
+        let mut hist = Histogram::new(3).expect("3 significant figures is valid");
+        measure(&mut hist);
+        hist
+    })?;
+    let hist = py.allow_threads(|| thread.join()).map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{name} thread panicked"))
+    })?;
+    Ok(LatencySummary::from_histogram(&hist))
+}
+
+/// Time `Python::with_gil` from a Rust thread that doesn't hold the GIL:
+/// acquiring and releasing it with nothing in between, `iterations` times,
+/// while the calling thread waits without the GIL. This is the floor under
+/// every wake that acquires the GIL. Returns a LatencySummary in ns.
+#[pyfunction]
+#[pyo3(signature = (iterations=100_000))]
+fn measure_gil_acquire(py: Python<'_>, iterations: usize) -> PyResult<LatencySummary> {
+    measure_off_thread(py, "gil-acquire", iterations, move |hist| {
+        for _ in 0..iterations {
+            let start = Instant::now();
+            Python::with_gil(|_py| {});
+            let _ = hist.record(start.elapsed().as_nanos() as u64);
+        }
+    })
+}
+
+/// Time a trivial `call_method1` (`(0).__add__(1)`) from a Rust thread that
+/// already holds the GIL, `iterations` times: the cost of calling into
+/// Python once the GIL is held, which `measure_gil_acquire()` excludes.
+/// Returns a LatencySummary in ns.
+#[pyfunction]
+#[pyo3(signature = (iterations=100_000))]
+fn measure_callmethod_overhead(py: Python<'_>, iterations: usize) -> PyResult<LatencySummary> {
+    measure_off_thread(py, "callmethod", iterations, move |hist| {
+        Python::with_gil(|py| {
+            let target = 0i64
+                .into_pyobject(py)
+                .expect("int conversion is infallible");
+            for _ in 0..iterations {
+                let start = Instant::now();
+                let result = target.call_method1("__add__", (1,));
+                let _ = hist.record(start.elapsed().as_nanos() as u64);
+                if let Err(err) = result {
+                    report_python_error(err);
+                }
+            }
+        });
+    })
+}
+
+// =============================================================================
+// FFI floor: no-op calls across the PyO3 boundary
+// =============================================================================
+
+/// Do nothing: a call into the extension with no arguments
+#[pyfunction]
+fn baseline_noop() {}
+
+/// Do nothing with three positional arguments, which PyO3 still extracts
+#[pyfunction]
+fn baseline_args(_a: i64, _b: f64, _c: &Bound<'_, PyAny>) {}
+
+/// Return an int outside CPython's small-int cache, so each call allocates one
+#[pyfunction]
+fn baseline_return_int() -> u64 {
+    1 << 40
+}
+
+/// Return a new two-element tuple of ints
+#[pyfunction]
+fn baseline_return_tuple() -> (u64, u64) {
+    (1 << 40, 1 << 41)
+}
+
+/// Time each baseline function, called through the Python call protocol as
+/// Python code calls it, `iterations` times, in reporting order
+fn ffi_baselines(
+    py: Python<'_>,
+    iterations: usize,
+) -> PyResult<Vec<(&'static str, LatencySummary)>> {
+    if iterations == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "iterations must be at least 1",
+        ));
+    }
+    let args = (1i64, 2.0f64, py.None()).into_pyobject(py)?;
+    let empty = pyo3::types::PyTuple::empty(py);
+    let functions = [
+        ("noop", wrap_pyfunction!(baseline_noop, py)?, &empty),
+        ("args", wrap_pyfunction!(baseline_args, py)?, &args),
+        (
+            "return_int",
+            wrap_pyfunction!(baseline_return_int, py)?,
+            &empty,
+        ),
+        (
+            "return_tuple",
+            wrap_pyfunction!(baseline_return_tuple, py)?,
+            &empty,
+        ),
+    ];
+    functions
+        .into_iter()
+        .map(|(name, function, args)| {
+            let mut hist = Histogram::<u64>::new(3).expect("3 significant figures is valid");
+            for _ in 0..iterations {
+                let start = Instant::now();
+                let result = function.call1(args);
+                let _ = hist.record(start.elapsed().as_nanos() as u64);
+                drop(result?);
+            }
+            Ok((name, LatencySummary::from_histogram(&hist)))
+        })
+        .collect()
+}
+
+/// Time the no-op baseline functions (`baseline_noop`, `baseline_args`,
+/// `baseline_return_int`, `baseline_return_tuple`) through the same call
+/// protocol Python code uses, `iterations` times each. This is the FFI floor
+/// under every other number in this crate. Each sample also includes one
+/// clock read. Returns `{"noop", "args", "return_int", "return_tuple"}`
+/// mapped to LatencySummary in ns.
+#[pyfunction]
+#[pyo3(signature = (iterations=100_000))]
+fn measure_ffi_baselines(
+    py: Python<'_>,
+    iterations: usize,
+) -> PyResult<Bound<'_, pyo3::types::PyDict>> {
+    let baselines = pyo3::types::PyDict::new(py);
+    for (name, summary) in ffi_baselines(py, iterations)? {
+        baselines.set_item(name, summary)?;
+    }
+    Ok(baselines)
+}
+
+// =============================================================================
+// Approach 3: eventfd-based wakeup (Linux only, no GIL acquisition on Rust side)
+// =============================================================================
+
+/// Outcomes of an EventFdWaker's drains
+#[cfg(target_os = "linux")]
+#[derive(Default)]
+struct EventFdDrainStats {
+    drains: AtomicU64,
+    /// Drains that found the counter already zero
+    empty: AtomicU64,
+    wakes: AtomicU64,
+    max_coalesced: AtomicU64,
+}
+
+/// A waker backed by a Linux eventfd. Unlike a pipe, the kernel keeps a single
+/// 8-byte counter, so any number of wakes between reads coalesce into one and
+/// the read returns how many there were. In semaphore mode (EFD_SEMAPHORE)
+/// each read takes a single wake instead, and the FD stays readable until
+/// every wake has been taken.
+#[cfg(target_os = "linux")]
+#[pyclass]
+struct EventFdWaker {
+    /// Shared with every handle, so the eventfd stays open while producers
+    /// can still write to it
+    fd: Arc<TrackedFd>,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+    #[pyo3(get)]
+    semaphore: bool,
+    drain_stats: EventFdDrainStats,
+}
+
+#[cfg(target_os = "linux")]
+#[pymethods]
+impl EventFdWaker {
+    #[new]
+    #[pyo3(signature = (semaphore=false))]
+    fn new(semaphore: bool) -> PyResult<Self> {
+        let mut flags = libc::EFD_NONBLOCK | libc::EFD_CLOEXEC;
+        if semaphore {
+            flags |= libc::EFD_SEMAPHORE;
+        }
+        let fd = unsafe { libc::eventfd(0, flags) };
+        if fd < 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyOSError, _>(
+                "Failed to create eventfd",
+            ));
+        }
+
+        let fd = TrackedFd::new(unsafe { OwnedFd::from_raw_fd(fd) }, "eventfd");
+
+        Ok(Self {
+            fd: Arc::new(fd),
+            stamps: WakeTimestamps::new(),
+            errors: Arc::new(AtomicU64::new(0)),
+            semaphore,
+            drain_stats: EventFdDrainStats::default(),
+        })
+    }
+
+    /// Get the eventfd for registering with the event loop
+    fn get_read_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+
+    /// Read the counter (call this in the callback). Normally this resets it
+    /// to zero and returns the number of wakes coalesced since the last drain.
+    /// In semaphore mode it takes one wake and returns 1, leaving the rest
+    /// for further callbacks. Returns 0 if no wake was pending.
+    fn drain(&self) -> PyResult<u64> {
+        trace_scope!("eventfd.drain");
+        let mut value: u64 = 0;
+        let result = unsafe {
+            libc::read(
+                self.fd.as_raw_fd(),
+                &mut value as *mut u64 as *mut libc::c_void,
+                std::mem::size_of::<u64>(),
+            )
+        };
+        let stats = &self.drain_stats;
+        stats.drains.fetch_add(1, Ordering::Relaxed);
+        if result <= 0 {
+            stats.empty.fetch_add(1, Ordering::Relaxed);
+            return Ok(0);
+        }
+        stats.wakes.fetch_add(value, Ordering::Relaxed);
+        stats.max_coalesced.fetch_max(value, Ordering::Relaxed);
+        Ok(value)
+    }
+
+    /// Add `n` to the counter from the calling thread with one write, so a
+    /// single `drain()` returns all `n` (one at a time in semaphore mode)
+    fn wake_many(&self, py: Python<'_>, n: u64) {
+        let handle = self.handle();
+        py.allow_threads(|| handle.wake_many(n));
+    }
+
+    /// Drain counters: `drains`, `empty` (drains with nothing pending),
+    /// `wakes` (total taken by drains), and `max_coalesced` (most wakes a
+    /// single drain returned; always 1 in semaphore mode)
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        let counters = [
+            ("drains", &self.drain_stats.drains),
+            ("empty", &self.drain_stats.empty),
+            ("wakes", &self.drain_stats.wakes),
+            ("max_coalesced", &self.drain_stats.max_coalesced),
+        ];
+        for (name, counter) in counters {
+            stats.set_item(name, counter.load(Ordering::Relaxed))?;
+        }
+        Ok(stats)
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl EventFdWaker {
+    fn handle(&self) -> EventFdWakerHandle {
+        EventFdWakerHandle {
+            fd: self.fd.clone(),
+            stamps: self.stamps.clone(),
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+/// Holder for the eventfd that can be sent across threads
+#[cfg(target_os = "linux")]
+struct EventFdWakerHandle {
+    fd: Arc<TrackedFd>,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+}
+
+#[cfg(target_os = "linux")]
+impl EventFdWakerHandle {
+    fn wake(&self) {
+        self.wake_many(1);
+    }
+
+    /// Add `n` to the counter in a single write
+    fn wake_many(&self, n: u64) {
+        trace_scope!("eventfd.wake");
+        if n == 0 {
+            return;
+        }
+        self.stamps.stamp();
+        // The kernel rejects the value u64::MAX
+        let value: u64 = n.min(u64::MAX - 1);
+        note_syscalls(1);
+        let written = unsafe {
+            libc::write(
+                self.fd.as_raw_fd(),
+                &value as *const u64 as *const libc::c_void,
+                std::mem::size_of::<u64>(),
+            )
+        };
+        if written < 0 {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+            report_os_error(std::io::Error::last_os_error());
+        }
+    }
+}
+
+/// Create an eventfd-based waker
+#[cfg(target_os = "linux")]
+#[pyfunction]
+#[pyo3(signature = (semaphore=false))]
+fn create_eventfd_waker(semaphore: bool) -> PyResult<EventFdWaker> {
+    EventFdWaker::new(semaphore)
+}
+
+// =============================================================================
+// Approach 4: socket-based wakeup (cross-platform, no GIL acquisition on Rust side)
+// =============================================================================
+
+/// The connected stream type backing a SocketWaker: a Unix socketpair where
+/// available, otherwise a loopback TCP connection (Windows has no socketpair).
+#[cfg(unix)]
+type SocketStream = TrackedFd<std::os::unix::net::UnixStream>;
+#[cfg(windows)]
+type SocketStream = std::net::TcpStream;
+
+/// The OS handle Python passes to `add_reader` (an FD on Unix, a SOCKET on Windows).
+#[cfg(unix)]
+type RawSocketHandle = RawFd;
+#[cfg(windows)]
+type RawSocketHandle = RawSocket;
+
+#[cfg(unix)]
+fn socket_pair() -> std::io::Result<(SocketStream, SocketStream)> {
+    let (read, write) = std::os::unix::net::UnixStream::pair()?;
+    Ok((
+        TrackedFd::new(read, "socket"),
+        TrackedFd::new(write, "socket"),
+    ))
+}
+
+#[cfg(windows)]
+fn socket_pair() -> std::io::Result<(SocketStream, SocketStream)> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))?;
+    let write = SocketStream::connect(listener.local_addr()?)?;
+    let (read, _) = listener.accept()?;
+    // Without this, Nagle's algorithm holds back single-byte wakes.
+    write.set_nodelay(true)?;
+    Ok((read, write))
+}
+
+#[cfg(unix)]
+fn raw_socket_handle(socket: &impl AsRawFd) -> RawSocketHandle {
+    socket.as_raw_fd()
+}
+
+#[cfg(windows)]
+fn raw_socket_handle(socket: &impl AsRawSocket) -> RawSocketHandle {
+    socket.as_raw_socket()
+}
+
+/// Record a socket in the FD accounting (a no-op for Windows SOCKETs)
+#[cfg(unix)]
+fn track_socket<T: AsRawFd>(socket: T) -> TrackedFd<T> {
+    TrackedFd::new(socket, "socket")
+}
+
+#[cfg(windows)]
+fn track_socket<T>(socket: T) -> T {
+    socket
+}
+
+#[cfg(unix)]
+type TcpEnd = TrackedFd<std::net::TcpStream>;
+#[cfg(windows)]
+type TcpEnd = std::net::TcpStream;
+#[cfg(unix)]
+type UdpEnd = TrackedFd<std::net::UdpSocket>;
+#[cfg(windows)]
+type UdpEnd = std::net::UdpSocket;
+#[cfg(unix)]
+type DgramEnd = TrackedFd<std::os::unix::net::UnixDatagram>;
+
+/// How a SocketWaker's two ends are connected
+#[derive(Clone, Copy)]
+enum SocketTransport {
+    /// A Unix socketpair (a loopback TCP connection on Windows)
+    Pair,
+    /// A loopback TCP connection with Nagle's algorithm disabled
+    Tcp,
+    /// Two connected loopback UDP sockets, one datagram per wake
+    Udp,
+    /// A Unix datagram socketpair, one datagram per wake. Sends never block:
+    /// when the receive queue is full the wake is dropped and counted.
+    #[cfg(unix)]
+    Dgram,
+}
+
+impl SocketTransport {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "pair" => Ok(Self::Pair),
+            "tcp" => Ok(Self::Tcp),
+            "udp" => Ok(Self::Udp),
+            #[cfg(unix)]
+            "dgram" => Ok(Self::Dgram),
+            #[cfg(not(unix))]
+            "dgram" => Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                "the dgram transport is only available on Unix",
+            )),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "transport must be 'pair', 'tcp', 'udp' or 'dgram', got {other:?}"
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Pair => "pair",
+            Self::Tcp => "tcp",
+            Self::Udp => "udp",
+            #[cfg(unix)]
+            Self::Dgram => "dgram",
+        }
+    }
+
+    /// Whether each wake is a separate message
+    fn is_datagram(self) -> bool {
+        match self {
+            Self::Pair | Self::Tcp => false,
+            Self::Udp => true,
+            #[cfg(unix)]
+            Self::Dgram => true,
+        }
+    }
+
+    /// Whether a wake that doesn't fit in the receive queue is dropped and
+    /// counted. Stream writes block instead, and loopback UDP drops silently.
+    fn counts_drops(self) -> bool {
+        match self {
+            Self::Pair | Self::Tcp | Self::Udp => false,
+            #[cfg(unix)]
+            Self::Dgram => true,
+        }
+    }
+
+    /// Create the (read, write) ends
+    fn connect(self) -> std::io::Result<(SocketEnd, SocketEnd)> {
+        let loopback = std::net::Ipv4Addr::LOCALHOST;
+        match self {
+            Self::Pair => {
+                let (read, write) = socket_pair()?;
+                Ok((SocketEnd::Pair(read), SocketEnd::Pair(write)))
+            }
+            Self::Tcp => {
+                let listener = std::net::TcpListener::bind((loopback, 0))?;
+                let write = std::net::TcpStream::connect(listener.local_addr()?)?;
+                let (read, _) = listener.accept()?;
+                // Without this, Nagle's algorithm holds back single-byte wakes.
+                write.set_nodelay(true)?;
+                Ok((
+                    SocketEnd::Tcp(track_socket(read)),
+                    SocketEnd::Tcp(track_socket(write)),
+                ))
+            }
+            Self::Udp => {
+                let read = std::net::UdpSocket::bind((loopback, 0))?;
+                let write = std::net::UdpSocket::bind((loopback, 0))?;
+                write.connect(read.local_addr()?)?;
+                // Only accept datagrams from the write end
+                read.connect(write.local_addr()?)?;
+                Ok((
+                    SocketEnd::Udp(track_socket(read)),
+                    SocketEnd::Udp(track_socket(write)),
+                ))
+            }
+            #[cfg(unix)]
+            Self::Dgram => {
+                let (read, write) = std::os::unix::net::UnixDatagram::pair()?;
+                write.set_nonblocking(true)?;
+                Ok((
+                    SocketEnd::Dgram(track_socket(read)),
+                    SocketEnd::Dgram(track_socket(write)),
+                ))
+            }
+        }
+    }
+}
+
+/// One end of a SocketWaker's connection
+enum SocketEnd {
+    Pair(SocketStream),
+    Tcp(TcpEnd),
+    Udp(UdpEnd),
+    #[cfg(unix)]
+    Dgram(DgramEnd),
+}
+
+impl SocketEnd {
+    fn raw_handle(&self) -> RawSocketHandle {
+        match self {
+            Self::Pair(stream) => raw_socket_handle(stream),
+            Self::Tcp(stream) => raw_socket_handle(stream),
+            Self::Udp(socket) => raw_socket_handle(socket),
+            #[cfg(unix)]
+            Self::Dgram(socket) => raw_socket_handle(socket),
+        }
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> std::io::Result<()> {
+        match self {
+            Self::Pair(stream) => stream.set_nonblocking(nonblocking),
+            Self::Tcp(stream) => stream.set_nonblocking(nonblocking),
+            Self::Udp(socket) => socket.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            Self::Dgram(socket) => socket.set_nonblocking(nonblocking),
+        }
+    }
+
+    fn send(&self, data: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Pair(stream) => (&*stream).write(data),
+            Self::Tcp(stream) => (&*stream).write(data),
+            Self::Udp(socket) => socket.send(data),
+            #[cfg(unix)]
+            Self::Dgram(socket) => socket.send(data),
+        }
+    }
+
+    /// Read up to `buf.len()` bytes, or one datagram
+    fn recv(&self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Pair(stream) => (&*stream).read(buf),
+            Self::Tcp(stream) => (&*stream).read(buf),
+            Self::Udp(socket) => socket.recv(buf),
+            #[cfg(unix)]
+            Self::Dgram(socket) => socket.recv(buf),
+        }
+    }
+}
+
+/// Failed sends of a SocketWaker, shared with its handles
+#[derive(Default)]
+struct SocketSendStats {
+    /// Datagrams discarded because the receive queue was full
+    dropped: AtomicU64,
+    errors: AtomicU64,
+}
+
+/// A waker that writes a byte to a connected pair of sockets: by default a
+/// socketpair, or a loopback TCP connection or UDP sockets to see what a
+/// plain local socket costs, or a Unix datagram pair that delivers exactly one
+/// message per wake. Works with SelectorEventLoop on every platform
+/// via `add_reader`; under Windows' ProactorEventLoop, wrap the read handle
+/// in a `socket.socket` and await `loop.sock_recv` instead.
+#[pyclass]
+struct SocketWaker {
+    transport: SocketTransport,
+    read: SocketEnd,
+    write: Arc<SocketEnd>,
+    stats: Arc<SocketSendStats>,
+    stamps: Arc<WakeTimestamps>,
+}
+
+#[pymethods]
+impl SocketWaker {
+    #[new]
+    #[pyo3(signature = (transport="pair"))]
+    fn new(transport: &str) -> PyResult<Self> {
+        let transport = SocketTransport::parse(transport)?;
+        let (read, write) = transport.connect()?;
+        read.set_nonblocking(true)?;
+
+        Ok(Self {
+            transport,
+            read,
+            write: Arc::new(write),
+            stats: Arc::new(SocketSendStats::default()),
+            stamps: WakeTimestamps::new(),
+        })
+    }
+
+    /// `"pair"`, `"tcp"`, `"udp"` or `"dgram"`
+    #[getter]
+    fn transport(&self) -> &'static str {
+        self.transport.name()
+    }
+
+    /// Get the read socket handle for registering with the event loop
+    fn get_read_fd(&self) -> RawSocketHandle {
+        self.read.raw_handle()
+    }
+
+    /// Drain any pending wakes from the socket (call this in the callback);
+    /// returns how many: bytes on a stream, messages on a datagram transport
+    fn drain(&self) -> PyResult<u64> {
+        trace_scope!("socket.drain");
+        let mut buf = [0u8; 64];
+        let mut wakes = 0;
+        loop {
+            match self.read.recv(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) if self.transport.is_datagram() => wakes += 1,
+                Ok(n) => wakes += n as u64,
+            }
+        }
+        Ok(wakes)
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+impl SocketWaker {
+    fn handle(&self) -> SocketWakerHandle {
+        SocketWakerHandle {
+            transport: self.transport,
+            write: self.write.clone(),
+            stats: self.stats.clone(),
+            stamps: self.stamps.clone(),
+        }
+    }
+}
+
+/// Holder for the write end of the socket pair that can be sent across threads
+struct SocketWakerHandle {
+    transport: SocketTransport,
+    write: Arc<SocketEnd>,
+    stats: Arc<SocketSendStats>,
+    stamps: Arc<WakeTimestamps>,
+}
+
+impl SocketWakerHandle {
+    fn wake(&self) {
+        trace_scope!("socket.wake");
+        self.stamps.stamp();
+        loop {
+            note_syscalls(1);
+            match self.write.send(&[1u8]) {
+                Ok(_) => return,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                Err(e) => {
+                    self.stats.errors.fetch_add(1, Ordering::Relaxed);
+                    report_os_error(e);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Create a socket-based waker over `transport`: `"pair"` (a socketpair, or
+/// loopback TCP on Windows), `"tcp"`, `"udp"` or `"dgram"` (Unix)
+#[pyfunction]
+#[pyo3(signature = (transport="pair"))]
+fn create_socket_waker(transport: &str) -> PyResult<SocketWaker> {
+    SocketWaker::new(transport)
+}
+
+// =============================================================================
+// Approach 5: kqueue EVFILT_USER wakeup (macOS only, no GIL acquisition on Rust side)
+// =============================================================================
+
+/// Identifier of the single EVFILT_USER event registered on each kqueue.
+#[cfg(target_os = "macos")]
+const KQUEUE_WAKE_IDENT: libc::uintptr_t = 1;
+
+/// Build a `kevent` for the waker's EVFILT_USER event.
+#[cfg(target_os = "macos")]
+fn kqueue_user_event(flags: u16, fflags: u32) -> libc::kevent {
+    libc::kevent {
+        ident: KQUEUE_WAKE_IDENT,
+        filter: libc::EVFILT_USER,
+        flags,
+        fflags,
+        data: 0,
+        udata: std::ptr::null_mut(),
+    }
+}
+
+/// A waker that triggers a user event on a kqueue. The kqueue FD itself is
+/// readable while the event is pending, so Python registers it with add_reader
+/// like any other FD. No pipe buffer is involved.
+#[cfg(target_os = "macos")]
+#[pyclass]
+struct KqueueUserWaker {
+    /// Shared with every handle, so the kqueue stays open while producers
+    /// can still trigger it
+    kq: Arc<TrackedFd>,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+}
+
+#[cfg(target_os = "macos")]
+#[pymethods]
+impl KqueueUserWaker {
+    #[new]
+    fn new() -> PyResult<Self> {
+        let kq = unsafe { libc::kqueue() };
+        if kq < 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyOSError, _>(
+                "Failed to create kqueue",
+            ));
+        }
+        let kq = TrackedFd::new(unsafe { OwnedFd::from_raw_fd(kq) }, "kqueue");
+
+        // EV_CLEAR resets the event once it has been retrieved by drain()
+        let change = kqueue_user_event(libc::EV_ADD | libc::EV_CLEAR, 0);
+        let result = unsafe {
+            libc::kevent(
+                kq.as_raw_fd(),
+                &change,
+                1,
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null(),
+            )
+        };
+        if result < 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyOSError, _>(
+                "Failed to register EVFILT_USER event",
+            ));
+        }
+
+        Ok(Self {
+            kq: Arc::new(kq),
+            stamps: WakeTimestamps::new(),
+            errors: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Get the kqueue FD for registering with the event loop
+    fn get_read_fd(&self) -> RawFd {
+        self.kq.as_raw_fd()
+    }
+
+    /// Retrieve any pending user events (call this in the callback); returns
+    /// how many were retrieved. `EV_CLEAR` folds every trigger since the last
+    /// drain into one event, so this is 1 or 0.
+    fn drain(&self) -> PyResult<usize> {
+        trace_scope!("kqueue.drain");
+        let mut events = [kqueue_user_event(0, 0); 8];
+        let timeout = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        let mut retrieved = 0;
+        loop {
+            let result = unsafe {
+                libc::kevent(
+                    self.kq.as_raw_fd(),
+                    std::ptr::null(),
+                    0,
+                    events.as_mut_ptr(),
+                    events.len() as libc::c_int,
+                    &timeout,
+                )
+            };
+            if result <= 0 {
+                break;
+            }
+            retrieved += result as usize;
+        }
+        Ok(retrieved)
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl KqueueUserWaker {
+    fn handle(&self) -> KqueueUserWakerHandle {
+        KqueueUserWakerHandle {
+            kq: self.kq.clone(),
+            stamps: self.stamps.clone(),
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+/// Holder for the kqueue FD that can be sent across threads
+#[cfg(target_os = "macos")]
+struct KqueueUserWakerHandle {
+    kq: Arc<TrackedFd>,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+}
+
+#[cfg(target_os = "macos")]
+impl KqueueUserWakerHandle {
+    fn wake(&self) {
+        trace_scope!("kqueue.wake");
+        self.stamps.stamp();
+        let change = kqueue_user_event(0, libc::NOTE_TRIGGER);
+        note_syscalls(1);
+        let result = unsafe {
+            libc::kevent(
+                self.kq.as_raw_fd(),
+                &change,
+                1,
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null(),
+            )
+        };
+        if result < 0 {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+            report_os_error(std::io::Error::last_os_error());
+        }
+    }
+}
+
+/// Create a kqueue EVFILT_USER waker
+#[cfg(target_os = "macos")]
+#[pyfunction]
+fn create_kqueue_waker() -> PyResult<KqueueUserWaker> {
+    KqueueUserWaker::new()
+}
+
+// =============================================================================
+// Approach 6: coalescing FD wakeup (pipe, redundant writes skipped)
+// =============================================================================
+
+/// Write counters shared by a CoalescingFdWaker and its handles
+#[cfg(unix)]
+#[derive(Default)]
+struct CoalescingStats {
+    writes: AtomicU64,
+    suppressed: AtomicU64,
+    drains: AtomicU64,
+}
+
+/// A pipe waker that only writes when transitioning from idle to pending.
+/// While a wake is pending (not yet drained), further wakes are suppressed and
+/// counted instead of each costing a write() syscall.
+#[cfg(unix)]
+#[pyclass]
+struct CoalescingFdWaker {
+    inner: FdWaker,
+    pending: Arc<AtomicBool>,
+    stats: Arc<CoalescingStats>,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl CoalescingFdWaker {
+    #[new]
+    fn new() -> PyResult<Self> {
+        Ok(Self {
+            inner: create_fd_waker()?,
+            pending: Arc::new(AtomicBool::new(false)),
+            stats: Arc::new(CoalescingStats::default()),
+        })
+    }
+
+    /// Get the read file descriptor for registering with the event loop
+    fn get_read_fd(&self) -> PyResult<RawFd> {
+        self.inner.get_read_fd()
+    }
+
+    /// Drain the pipe, then return to idle (call this in the callback).
+    /// Clearing after the drain means a wake racing with it is coalesced into
+    /// the current callback rather than leaving the flag set with an empty pipe.
+    /// Returns 1 if a wake was pending, else 0: only the idle-to-pending wake
+    /// writes, so the pipe never holds more than one token.
+    fn drain(&self) -> PyResult<usize> {
+        let drained = self.inner.drain()?;
+        self.pending.store(false, Ordering::SeqCst);
+        self.stats.drains.fetch_add(1, Ordering::Relaxed);
+        Ok(drained)
+    }
+
+    /// Counters: pipe writes performed, wakes suppressed while pending, and drains
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        stats.set_item("writes", self.stats.writes.load(Ordering::Relaxed))?;
+        stats.set_item("suppressed", self.stats.suppressed.load(Ordering::Relaxed))?;
+        stats.set_item("drains", self.stats.drains.load(Ordering::Relaxed))?;
+        Ok(stats)
+    }
+
+    /// Monotonic ns at which the most recent pipe write started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.inner.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent pipe writes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.inner.stamps.snapshot()
+    }
+}
+
+#[cfg(unix)]
+impl CoalescingFdWaker {
+    fn handle(&self) -> PyResult<CoalescingFdWakerHandle> {
+        Ok(CoalescingFdWakerHandle {
+            inner: self.inner.handle()?,
+            pending: self.pending.clone(),
+            stats: self.stats.clone(),
+        })
+    }
+}
+
+/// Holder for the coalescing waker's write side that can be sent across threads
+#[cfg(unix)]
+struct CoalescingFdWakerHandle {
+    inner: FdWakerHandle,
+    pending: Arc<AtomicBool>,
+    stats: Arc<CoalescingStats>,
+}
+
+#[cfg(unix)]
+impl CoalescingFdWakerHandle {
+    fn wake(&self) {
+        if self.pending.swap(true, Ordering::SeqCst) {
+            self.stats.suppressed.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.inner.wake();
+            self.stats.writes.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+// =============================================================================
+// Approach 7: batched call_soon_threadsafe (one GIL acquisition per N wakes)
+// =============================================================================
+
+/// A callback waker that amortizes GIL acquisition: wakes are counted on the
+/// Rust side, and only every `batch_size`-th wake acquires the GIL to schedule
+/// `callback(count)` for the whole batch. Call sites flush any partial batch
+/// when they finish. Like CallbackWaker, it holds the loop weakly.
+#[pyclass]
+struct BatchedCallbackWaker {
+    callback: PyObject,
+    event_loop: Py<pyo3::types::PyWeakrefReference>,
+    batch_size: u64,
+    pending: Arc<AtomicU64>,
+    batches: Arc<AtomicU64>,
+    stamps: Arc<WakeTimestamps>,
+    gil: Arc<GilStats>,
+}
+
+#[pymethods]
+impl BatchedCallbackWaker {
+    #[new]
+    fn new(
+        py: Python<'_>,
+        callback: PyObject,
+        event_loop: PyObject,
+        batch_size: u64,
+    ) -> PyResult<Self> {
+        if batch_size == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "batch_size must be positive",
+            ));
+        }
+        Ok(Self {
+            callback,
+            event_loop: pyo3::types::PyWeakrefReference::new(event_loop.bind(py))?.unbind(),
+            batch_size,
+            pending: Arc::new(AtomicU64::new(0)),
+            batches: Arc::new(AtomicU64::new(0)),
+            stamps: WakeTimestamps::new(),
+            gil: Arc::new(GilStats::default()),
+        })
+    }
+
+    /// Number of callbacks scheduled so far (one per batch)
+    fn batch_count(&self) -> u64 {
+        self.batches.load(Ordering::Relaxed)
+    }
+
+    /// GIL timings per batch; see CallbackWaker.gil_stats()
+    fn gil_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        self.gil.to_dict(py)
+    }
+
+    fn reset_gil_stats(&self) {
+        self.gil.reset();
+    }
+
+    /// Monotonic ns at which the most recent batch flush started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent batch flushes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+impl BatchedCallbackWaker {
+    fn handle(&self, py: Python<'_>) -> PyResult<BatchedCallbackWakerHandle> {
+        upgrade_loop(self.event_loop.bind(py), "BatchedCallbackWaker")?;
+        Ok(BatchedCallbackWakerHandle {
+            callback: LoopHeld::new(&self.callback, py),
+            event_loop: LoopHeld::new(self.event_loop.as_any(), py),
+            batch_size: self.batch_size,
+            pending: self.pending.clone(),
+            batches: self.batches.clone(),
+            stamps: self.stamps.clone(),
+            gil: self.gil.clone(),
+        })
+    }
+}
+
+/// Holder for the batched callback waker that can be sent across threads.
+/// `event_loop` is the waker's weak reference to the loop.
+struct BatchedCallbackWakerHandle {
+    callback: LoopHeld,
+    event_loop: LoopHeld,
+    batch_size: u64,
+    pending: Arc<AtomicU64>,
+    batches: Arc<AtomicU64>,
+    stamps: Arc<WakeTimestamps>,
+    gil: Arc<GilStats>,
+}
+
+impl Drop for BatchedCallbackWakerHandle {
+    fn drop(&mut self) {
+        release_on_loop(
+            &mut self.event_loop,
+            "call_soon_threadsafe",
+            [&mut self.callback],
+        );
+    }
+}
+
+impl BatchedCallbackWakerHandle {
+    /// Count a wake; the wake that completes a batch schedules the callback
+    fn wake(&self) {
+        if self.pending.fetch_add(1, Ordering::AcqRel) + 1 >= self.batch_size {
+            self.flush();
+        }
+    }
+
+    /// Schedule `callback(count)` for all pending wakes, if any
+    fn flush(&self) {
+        let count = self.pending.swap(0, Ordering::AcqRel);
+        if count == 0 {
+            return;
+        }
+        self.stamps.stamp();
+        let wait_start = Instant::now();
+        // This ACQUIRES THE GIL from the Rust thread, once per batch
+        Python::with_gil(|py| {
+            let acquired = Instant::now();
+            let scheduled = self
+                .event_loop
+                .downcast_bound(py)
+                .map_err(PyErr::from)
+                .and_then(|event_loop| upgrade_loop(event_loop, "BatchedCallbackWaker"))
+                .and_then(|event_loop| {
+                    event_loop.call_method1("call_soon_threadsafe", (&*self.callback, count))
+                });
+            if let Err(err) = scheduled {
+                report_python_error(err);
+            }
+            self.gil.record(acquired - wait_start, acquired.elapsed());
+        });
+        self.batches.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// =============================================================================
+// Approach 8: Trio run_sync_soon wakeup (acquires GIL on Rust side)
+// =============================================================================
+
+/// A waker for Trio: calls `token.run_sync_soon(callback)` on a TrioToken
+/// (from `trio.lowlevel.current_trio_token()`). Like CallbackWaker, this DOES
+/// acquire the GIL on the Rust side.
+#[pyclass]
+struct TrioWaker {
+    callback: PyObject,
+    token: PyObject,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+    gil: Arc<GilStats>,
+}
+
+#[pymethods]
+impl TrioWaker {
+    #[new]
+    fn new(callback: PyObject, token: PyObject) -> Self {
+        Self {
+            callback,
+            token,
+            stamps: WakeTimestamps::new(),
+            errors: Arc::new(AtomicU64::new(0)),
+            gil: Arc::new(GilStats::default()),
+        }
+    }
+
+    /// GIL timings across all wakes so far, as for `CallbackWaker.gil_stats()`
+    fn gil_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        self.gil.to_dict(py)
+    }
+
+    fn reset_gil_stats(&self) {
+        self.gil.reset();
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+impl TrioWaker {
+    fn handle(&self, py: Python<'_>) -> TrioWakerHandle {
+        TrioWakerHandle {
+            callback: LoopHeld::new(&self.callback, py),
+            token: LoopHeld::new(&self.token, py),
+            stamps: self.stamps.clone(),
+            errors: self.errors.clone(),
+            gil: self.gil.clone(),
+        }
+    }
+}
+
+/// Holder for the Trio waker that producer threads carry. Dropped off the
+/// Trio thread, it releases its callback there through the token.
+struct TrioWakerHandle {
+    callback: LoopHeld,
+    token: LoopHeld,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+    gil: Arc<GilStats>,
+}
+
+impl Drop for TrioWakerHandle {
+    fn drop(&mut self) {
+        release_on_loop(&mut self.token, "run_sync_soon", [&mut self.callback]);
+    }
+}
+
+impl TrioWakerHandle {
+    fn wake(&self) {
+        // Stamp before the GIL wait so it counts towards wake latency
+        self.stamps.stamp();
+        let wait_start = Instant::now();
+        Python::with_gil(|py| {
+            let acquired = Instant::now();
+            // Fails with RunFinishedError once the Trio run has exited
+            if let Err(err) = self
+                .token
+                .call_method1(py, "run_sync_soon", (&*self.callback,))
+            {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+                report_python_error(err);
+            }
+            self.gil.record(acquired - wait_start, acquired.elapsed());
+        });
+    }
+}
+
+/// Create a Trio waker
+#[pyfunction]
+fn create_trio_waker(callback: PyObject, token: PyObject) -> TrioWaker {
+    TrioWaker::new(callback, token)
+}
+
+// =============================================================================
+// Approach 9: gevent hub async watcher (acquires GIL on Rust side)
+// =============================================================================
+
+/// A waker for gevent: an `async_` watcher on the hub's loop, whose `send()`
+/// may be called from any thread. The watcher runs `callback()` in the hub.
+/// Like CallbackWaker, this DOES acquire the GIL on the Rust side.
+///
+/// libev/libuv coalesce async sends, so wakes sent before the hub gets to
+/// run produce a single callback. The started watcher keeps the hub's loop
+/// alive until `close()`.
+#[pyclass]
+struct GeventWaker {
+    watcher: PyObject,
+    hub_loop: PyObject,
+    closed: Arc<AtomicBool>,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+    gil: Arc<GilStats>,
+}
+
+#[pymethods]
+impl GeventWaker {
+    #[new]
+    #[pyo3(signature = (callback, hub=None))]
+    fn new(py: Python<'_>, callback: PyObject, hub: Option<Bound<'_, PyAny>>) -> PyResult<Self> {
+        let hub = match hub {
+            Some(hub) => hub,
+            None => py.import("gevent")?.call_method0("get_hub")?,
+        };
+        let hub_loop = hub.getattr("loop")?;
+        let watcher = hub_loop.call_method0("async_")?;
+        watcher.call_method1("start", (callback,))?;
+        Ok(Self {
+            watcher: watcher.unbind(),
+            hub_loop: hub_loop.unbind(),
+            closed: Arc::new(AtomicBool::new(false)),
+            stamps: WakeTimestamps::new(),
+            errors: Arc::new(AtomicU64::new(0)),
+            gil: Arc::new(GilStats::default()),
+        })
+    }
+
+    /// Stop and close the watcher; later wakes are discarded. Idempotent.
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        if !self.closed.swap(true, Ordering::SeqCst) {
+            self.watcher.call_method0(py, "stop")?;
+            self.watcher.call_method0(py, "close")?;
+        }
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.close(py)?;
+        Ok(false)
+    }
+
+    /// GIL timings across all wakes so far, as for `CallbackWaker.gil_stats()`
+    fn gil_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        self.gil.to_dict(py)
+    }
+
+    fn reset_gil_stats(&self) {
+        self.gil.reset();
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+impl GeventWaker {
+    fn handle(&self, py: Python<'_>) -> PyResult<GeventWakerHandle> {
+        if self.is_closed() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "GeventWaker is closed",
+            ));
+        }
+        Ok(GeventWakerHandle {
+            watcher: LoopHeld::new(&self.watcher, py),
+            hub_loop: LoopHeld::new(&self.hub_loop, py),
+            closed: self.closed.clone(),
+            stamps: self.stamps.clone(),
+            errors: self.errors.clone(),
+            gil: self.gil.clone(),
+        })
+    }
+}
+
+/// Holder for the gevent watcher that producer threads carry. Dropped off the
+/// hub's thread, it releases the watcher there through the hub's loop.
+struct GeventWakerHandle {
+    watcher: LoopHeld,
+    hub_loop: LoopHeld,
+    closed: Arc<AtomicBool>,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+    gil: Arc<GilStats>,
+}
+
+impl Drop for GeventWakerHandle {
+    fn drop(&mut self) {
+        release_on_loop(
+            &mut self.hub_loop,
+            "run_callback_threadsafe",
+            [&mut self.watcher],
+        );
+    }
+}
+
+impl GeventWakerHandle {
+    fn wake(&self) {
+        // Stamp before the GIL wait so it counts towards wake latency
+        self.stamps.stamp();
+        let wait_start = Instant::now();
+        Python::with_gil(|py| {
+            let acquired = Instant::now();
+            // Checked under the GIL, which close() also holds
+            if !self.closed.load(Ordering::SeqCst) {
+                if let Err(err) = self.watcher.call_method0(py, "send") {
+                    self.errors.fetch_add(1, Ordering::Relaxed);
+                    report_python_error(err);
+                }
+            }
+            self.gil.record(acquired - wait_start, acquired.elapsed());
+        });
+    }
+}
+
+/// Create a gevent waker on `hub` (by default the current thread's hub)
+#[pyfunction]
+#[pyo3(signature = (callback, hub=None))]
+fn create_gevent_waker(
+    py: Python<'_>,
+    callback: PyObject,
+    hub: Option<Bound<'_, PyAny>>,
+) -> PyResult<GeventWaker> {
+    GeventWaker::new(py, callback, hub)
+}
+
+// =============================================================================
+// Approach 10: signal.set_wakeup_fd (no GIL acquisition on Rust side)
+// =============================================================================
+
+/// A waker that raises a signal at the process. CPython's C-level handler
+/// writes the signal number to the FD installed with `signal.set_wakeup_fd`,
+/// which is how `loop.add_signal_handler` wakes asyncio's selector. The Rust
+/// thread only calls kill(2); it does NOT acquire the GIL.
+///
+/// The signal must have a Python-level handler (e.g. from
+/// `loop.add_signal_handler`); otherwise its default action would apply, so
+/// wakes refuse to start. Standard signals don't queue: several raised before
+/// the handler runs are delivered once.
+#[cfg(unix)]
+#[pyclass]
+struct SignalWaker {
+    #[pyo3(get)]
+    signum: libc::c_int,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl SignalWaker {
+    #[new]
+    #[pyo3(signature = (signum=libc::SIGUSR1))]
+    fn new(signum: libc::c_int) -> PyResult<Self> {
+        if signum <= 0 || signum == libc::SIGKILL || signum == libc::SIGSTOP {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "{signum} is not a catchable signal"
+            )));
+        }
+        Ok(Self {
+            signum,
+            stamps: WakeTimestamps::new(),
+            errors: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Whether the signal currently has a Python-level handler, so raising it
+    /// reaches the wakeup FD instead of its default action
+    fn is_handled(&self, py: Python<'_>) -> PyResult<bool> {
+        let signal = py.import("signal")?;
+        let handler = signal.call_method1("getsignal", (self.signum,))?;
+        Ok(!handler.is_none()
+            && !handler.eq(signal.getattr("SIG_DFL")?)?
+            && !handler.eq(signal.getattr("SIG_IGN")?)?)
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+#[cfg(unix)]
+impl SignalWaker {
+    fn handle(&self, py: Python<'_>) -> PyResult<SignalWakerHandle> {
+        if !self.is_handled(py)? {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "signal {} has no Python handler; install one with loop.add_signal_handler() \
+                 or signal.signal() first",
+                self.signum
+            )));
+        }
+        Ok(SignalWakerHandle {
+            signum: self.signum,
+            stamps: self.stamps.clone(),
+            errors: self.errors.clone(),
+        })
+    }
+}
+
+/// Holder for the signal number that can be sent across threads
+#[cfg(unix)]
+struct SignalWakerHandle {
+    signum: libc::c_int,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+}
+
+#[cfg(unix)]
+impl SignalWakerHandle {
+    fn wake(&self) {
+        self.stamps.stamp();
+        // Process-directed: the kernel picks a thread that doesn't block the
+        // signal, and CPython's handler writes the wakeup FD from any thread
+        note_syscalls(2);
+        if unsafe { libc::kill(libc::getpid(), self.signum) } < 0 {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+            report_os_error(std::io::Error::last_os_error());
+        }
+    }
+}
+
+/// Create a signal waker
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (signum=libc::SIGUSR1))]
+fn create_signal_waker(signum: libc::c_int) -> PyResult<SignalWaker> {
+    SignalWaker::new(signum)
+}
+
+// =============================================================================
+// Approach 11: Py_AddPendingCall (no GIL acquisition on Rust side)
+// =============================================================================
+
+/// State shared by a PendingCallWaker, its handles, and queued calls
+struct PendingCallState {
+    callback: PyObject,
+    /// Calls accepted into the interpreter's pending-call queue
+    queued: AtomicU64,
+    /// Calls refused because the queue was full
+    dropped: AtomicU64,
+    /// Calls the main thread has run
+    ran: AtomicU64,
+}
+
+/// Runs on the main thread, with the GIL held, when the eval loop next checks
+/// for pending calls. `arg` is a reference to the state leaked by `wake()`.
+extern "C" fn run_pending_call(arg: *mut std::ffi::c_void) -> std::ffi::c_int {
+    let state = unsafe { Arc::from_raw(arg as *const PendingCallState) };
+    let py = unsafe { Python::assume_gil_acquired() };
+    state.ran.fetch_add(1, Ordering::Relaxed);
+    if let Err(err) = state.callback.call0(py) {
+        // Returning -1 would raise the error in whatever code was interrupted
+        err.write_unraisable(py, Some(state.callback.bind(py)));
+    }
+    0
+}
+
+/// A waker that queues `callback()` with `Py_AddPendingCall`, the mechanism
+/// CPython uses to run signal handlers. The Rust thread does NOT acquire the
+/// GIL; the main thread runs the call the next time its eval loop checks
+/// for pending work.
+///
+/// A main thread blocked in a system call (an idle event loop in select())
+/// does not check, so pending calls alone don't wake a sleeping loop. On
+/// Python 3.11 a call queued from a non-Python thread is only noticed once
+/// the main thread next re-acquires the GIL. The queue is small (32 entries
+/// before Python 3.12); calls beyond it are dropped.
+#[pyclass]
+struct PendingCallWaker {
+    state: Arc<PendingCallState>,
+    stamps: Arc<WakeTimestamps>,
+}
+
+#[pymethods]
+impl PendingCallWaker {
+    #[new]
+    fn new(callback: PyObject) -> Self {
+        Self {
+            state: Arc::new(PendingCallState {
+                callback,
+                queued: AtomicU64::new(0),
+                dropped: AtomicU64::new(0),
+                ran: AtomicU64::new(0),
+            }),
+            stamps: WakeTimestamps::new(),
+        }
+    }
+
+    /// Pending-call outcomes: `queued`, `dropped` (queue full) and `ran`
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        let counters = [
+            ("queued", &self.state.queued),
+            ("dropped", &self.state.dropped),
+            ("ran", &self.state.ran),
+        ];
+        for (name, counter) in counters {
+            stats.set_item(name, counter.load(Ordering::Relaxed))?;
+        }
+        Ok(stats)
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+impl PendingCallWaker {
+    fn handle(&self) -> PendingCallWakerHandle {
+        PendingCallWakerHandle {
+            state: self.state.clone(),
+            stamps: self.stamps.clone(),
+        }
+    }
+}
+
+/// Holder for the pending-call state that can be sent across threads
+struct PendingCallWakerHandle {
+    state: Arc<PendingCallState>,
+    stamps: Arc<WakeTimestamps>,
+}
+
+impl PendingCallWakerHandle {
+    fn wake(&self) {
+        self.stamps.stamp();
+        let arg = Arc::into_raw(self.state.clone()) as *mut std::ffi::c_void;
+        // Py_AddPendingCall may be called without the GIL, from any thread
+        if unsafe { pyo3::ffi::Py_AddPendingCall(Some(run_pending_call), arg) } == 0 {
+            self.state.queued.fetch_add(1, Ordering::Relaxed);
+        } else {
+            drop(unsafe { Arc::from_raw(arg as *const PendingCallState) });
+            self.state.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Create a pending-call waker
+#[pyfunction]
+fn create_pending_call_waker(callback: PyObject) -> PendingCallWaker {
+    PendingCallWaker::new(callback)
+}
+
+// =============================================================================
+// Approach 12: cross-process wake over a Unix datagram socket (no GIL)
+// =============================================================================
+
+/// The consumer side of a cross-process waker: a non-blocking Unix datagram
+/// socket bound to `path`. Register `get_read_fd()` with `loop.add_reader()`
+/// and call `drain()` in the callback; producers in any process send wakes
+/// through a RemoteWakerClient connected to the same path.
+#[cfg(unix)]
+#[pyclass]
+struct RemoteWaker {
+    #[pyo3(get)]
+    path: std::path::PathBuf,
+    // None once close() has been called
+    socket: Mutex<Option<TrackedFd<std::os::unix::net::UnixDatagram>>>,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl RemoteWaker {
+    /// Bind to `path`, which must not already exist
+    #[new]
+    fn new(path: std::path::PathBuf) -> PyResult<Self> {
+        let socket = std::os::unix::net::UnixDatagram::bind(&path)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            path,
+            socket: Mutex::new(Some(TrackedFd::new(socket, "remote"))),
+        })
+    }
+
+    /// Get the read file descriptor for registering with the event loop
+    fn get_read_fd(&self) -> PyResult<RawFd> {
+        match &*self.socket.lock().unwrap() {
+            Some(socket) => Ok(socket.as_raw_fd()),
+            None => Err(closed_error("RemoteWaker")),
+        }
+    }
+
+    /// Receive all pending wakes (call this in the callback); returns how many
+    fn drain(&self) -> PyResult<u64> {
+        let socket = self.socket.lock().unwrap();
+        let socket = socket.as_ref().ok_or_else(|| closed_error("RemoteWaker"))?;
+        let mut buf = [0u8; 64];
+        let mut wakes = 0;
+        while socket.recv(&mut buf).is_ok() {
+            wakes += 1;
+        }
+        Ok(wakes)
+    }
+
+    /// Close the socket and remove its path; clients' later wakes fail and are
+    /// counted as errors. Remove the reader from the loop first. Idempotent.
+    fn close(&self) -> PyResult<()> {
+        if self.socket.lock().unwrap().take().is_some() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.socket.lock().unwrap().is_none()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RemoteWaker {
+    fn drop(&mut self) {
+        if self.socket.get_mut().unwrap().take().is_some() {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Outcomes of a RemoteWakerClient's sends, shared with its handles
+#[cfg(unix)]
+#[derive(Default)]
+struct RemoteSendStats {
+    sent: AtomicU64,
+    /// Wakes discarded because the server's receive queue was full
+    dropped: AtomicU64,
+    /// Failed sends, e.g. because the server has closed
+    errors: AtomicU64,
+}
+
+/// The producer side of a cross-process waker: a Unix datagram socket
+/// connected to a RemoteWaker's path, usable from any process. Each wake is
+/// one datagram; sends never block, so when the server's queue is full the
+/// wake is dropped and counted.
+#[cfg(unix)]
+#[pyclass]
+struct RemoteWakerClient {
+    socket: Arc<TrackedFd<std::os::unix::net::UnixDatagram>>,
+    stats: Arc<RemoteSendStats>,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl RemoteWakerClient {
+    #[new]
+    fn new(path: std::path::PathBuf) -> PyResult<Self> {
+        let socket = std::os::unix::net::UnixDatagram::unbound()?;
+        socket.connect(&path)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket: Arc::new(TrackedFd::new(socket, "remote")),
+            stats: Arc::new(RemoteSendStats::default()),
+        })
+    }
+
+    /// Send one wake from the calling thread
+    fn wake(&self, py: Python<'_>) {
+        let handle = self.handle();
+        py.allow_threads(|| handle.wake());
+    }
+
+    /// Send outcomes: `sent`, `dropped` (server queue full) and `errors`
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        let counters = [
+            ("sent", &self.stats.sent),
+            ("dropped", &self.stats.dropped),
+            ("errors", &self.stats.errors),
+        ];
+        for (name, counter) in counters {
+            stats.set_item(name, counter.load(Ordering::Relaxed))?;
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(unix)]
+impl RemoteWakerClient {
+    fn handle(&self) -> RemoteWakerClientHandle {
+        RemoteWakerClientHandle {
+            socket: self.socket.clone(),
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+/// Holder for the client socket that can be sent across threads
+#[cfg(unix)]
+struct RemoteWakerClientHandle {
+    socket: Arc<TrackedFd<std::os::unix::net::UnixDatagram>>,
+    stats: Arc<RemoteSendStats>,
+}
+
+#[cfg(unix)]
+impl RemoteWakerClientHandle {
+    fn wake(&self) {
+        loop {
+            note_syscalls(1);
+            match self.socket.send(&[1u8]) {
+                Ok(_) => {
+                    self.stats.sent.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                Err(e) => {
+                    self.stats.errors.fetch_add(1, Ordering::Relaxed);
+                    report_os_error(e);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Approach 13: cross-process wake via shared memory + named semaphore (no GIL)
+// =============================================================================
+
+/// A shared-memory wake counter and the named POSIX semaphore posted with it.
+/// The consumer creates both and unlinks them when dropped.
+#[cfg(unix)]
+struct ShmChannel {
+    name: std::ffi::CString,
+    counter: *mut AtomicU64,
+    sem: *mut libc::sem_t,
+    // Set for the consumer until the names are unlinked
+    owner: AtomicBool,
+}
+
+// The mapping and semaphore are process-shared by design
+#[cfg(unix)]
+unsafe impl Send for ShmChannel {}
+#[cfg(unix)]
+unsafe impl Sync for ShmChannel {}
+
+#[cfg(unix)]
+impl ShmChannel {
+    /// Create (`owner`) or open the shm object and semaphore named `/name`
+    fn new(name: &str, owner: bool) -> PyResult<Self> {
+        if name.is_empty() || name.contains('/') {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "name must be non-empty and contain no '/', got {name:?}"
+            )));
+        }
+        let name = std::ffi::CString::new(format!("/{name}"))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let flags = if owner {
+            libc::O_CREAT | libc::O_EXCL | libc::O_RDWR
+        } else {
+            libc::O_RDWR
+        };
+        let size = std::mem::size_of::<AtomicU64>();
+
+        let fd = unsafe { libc::shm_open(name.as_ptr(), flags, 0o600 as libc::c_uint) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        let unlink_shm = || {
+            if owner {
+                unsafe { libc::shm_unlink(name.as_ptr()) };
+            }
+        };
+        if owner && unsafe { libc::ftruncate(fd.as_raw_fd(), size as libc::off_t) } != 0 {
+            let err = std::io::Error::last_os_error();
+            unlink_shm();
+            return Err(err.into());
+        }
+        let counter = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd.as_raw_fd(),
+                0,
+            )
+        };
+        if counter == libc::MAP_FAILED {
+            let err = std::io::Error::last_os_error();
+            unlink_shm();
+            return Err(err.into());
+        }
+
+        let sem_flags = if owner {
+            libc::O_CREAT | libc::O_EXCL
+        } else {
+            0
+        };
+        let sem = unsafe { libc::sem_open(name.as_ptr(), sem_flags, 0o600 as libc::c_uint, 0) };
+        if sem == libc::SEM_FAILED {
+            let err = std::io::Error::last_os_error();
+            unsafe { libc::munmap(counter, size) };
+            unlink_shm();
+            return Err(err.into());
+        }
+
+        Ok(Self {
+            name,
+            counter: counter as *mut AtomicU64,
+            sem,
+            owner: AtomicBool::new(owner),
+        })
+    }
+
+    /// Remove the names so no new client can open them; existing mappings stay valid
+    fn unlink(&self) {
+        if self.owner.swap(false, Ordering::AcqRel) {
+            unsafe {
+                libc::sem_unlink(self.name.as_ptr());
+                libc::shm_unlink(self.name.as_ptr());
+            }
+        }
+    }
+
+    fn counter(&self) -> &AtomicU64 {
+        unsafe { &*self.counter }
+    }
+
+    /// Count a wake, then post the semaphore
+    fn post(&self) -> bool {
+        self.counter().fetch_add(1, Ordering::Release);
+        note_syscalls(1);
+        unsafe { libc::sem_post(self.sem) == 0 }
+    }
+
+    /// Block until the semaphore is posted; false on error
+    fn wait(&self) -> bool {
+        loop {
+            if unsafe { libc::sem_wait(self.sem) } == 0 {
+                return true;
+            }
+            if std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                return false;
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ShmChannel {
+    fn drop(&mut self) {
+        unsafe {
+            libc::sem_close(self.sem);
+            libc::munmap(
+                self.counter as *mut libc::c_void,
+                std::mem::size_of::<AtomicU64>(),
+            );
+        }
+        self.unlink();
+    }
+}
+
+/// Advance `seen` to the running total `total` and return how much of it is
+/// new. Two drains may read the total in either order; `fetch_max` never
+/// moves `seen` backwards, so each posted wake is counted exactly once.
+#[cfg(unix)]
+fn claim_new(seen: &AtomicU64, total: u64) -> u64 {
+    total.saturating_sub(seen.fetch_max(total, Ordering::Relaxed))
+}
+
+/// The consumer side of a shared-memory waker. Creates a shared counter and a
+/// named semaphore (`/name`); a helper thread blocks on the semaphore and
+/// forwards each post to a pipe, whose `get_read_fd()` Python registers with
+/// `loop.add_reader()`. Producers in any process post through a
+/// ShmWakerClient opened with the same name.
+#[cfg(unix)]
+#[pyclass]
+struct ShmWaker {
+    #[pyo3(get)]
+    name: String,
+    channel: Arc<ShmChannel>,
+    pipe: FdWaker,
+    stop: Arc<AtomicBool>,
+    forwarder: Mutex<Option<std::thread::JoinHandle<()>>>,
+    // Counter value as of the last drain()
+    seen: AtomicU64,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl ShmWaker {
+    /// Create the shared memory and semaphore, which must not already exist
+    #[new]
+    fn new(name: String) -> PyResult<Self> {
+        let channel = Arc::new(ShmChannel::new(&name, true)?);
+        let pipe = create_fd_waker()?;
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let forwarder = {
+            let (channel, stop, handle) = (channel.clone(), stop.clone(), pipe.handle()?);
+            thread_builder("shm_forwarder", None, None).spawn(move || {
+                while channel.wait() && !stop.load(Ordering::Acquire) {
+                    handle.wake();
+                }
+            })?
+        };
+
+        Ok(Self {
+            name,
+            channel,
+            pipe,
+            stop,
+            forwarder: Mutex::new(Some(forwarder)),
+            seen: AtomicU64::new(0),
+        })
+    }
+
+    /// Get the read file descriptor for registering with the event loop
+    fn get_read_fd(&self) -> PyResult<RawFd> {
+        self.pipe.get_read_fd()
+    }
+
+    /// Drain the forwarding pipe (call this in the callback); returns how many
+    /// wakes producers have posted since the last drain
+    fn drain(&self) -> PyResult<u64> {
+        self.pipe.drain()?;
+        let total = self.channel.counter().load(Ordering::Acquire);
+        Ok(claim_new(&self.seen, total))
+    }
+
+    /// Stop the helper thread and unlink the shared memory and semaphore.
+    /// Remove the reader from the loop first. Idempotent.
+    fn close(&self, py: Python<'_>) {
+        let forwarder = self.forwarder.lock().unwrap().take();
+        if let Some(forwarder) = forwarder {
+            self.stop_forwarder(py, forwarder);
+        }
+        self.channel.unlink();
+        self.pipe.close(py);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.pipe.is_closed()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> bool {
+        self.close(py);
+        false
+    }
+}
+
+#[cfg(unix)]
+impl ShmWaker {
+    fn stop_forwarder(&self, py: Python<'_>, forwarder: std::thread::JoinHandle<()>) {
+        self.stop.store(true, Ordering::Release);
+        unsafe { libc::sem_post(self.channel.sem) };
+        py.allow_threads(|| {
+            let _ = forwarder.join();
+        });
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ShmWaker {
+    fn drop(&mut self) {
+        if let Some(forwarder) = self.forwarder.get_mut().unwrap().take() {
+            self.stop.store(true, Ordering::Release);
+            unsafe { libc::sem_post(self.channel.sem) };
+            let _ = forwarder.join();
+        }
+    }
+}
+
+/// The producer side of a shared-memory waker, usable from any process:
+/// each wake increments the shared counter and posts the semaphore.
+#[cfg(unix)]
+#[pyclass]
+struct ShmWakerClient {
+    channel: Arc<ShmChannel>,
+    errors: Arc<AtomicU64>,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl ShmWakerClient {
+    /// Open the shared memory and semaphore created by `ShmWaker(name)`
+    #[new]
+    fn new(name: &str) -> PyResult<Self> {
+        Ok(Self {
+            channel: Arc::new(ShmChannel::new(name, false)?),
+            errors: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Send one wake from the calling thread
+    fn wake(&self) {
+        self.handle().wake();
+    }
+}
+
+#[cfg(unix)]
+impl ShmWakerClient {
+    fn handle(&self) -> ShmWakerClientHandle {
+        ShmWakerClientHandle {
+            channel: self.channel.clone(),
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+/// Holder for the client's mapping that can be sent across threads
+#[cfg(unix)]
+struct ShmWakerClientHandle {
+    channel: Arc<ShmChannel>,
+    errors: Arc<AtomicU64>,
+}
+
+#[cfg(unix)]
+impl ShmWakerClientHandle {
+    fn wake(&self) {
+        if !self.channel.post() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+            report_os_error(std::io::Error::last_os_error());
+        }
+    }
+}
+
+// =============================================================================
+// Approach 14: priority FD wakeup (separate urgent and normal pipes, no GIL)
+// =============================================================================
+
+/// Two pipe wakers, urgent and normal. Python drains the urgent pipe first,
+/// so a saturated normal channel delays urgent wakes by at most the callback
+/// already running rather than by the normal backlog.
+#[cfg(unix)]
+#[pyclass]
+struct PriorityFdWaker {
+    urgent: FdWaker,
+    normal: FdWaker,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl PriorityFdWaker {
+    #[new]
+    fn new() -> PyResult<Self> {
+        Ok(Self {
+            urgent: create_fd_waker()?,
+            normal: create_fd_waker()?,
+        })
+    }
+
+    fn get_urgent_fd(&self) -> PyResult<RawFd> {
+        self.urgent.get_read_fd()
+    }
+
+    fn get_normal_fd(&self) -> PyResult<RawFd> {
+        self.normal.get_read_fd()
+    }
+
+    /// Drain the urgent pipe, then the normal one; returns the wakes
+    /// drained from each as `(urgent, normal)`
+    fn drain(&self) -> PyResult<(usize, usize)> {
+        let urgent = self.urgent.drain()?;
+        Ok((urgent, self.normal.drain()?))
+    }
+
+    /// Register both read FDs with `event_loop.add_reader()`. Whichever
+    /// becomes readable, the handler drains the urgent pipe first and calls
+    /// `on_urgent()` if it held wakes, then does the same for the normal
+    /// pipe and `on_normal()`. With a `recorder`, each urgent drain records
+    /// the time since the most recent urgent wake started.
+    #[pyo3(signature = (event_loop, on_urgent, on_normal=None, recorder=None))]
+    fn register(
+        &self,
+        event_loop: &Bound<'_, PyAny>,
+        on_urgent: PyObject,
+        on_normal: Option<PyObject>,
+        recorder: Option<&LatencyRecorder>,
+    ) -> PyResult<ReaderRegistration> {
+        let (urgent, normal) = (self.urgent.pipe()?, self.normal.pipe()?);
+        let fds = vec![urgent.read.as_raw_fd(), normal.read.as_raw_fd()];
+        let stamps = self.urgent.stamps.clone();
+        let (urgent_stats, normal_stats) = (
+            self.urgent.drain_stats.clone(),
+            self.normal.drain_stats.clone(),
+        );
+        let (urgent_policy, normal_policy) = (self.urgent.drain_policy, self.normal.drain_policy);
+        let recorder = recorder.map(|r| r.recording.clone());
+        let on_readable = pyo3::types::PyCFunction::new_closure(
+            event_loop.py(),
+            None,
+            None,
+            move |args, _kwargs| -> PyResult<()> {
+                let py = args.py();
+                if urgent_stats.drain(&urgent, urgent_policy) > 0 {
+                    if let Some(start) = stamps.last() {
+                        let now = timestamp_clock_source().read_ns();
+                        record_latency(&recorder, now.saturating_sub(start));
+                    }
+                    on_urgent.call0(py)?;
+                }
+                if normal_stats.drain(&normal, normal_policy) > 0 {
+                    if let Some(on_normal) = &on_normal {
+                        on_normal.call0(py)?;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+        for fd in &fds {
+            event_loop.call_method1("add_reader", (fd, &on_readable))?;
+        }
+        Ok(ReaderRegistration {
+            event_loop: Arc::new(Mutex::new(Some(event_loop.clone().unbind()))),
+            fds,
+        })
+    }
+
+    /// Pipe write outcomes of each channel, as `{"urgent": ..., "normal": ...}`
+    /// in the shape of `FdWaker.stats()`
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        stats.set_item("urgent", self.urgent.stats(py)?)?;
+        stats.set_item("normal", self.normal.stats(py)?)?;
+        Ok(stats)
+    }
+
+    /// Monotonic ns at which the most recent urgent wake started, or None
+    fn last_urgent_wake_ns(&self) -> Option<u64> {
+        self.urgent.stamps.last()
+    }
+
+    /// Monotonic ns at which the most recent normal wake started, or None
+    fn last_normal_wake_ns(&self) -> Option<u64> {
+        self.normal.stamps.last()
+    }
+
+    /// Close both pipes. Idempotent; remove the readers from the loop first.
+    fn close(&self, py: Python<'_>) {
+        self.urgent.close(py);
+        self.normal.close(py);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.urgent.is_closed()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> bool {
+        self.close(py);
+        false
+    }
+}
+
+/// Priority-inversion benchmark: send `iterations` urgent wakes, one every
+/// `interval_micros`, while (with `saturate`) a second producer floods the
+/// normal pipe as fast as it can. Register the waker with a recorder to
+/// measure how long urgent wakes wait behind normal work. Returns a BenchTask
+/// for the urgent producer; the flood stops when it finishes.
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (waker, iterations, interval_micros=1000, saturate=true, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+#[allow(clippy::too_many_arguments)]
+fn priority_wakeup_inversion(
+    waker: &PriorityFdWaker,
+    iterations: usize,
+    interval_micros: u64,
+    saturate: bool,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<BenchTask> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let urgent = waker.urgent.handle()?;
+    let normal = waker.normal.handle()?;
+
+    let spec = BenchSpec::new("priority_wakeup_inversion", iterations, &placement)
+        .config("interval_micros", ConfigValue::Int(interval_micros as i64))
+        .config("saturate", ConfigValue::Int(saturate as i64))
+        .drops(waker.urgent.drop_counter());
+
+    Ok(spawn_bench_task(placement, spec, move |task| {
+        let flooding = AtomicBool::new(saturate);
+        std::thread::scope(|scope| {
+            if saturate {
+                let flood = placement
+                    .thread("priority_flood", None)
+                    .spawn_scoped(scope, || {
+                        placement.apply();
+                        while flooding.load(Ordering::Relaxed) {
+                            normal.wake();
+                        }
+                    });
+                // Without the flood there is no inversion to measure
+                if let Err(error) = flood {
+                    report_os_error(error);
+                    return 0;
+                }
+            }
+            let timer = Timer::new(TimerBackend::Sleep, Duration::ZERO);
+            let mut sent = 0;
+            for _ in 0..iterations {
+                if task.is_cancelled() {
+                    break;
+                }
+                timer.sleep(Duration::from_micros(interval_micros));
+                urgent.wake();
+                sent += 1;
+            }
+            flooding.store(false, Ordering::Relaxed);
+            sent
+        })
+    }))
+}
+
+// =============================================================================
+// Approach 15: futex wakeup relayed by a forwarder thread (Linux, no GIL)
+// =============================================================================
+
+/// State shared by a FutexWaker, its handles and its forwarder thread
+#[cfg(target_os = "linux")]
+#[derive(Default)]
+struct FutexState {
+    /// The futex word, bumped by every wake
+    word: std::sync::atomic::AtomicU32,
+    /// Set while the forwarder is blocked (or about to block) in FUTEX_WAIT
+    sleeping: AtomicBool,
+    stop: AtomicBool,
+    posted: AtomicU64,
+    futex_wakes: AtomicU64,
+    forwarded: AtomicU64,
+    errors: AtomicU64,
+}
+
+#[cfg(target_os = "linux")]
+impl FutexState {
+    fn futex(&self, op: libc::c_int, value: u32) -> libc::c_long {
+        unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                self.word.as_ptr(),
+                op | libc::FUTEX_PRIVATE_FLAG,
+                value,
+                std::ptr::null::<libc::timespec>(),
+            )
+        }
+    }
+
+    /// Bump the word; only issue FUTEX_WAKE if the forwarder may be asleep.
+    /// The SeqCst pair with `wait()` guarantees that either the forwarder sees
+    /// the new word before sleeping or this sees `sleeping` set. Clearing the
+    /// flag means one producer wakes a sleeping forwarder and the rest skip
+    /// the syscall until it sleeps again.
+    fn post(&self) {
+        self.posted.fetch_add(1, Ordering::Relaxed);
+        self.word.fetch_add(1, Ordering::SeqCst);
+        if self.sleeping.swap(false, Ordering::SeqCst) {
+            note_syscalls(1);
+            self.futex_wakes.fetch_add(1, Ordering::Relaxed);
+            if self.futex(libc::FUTEX_WAKE, 1) < 0 {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+                report_os_error(std::io::Error::last_os_error());
+            }
+        }
+    }
+
+    /// Block until the word differs from `seen`; returns its new value
+    fn wait(&self, seen: u32) -> u32 {
+        loop {
+            self.sleeping.store(true, Ordering::SeqCst);
+            let current = self.word.load(Ordering::SeqCst);
+            if current == seen {
+                // Returns at once if the word changed since the load
+                self.futex(libc::FUTEX_WAIT, seen);
+            }
+            self.sleeping.store(false, Ordering::SeqCst);
+            if current != seen {
+                return current;
+            }
+        }
+    }
+
+    /// Wake the forwarder unconditionally so it notices `stop`
+    fn shutdown(&self) {
+        self.stop.store(true, Ordering::Release);
+        self.word.fetch_add(1, Ordering::SeqCst);
+        self.futex(libc::FUTEX_WAKE, 1);
+    }
+}
+
+/// A waker whose producers only bump a futex word, skipping the syscall
+/// entirely while the forwarder is awake. A Rust forwarder thread blocks in
+/// FUTEX_WAIT without the GIL and relays to a pipe the event loop watches, so
+/// the producer side avoids both the pipe write and the GIL.
+#[cfg(target_os = "linux")]
+#[pyclass]
+struct FutexWaker {
+    state: Arc<FutexState>,
+    pipe: FdWaker,
+    stamps: Arc<WakeTimestamps>,
+    forwarder: Mutex<Option<std::thread::JoinHandle<()>>>,
+    // Posted count as of the last drain()
+    seen: AtomicU64,
+}
+
+#[cfg(target_os = "linux")]
+#[pymethods]
+impl FutexWaker {
+    #[new]
+    fn new() -> PyResult<Self> {
+        let state = Arc::new(FutexState::default());
+        let pipe = create_fd_waker()?;
+
+        let forwarder = {
+            let (state, handle) = (state.clone(), pipe.handle()?);
+            thread_builder("futex_forwarder", None, None).spawn(move || {
+                let mut seen = 0;
+                loop {
+                    seen = state.wait(seen);
+                    if state.stop.load(Ordering::Acquire) {
+                        break;
+                    }
+                    handle.wake();
+                    state.forwarded.fetch_add(1, Ordering::Relaxed);
+                }
+            })?
+        };
+
+        Ok(Self {
+            state,
+            pipe,
+            stamps: WakeTimestamps::new(),
+            forwarder: Mutex::new(Some(forwarder)),
+            seen: AtomicU64::new(0),
+        })
+    }
+
+    /// Send one wake from the calling thread
+    fn wake(&self) {
+        self.handle().wake();
+    }
+
+    /// Get the read file descriptor for registering with the event loop
+    fn get_read_fd(&self) -> PyResult<RawFd> {
+        self.pipe.get_read_fd()
+    }
+
+    /// Drain the forwarding pipe (call this in the callback); returns how many
+    /// wakes producers have posted since the last drain
+    fn drain(&self) -> PyResult<u64> {
+        self.pipe.drain()?;
+        let total = self.state.posted.load(Ordering::Acquire);
+        Ok(claim_new(&self.seen, total))
+    }
+
+    /// Counters: wakes `posted` by producers, `futex_wakes` they issued (the
+    /// rest found the forwarder awake), and pipe writes `forwarded`
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        let counters = [
+            ("posted", &self.state.posted),
+            ("futex_wakes", &self.state.futex_wakes),
+            ("forwarded", &self.state.forwarded),
+        ];
+        for (name, counter) in counters {
+            stats.set_item(name, counter.load(Ordering::Relaxed))?;
+        }
+        Ok(stats)
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+
+    /// Stop the forwarder thread and close the pipe. Remove the reader from
+    /// the loop first. Idempotent.
+    fn close(&self, py: Python<'_>) {
+        let forwarder = self.forwarder.lock().unwrap().take();
+        if let Some(forwarder) = forwarder {
+            self.state.shutdown();
+            py.allow_threads(|| {
+                let _ = forwarder.join();
+            });
+        }
+        self.pipe.close(py);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.pipe.is_closed()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> bool {
+        self.close(py);
+        false
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl FutexWaker {
+    fn handle(&self) -> FutexWakerHandle {
+        FutexWakerHandle {
+            state: self.state.clone(),
+            stamps: self.stamps.clone(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for FutexWaker {
+    fn drop(&mut self) {
+        if let Some(forwarder) = self.forwarder.get_mut().unwrap().take() {
+            self.state.shutdown();
+            let _ = forwarder.join();
+        }
+    }
+}
+
+/// Holder for the futex state that can be sent across threads
+#[cfg(target_os = "linux")]
+struct FutexWakerHandle {
+    state: Arc<FutexState>,
+    stamps: Arc<WakeTimestamps>,
+}
+
+#[cfg(target_os = "linux")]
+impl FutexWakerHandle {
+    fn wake(&self) {
+        trace_scope!("futex.wake");
+        self.stamps.stamp();
+        self.state.post();
+    }
+}
+
+// =============================================================================
+// Approach 16: threading.Event bridged by run_in_executor (acquires GIL on Rust side)
+// =============================================================================
+
+/// The naive Python baseline: the Rust side calls `event.set()` on a
+/// `threading.Event` (this DOES acquire the GIL), and an executor thread
+/// blocked in `event.wait()` completes a `loop.run_in_executor` future.
+#[pyclass]
+struct ThreadingEventWaker {
+    #[pyo3(get)]
+    event: PyObject,
+    executor: Option<PyObject>,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+    gil: Arc<GilStats>,
+}
+
+#[pymethods]
+impl ThreadingEventWaker {
+    /// `executor` is a `concurrent.futures.Executor` for the waiting thread;
+    /// None uses the loop's default executor
+    #[new]
+    #[pyo3(signature = (executor=None))]
+    fn new(py: Python<'_>, executor: Option<PyObject>) -> PyResult<Self> {
+        Ok(Self {
+            event: py.import("threading")?.call_method0("Event")?.unbind(),
+            executor,
+            stamps: WakeTimestamps::new(),
+            errors: Arc::new(AtomicU64::new(0)),
+            gil: Arc::new(GilStats::default()),
+        })
+    }
+
+    /// Awaitable that completes after the next wake (or at once if the event
+    /// is already set): `event.wait()` then `event.clear()` run in the
+    /// executor via the running loop's `run_in_executor`. Wakes that arrive
+    /// before the clear coalesce into this one.
+    fn wait<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+        self.submit(&event_loop)
+    }
+
+    /// GIL timings across all wakes so far, as for `CallbackWaker.gil_stats()`
+    fn gil_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        self.gil.to_dict(py)
+    }
+
+    fn reset_gil_stats(&self) {
+        self.gil.reset();
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+impl ThreadingEventWaker {
+    fn handle(&self, py: Python<'_>) -> ThreadingEventWakerHandle {
+        ThreadingEventWakerHandle {
+            event: self.event.clone_ref(py),
+            stamps: self.stamps.clone(),
+            errors: self.errors.clone(),
+            gil: self.gil.clone(),
+        }
+    }
+
+    /// Submit wait-then-clear to the executor on `event_loop`
+    fn submit<'py>(&self, event_loop: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let py = event_loop.py();
+        let event = self.event.clone_ref(py);
+        let wait_and_clear = pyo3::types::PyCFunction::new_closure(
+            py,
+            None,
+            None,
+            move |args, _kwargs| -> PyResult<()> {
+                let py = args.py();
+                event.call_method0(py, "wait")?;
+                event.call_method0(py, "clear")?;
+                Ok(())
+            },
+        )?;
+        let executor = self.executor.as_ref().map(|e| e.clone_ref(py));
+        event_loop.call_method1("run_in_executor", (executor, wait_and_clear))
+    }
+}
+
+/// Holder for the event that can be sent across threads
+struct ThreadingEventWakerHandle {
+    event: PyObject,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+    gil: Arc<GilStats>,
+}
+
+impl ThreadingEventWakerHandle {
+    fn wake(&self) {
+        // Stamp before the GIL wait so it counts towards wake latency
+        self.stamps.stamp();
+        let wait_start = Instant::now();
+        Python::with_gil(|py| {
+            let acquired = Instant::now();
+            if let Err(err) = self.event.call_method0(py, "set") {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+                report_python_error(err);
+            }
+            self.gil.record(acquired - wait_start, acquired.elapsed());
+        });
+    }
+}
+
+// =============================================================================
+// Approach 17: resolving an asyncio.Future from a Rust thread (acquires GIL on Rust side)
+// =============================================================================
+
+/// Python callable `(future, result)` that sets the result unless the future
+/// is already done, e.g. cancelled while the completion was in flight
+fn future_resolver(py: Python<'_>) -> PyResult<PyObject> {
+    let resolve =
+        pyo3::types::PyCFunction::new_closure(py, None, None, |args, _kwargs| -> PyResult<()> {
+            let future = args.get_item(0)?;
+            if !future.call_method0("done")?.is_truthy()? {
+                future.call_method1("set_result", (args.get_item(1)?,))?;
+            }
+            Ok(())
+        })?;
+    Ok(resolve.into_any().unbind())
+}
+
+/// Completes futures from Rust threads through the loop each future was
+/// created on, the way most FFI code hands results back to asyncio
+struct FutureCompleter {
+    resolve: PyObject,
+}
+
+impl FutureCompleter {
+    fn new(py: Python<'_>) -> PyResult<Self> {
+        Ok(Self {
+            resolve: future_resolver(py)?,
+        })
+    }
+
+    /// Schedule `future.set_result(result)` on `event_loop`; must hold the GIL
+    fn complete(
+        &self,
+        py: Python<'_>,
+        event_loop: &PyObject,
+        future: PyObject,
+        result: PyObject,
+    ) -> PyResult<()> {
+        event_loop.call_method1(
+            py,
+            "call_soon_threadsafe",
+            (self.resolve.clone_ref(py), future, result),
+        )?;
+        Ok(())
+    }
+}
+
+/// Spawn a Rust OS thread that resolves `future` with `result` after an
+/// optional delay, via `call_soon_threadsafe` on the loop the future was
+/// created on. This ACQUIRES the GIL from the Rust thread. The task counts
+/// 0 wakes if it was cancelled first or the loop was already closed; a
+/// future cancelled in the meantime is left alone.
+#[pyfunction]
+#[pyo3(signature = (future, delay_micros, result=None, pin_cpu=None, rt_priority=None, rt_policy="fifo", timer="sleep"))]
+#[allow(clippy::too_many_arguments)]
+fn complete_future_from_thread(
+    py: Python<'_>,
+    future: Bound<'_, PyAny>,
+    delay_micros: &Bound<'_, PyAny>,
+    result: Option<PyObject>,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+    timer: &str,
+) -> PyResult<BenchTask> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let timer = TimerBackend::parse(timer)?;
+    let delay = draw_delay(delay_micros)?;
+    let event_loop = future.call_method0("get_loop").map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyTypeError, _>("future must be an asyncio.Future")
+    })?;
+    let completer = FutureCompleter::new(py)?;
+    let (event_loop, future) = (event_loop.unbind(), future.unbind());
+    let result = result.unwrap_or_else(|| py.None());
+
+    let spec = BenchSpec::new("complete_future_from_thread", 1, &placement)
+        .delay(delay)
+        .config("timer", ConfigValue::Str(timer.name()));
+
+    Ok(spawn_bench_task(placement, spec, move |task| {
+        task.delay(timer, delay.duration);
+        if task.is_cancelled() {
+            return 0;
+        }
+        Python::with_gil(|py| completer.complete(py, &event_loop, future, result).is_ok() as u64)
+    }))
+}
+
+// =============================================================================
+// Approach 18: asyncio.run_coroutine_threadsafe (acquires GIL on Rust side)
+// =============================================================================
+
+/// `async def call(callback): callback()`, the tiny coroutine scheduled per wake
+fn calling_coroutine(py: Python<'_>) -> PyResult<PyObject> {
+    let module = PyModule::from_code(
+        py,
+        pyo3::ffi::c_str!("async def call(callback):\n    callback()\n"),
+        pyo3::ffi::c_str!("wakerbench_coroutine.py"),
+        pyo3::ffi::c_str!("wakerbench_coroutine"),
+    )?;
+    Ok(module.getattr("call")?.unbind())
+}
+
+/// Wakes by scheduling a coroutine that calls `callback` onto `event_loop`
+/// with `asyncio.run_coroutine_threadsafe`, so each wake also pays for
+/// creating the coroutine, wrapping it in a Task and the
+/// `concurrent.futures.Future` handshake
+#[pyclass]
+struct CoroutineWaker {
+    callback: PyObject,
+    event_loop: PyObject,
+    coroutine: PyObject,
+    run_coroutine_threadsafe: PyObject,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+    gil: Arc<GilStats>,
+}
+
+#[pymethods]
+impl CoroutineWaker {
+    #[new]
+    fn new(py: Python<'_>, callback: PyObject, event_loop: PyObject) -> PyResult<Self> {
+        Ok(Self {
+            callback,
+            event_loop,
+            coroutine: calling_coroutine(py)?,
+            run_coroutine_threadsafe: py
+                .import("asyncio")?
+                .getattr("run_coroutine_threadsafe")?
+                .unbind(),
+            stamps: WakeTimestamps::new(),
+            errors: Arc::new(AtomicU64::new(0)),
+            gil: Arc::new(GilStats::default()),
+        })
+    }
+
+    /// GIL timings across all wakes so far, as for `CallbackWaker.gil_stats()`
+    fn gil_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        self.gil.to_dict(py)
+    }
+
+    fn reset_gil_stats(&self) {
+        self.gil.reset();
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+impl CoroutineWaker {
+    fn handle(&self, py: Python<'_>) -> CoroutineWakerHandle {
+        CoroutineWakerHandle {
+            callback: self.callback.clone_ref(py),
+            event_loop: self.event_loop.clone_ref(py),
+            coroutine: self.coroutine.clone_ref(py),
+            run_coroutine_threadsafe: self.run_coroutine_threadsafe.clone_ref(py),
+            stamps: self.stamps.clone(),
+            errors: self.errors.clone(),
+            gil: self.gil.clone(),
+        }
+    }
+}
+
+/// Holder for the callback and loop that can be sent across threads
+struct CoroutineWakerHandle {
+    callback: PyObject,
+    event_loop: PyObject,
+    coroutine: PyObject,
+    run_coroutine_threadsafe: PyObject,
+    stamps: Arc<WakeTimestamps>,
+    errors: Arc<AtomicU64>,
+    gil: Arc<GilStats>,
+}
+
+impl CoroutineWakerHandle {
+    fn wake(&self) {
+        // Stamp before the GIL wait so it counts towards wake latency
+        self.stamps.stamp();
+        let wait_start = Instant::now();
+        Python::with_gil(|py| {
+            let acquired = Instant::now();
+            // asyncio.run_coroutine_threadsafe(call(callback), event_loop)
+            let scheduled = self
+                .coroutine
+                .call1(py, (&self.callback,))
+                .and_then(|coro| {
+                    self.run_coroutine_threadsafe
+                        .call1(py, (coro, &self.event_loop))
+                });
+            if let Err(err) = scheduled {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+                report_python_error(err);
+            }
+            self.gil.record(acquired - wait_start, acquired.elapsed());
+        });
+    }
+}
+
+// =============================================================================
+// Approach 19: IOCP completion packet (Windows ProactorEventLoop, no GIL)
+// =============================================================================
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn PostQueuedCompletionStatus(
+        port: *mut std::ffi::c_void,
+        bytes: u32,
+        key: usize,
+        overlapped: *mut std::ffi::c_void,
+    ) -> i32;
+}
+
+/// Top bit of the OVERLAPPED addresses handed out as proactor keys. No
+/// user-mode address on 64-bit Windows has it set, so packets carrying one can
+/// never be mistaken for I/O completions on a real (heap) OVERLAPPED.
+#[cfg(windows)]
+const IOCP_KEY_BASE: usize = 1 << (usize::BITS - 1);
+/// Sequence below `IOCP_KEY_BASE`. It only increases, so no two wakers ever
+/// share a key, and a stale packet outliving its waker matches nothing.
+#[cfg(windows)]
+static NEXT_IOCP_KEY: AtomicU64 = AtomicU64::new(0);
+
+/// State shared by an IocpWaker, its proactor entry, and its producer handles
+#[cfg(windows)]
+struct IocpPort {
+    // The loop's completion port (HANDLE), as an integer so it can cross threads
+    iocp: usize,
+    // Address posted with each packet; the key of the proactor entry
+    address: usize,
+    closed: AtomicBool,
+}
+
+/// The entry an IocpWaker keeps in the proactor's table of pending operations
+/// (`IocpProactor._cache`). The proactor treats it as the operation's future
+/// and its completion callback: each packet calls it, and it re-inserts itself
+/// before scheduling the user's callback, so every posted packet becomes one
+/// callback. `cancel()`, which the proactor calls on every pending entry when
+/// the loop closes, removes it for good.
+#[cfg(windows)]
+#[pyclass(frozen)]
+struct IocpCompletion {
+    port: Arc<IocpPort>,
+    callback: PyObject,
+    event_loop: PyObject,
+    cache: PyObject,
+    // (self, None, 0, self), set once the entry exists; cleared on close
+    entry: Mutex<Option<PyObject>>,
+    handled: AtomicU64,
+}
+
+#[cfg(windows)]
+#[pymethods]
+impl IocpCompletion {
+    /// The proactor's completion callback: `callback(transferred, key, ov)`
+    fn __call__(
+        &self,
+        py: Python<'_>,
+        _transferred: PyObject,
+        _key: PyObject,
+        _ov: PyObject,
+    ) -> PyResult<()> {
+        trace_scope!("iocp.drain");
+        let Some(entry) = self.entry.lock().unwrap().as_ref().map(|e| e.clone_ref(py)) else {
+            return Ok(());
+        };
+        self.cache.bind(py).set_item(self.port.address, entry)?;
+        self.handled.fetch_add(1, Ordering::Relaxed);
+        self.event_loop
+            .call_method1(py, "call_soon", (self.callback.clone_ref(py),))?;
+        Ok(())
+    }
+
+    // The rest is the slice of the Future interface the proactor uses
+
+    fn done(&self) -> bool {
+        self.port.closed.load(Ordering::SeqCst)
+    }
+
+    fn cancelled(&self) -> bool {
+        self.done()
+    }
+
+    fn cancel(&self, py: Python<'_>) -> PyResult<bool> {
+        self.close(py)?;
+        Ok(true)
+    }
+
+    fn set_result(&self, _result: PyObject) {}
+
+    fn set_exception(&self, _exception: PyObject) {}
+}
+
+#[cfg(windows)]
+impl IocpCompletion {
+    /// Stop producers posting and leave the proactor's table. Idempotent.
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        self.port.closed.store(true, Ordering::SeqCst);
+        if self.entry.lock().unwrap().take().is_some() {
+            self.cache
+                .call_method1(py, "pop", (self.port.address, py.None()))?;
+        }
+        Ok(())
+    }
+}
+
+/// A waker that posts a completion packet to a ProactorEventLoop's I/O
+/// completion port with PostQueuedCompletionStatus, waking the loop's
+/// GetQueuedCompletionStatus. The Rust side never touches Python, and each
+/// packet runs `callback` once: unlike a pipe, nothing coalesces.
+///
+/// This relies on asyncio's `IocpProactor` internals (`_iocp`, `_cache`), as
+/// `wait_for_handle()` does for RegisterWaitWithQueue.
+#[cfg(windows)]
+#[pyclass]
+struct IocpWaker {
+    completion: Py<IocpCompletion>,
+    stamps: Arc<WakeTimestamps>,
+}
+
+#[cfg(windows)]
+#[pymethods]
+impl IocpWaker {
+    #[new]
+    fn new(py: Python<'_>, callback: PyObject, event_loop: PyObject) -> PyResult<Self> {
+        let proactor = event_loop.getattr(py, "_proactor").map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyTypeError, _>("IocpWaker needs a ProactorEventLoop")
+        })?;
+        let iocp: Option<usize> = proactor.getattr(py, "_iocp")?.extract(py)?;
+        let iocp = iocp.ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>("the event loop is closed")
+        })?;
+        let key = NEXT_IOCP_KEY.fetch_add(1, Ordering::Relaxed) as usize;
+        let port = Arc::new(IocpPort {
+            iocp,
+            address: IOCP_KEY_BASE | key,
+            closed: AtomicBool::new(false),
+        });
+        let cache = proactor.getattr(py, "_cache")?;
+        let completion = Py::new(
+            py,
+            IocpCompletion {
+                port: port.clone(),
+                callback,
+                event_loop,
+                cache: cache.clone_ref(py),
+                entry: Mutex::new(None),
+                handled: AtomicU64::new(0),
+            },
+        )?;
+        let entry = (
+            completion.clone_ref(py),
+            py.None(),
+            0,
+            completion.clone_ref(py),
+        )
+            .into_pyobject(py)?
+            .into_any()
+            .unbind();
+        cache.bind(py).set_item(port.address, entry.clone_ref(py))?;
+        *completion.get().entry.lock().unwrap() = Some(entry);
+        Ok(Self {
+            completion,
+            stamps: WakeTimestamps::new(),
+        })
+    }
+
+    /// Packets that have run the callback so far
+    fn handled(&self) -> u64 {
+        self.completion.get().handled.load(Ordering::Relaxed)
+    }
+
+    /// Leave the proactor; wakes sent afterwards are discarded. Close the
+    /// waker before its loop: the loop closes it when it closes, but a
+    /// producer still posting then could reach a reused handle. Idempotent.
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        self.completion.get().close(py)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.completion.get().done()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.close(py)?;
+        Ok(false)
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.stamps.snapshot()
+    }
+}
+
+#[cfg(windows)]
+impl IocpWaker {
+    fn handle(&self) -> PyResult<IocpWakerHandle> {
+        let port = self.completion.get().port.clone();
+        if port.closed.load(Ordering::SeqCst) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "IocpWaker is closed",
+            ));
+        }
+        Ok(IocpWakerHandle {
+            port,
+            stamps: self.stamps.clone(),
+        })
+    }
+}
+
+/// Holder for the completion port that can be sent across threads
+#[cfg(windows)]
+struct IocpWakerHandle {
+    port: Arc<IocpPort>,
+    stamps: Arc<WakeTimestamps>,
+}
+
+#[cfg(windows)]
+impl IocpWakerHandle {
+    fn wake(&self) {
+        trace_scope!("iocp.wake");
+        if self.port.closed.load(Ordering::SeqCst) {
+            return;
+        }
+        self.stamps.stamp();
+        note_syscalls(1);
+        let posted = unsafe {
+            PostQueuedCompletionStatus(
+                self.port.iocp as *mut std::ffi::c_void,
+                0,
+                0,
+                self.port.address as *mut std::ffi::c_void,
+            )
+        };
+        if posted == 0 {
+            report_os_error(std::io::Error::last_os_error());
+        }
+    }
+}
+
+// =============================================================================
+// Approach 20: one-shot wake, torn down once delivered
+// =============================================================================
+
+/// How a OneShotWaker reaches its event loop
+#[derive(Clone, Copy)]
+enum OneShotMode {
+    /// A pipe registered with `add_reader` (no GIL on the producer)
+    #[cfg(unix)]
+    Fd,
+    /// `call_soon_threadsafe` (acquires the GIL on the producer)
+    Callback,
+}
+
+impl OneShotMode {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            #[cfg(unix)]
+            "fd" => Ok(Self::Fd),
+            #[cfg(not(unix))]
+            "fd" => Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                "the fd mode is only available on Unix",
+            )),
+            "callback" => Ok(Self::Callback),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "mode must be 'fd' or 'callback', got {other:?}"
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            #[cfg(unix)]
+            Self::Fd => "fd",
+            Self::Callback => "callback",
+        }
+    }
+}
+
+/// Progress of a OneShotWaker's single wake, shared with its handles and its
+/// delivery callback. Timestamps are on the timestamp clock, 0 until taken.
+#[derive(Default)]
+struct OneShotState {
+    /// Claimed by the first wake (or by close()); later wakes are refused
+    fired: AtomicBool,
+    sent_ns: AtomicU64,
+    delivered_ns: AtomicU64,
+    /// Wakes refused because the one wake had already been claimed
+    refused: AtomicU64,
+}
+
+impl OneShotState {
+    /// Claim the one wake and stamp it; false if it was already claimed
+    fn claim(&self) -> bool {
+        if self.fired.swap(true, Ordering::AcqRel) {
+            self.refused.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        self.sent_ns
+            .store(timestamp_clock_source().read_ns(), Ordering::Release);
+        true
+    }
+
+    fn sent(&self) -> Option<u64> {
+        Some(self.sent_ns.load(Ordering::Acquire)).filter(|&ns| ns != 0)
+    }
+
+    fn delivered(&self) -> Option<u64> {
+        Some(self.delivered_ns.load(Ordering::Acquire)).filter(|&ns| ns != 0)
+    }
+
+    /// Stamp the delivery on the loop thread; returns the latency in ns
+    fn deliver(&self) -> u64 {
+        let now = timestamp_clock_source().read_ns();
+        self.delivered_ns.store(now, Ordering::Release);
+        now.saturating_sub(self.sent().unwrap_or(now))
+    }
+}
+
+/// The loop and delivery callable a callback-mode wake schedules, taken by
+/// that wake (or by close()) so later wakes find nothing to call
+type OneShotTarget = Arc<Mutex<Option<(PyObject, PyObject)>>>;
+
+/// Delivers exactly one wake to `event_loop`, then tears itself down: in
+/// `"fd"` mode the delivery removes the pipe's reader, and in `"callback"`
+/// mode the first wake takes the loop and callback so no later wake can
+/// schedule anything. No drain or re-arm happens on the delivery path. The
+/// future from `wait()` resolves with the delivery latency in ns.
+#[pyclass]
+struct OneShotWaker {
+    mode: OneShotMode,
+    state: Arc<OneShotState>,
+    future: PyObject,
+    /// Loop the fd mode's reader is registered with
+    #[cfg(unix)]
+    event_loop: PyObject,
+    #[cfg(unix)]
+    pipe: Option<Arc<Pipe>>,
+    target: OneShotTarget,
+}
+
+#[pymethods]
+impl OneShotWaker {
+    /// Register with `event_loop` at once: `mode` is `"fd"` (Unix) or `"callback"`
+    #[new]
+    #[pyo3(signature = (event_loop, mode="fd"))]
+    fn new(event_loop: &Bound<'_, PyAny>, mode: &str) -> PyResult<Self> {
+        let py = event_loop.py();
+        let mode = OneShotMode::parse(mode)?;
+        let state = Arc::new(OneShotState::default());
+        let future = event_loop.call_method0("create_future")?.unbind();
+        #[cfg(unix)]
+        let pipe = matches!(mode, OneShotMode::Fd)
+            .then(|| Pipe::new(false).map(Arc::new))
+            .transpose()?;
+
+        let deliver = {
+            let (state, future) = (state.clone(), future.clone_ref(py));
+            #[cfg(unix)]
+            let teardown = pipe.clone().map(|pipe| (pipe, event_loop.clone().unbind()));
+            pyo3::types::PyCFunction::new_closure(
+                py,
+                None,
+                None,
+                move |args, _kwargs| -> PyResult<()> {
+                    let py = args.py();
+                    // Stamp before the teardown so it isn't counted as latency
+                    let latency = state.deliver();
+                    #[cfg(unix)]
+                    if let Some((pipe, event_loop)) = &teardown {
+                        pipe.closed.store(true, Ordering::Relaxed);
+                        event_loop.call_method1(py, "remove_reader", (pipe.read.as_raw_fd(),))?;
+                    }
+                    if !future.call_method0(py, "done")?.is_truthy(py)? {
+                        future.call_method1(py, "set_result", (latency,))?;
+                    }
+                    Ok(())
+                },
+            )?
+            .into_any()
+            .unbind()
+        };
+
+        let target = OneShotTarget::default();
+        match mode {
+            #[cfg(unix)]
+            OneShotMode::Fd => {
+                let fd = pipe.as_ref().expect("fd mode has a pipe").read.as_raw_fd();
+                event_loop.call_method1("add_reader", (fd, deliver))?;
+            }
+            OneShotMode::Callback => {
+                *target.lock().unwrap() = Some((event_loop.clone().unbind(), deliver));
+            }
+        }
+        Ok(Self {
+            mode,
+            state,
+            future,
+            #[cfg(unix)]
+            event_loop: event_loop.clone().unbind(),
+            #[cfg(unix)]
+            pipe,
+            target,
+        })
+    }
+
+    #[getter]
+    fn mode(&self) -> &'static str {
+        self.mode.name()
+    }
+
+    /// The future resolved with the delivery latency in ns; the same one on
+    /// every call
+    fn wait(&self, py: Python<'_>) -> PyObject {
+        self.future.clone_ref(py)
+    }
+
+    /// Timestamp-clock ns at which the wake was sent, or None
+    fn sent_ns(&self) -> Option<u64> {
+        self.state.sent()
+    }
+
+    /// Timestamp-clock ns at which the loop received the wake, or None
+    fn delivered_ns(&self) -> Option<u64> {
+        self.state.delivered()
+    }
+
+    /// Delivery latency in ns, or None until delivered
+    fn latency_ns(&self) -> Option<u64> {
+        let delivered = self.state.delivered()?;
+        Some(delivered.saturating_sub(self.state.sent()?))
+    }
+
+    fn is_delivered(&self) -> bool {
+        self.state.delivered().is_some()
+    }
+
+    /// `{"fired", "delivered", "refused"}`: whether the one wake was claimed
+    /// and received, and how many later wakes were ignored
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        stats.set_item("fired", self.state.fired.load(Ordering::Acquire))?;
+        stats.set_item("delivered", self.is_delivered())?;
+        stats.set_item("refused", self.state.refused.load(Ordering::Relaxed))?;
+        Ok(stats)
+    }
+
+    /// Give up on an undelivered wake: refuse it from now on, remove the
+    /// reader or drop the callback, and cancel the future. Idempotent, and a
+    /// no-op once delivered.
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        if self.is_delivered() {
+            return Ok(());
+        }
+        self.state.fired.store(true, Ordering::Release);
+        self.target.lock().unwrap().take();
+        #[cfg(unix)]
+        if let Some(pipe) = &self.pipe {
+            if !pipe.closed.swap(true, Ordering::Relaxed) {
+                self.event_loop
+                    .call_method1(py, "remove_reader", (pipe.read.as_raw_fd(),))?;
+            }
+        }
+        self.future.call_method0(py, "cancel")?;
+        Ok(())
+    }
+
+    fn __repr__(&self) -> String {
+        let latency = self
+            .latency_ns()
+            .map_or_else(|| "None".to_string(), |ns| ns.to_string());
+        format!(
+            "OneShotWaker(mode={:?}, latency_ns={latency})",
+            self.mode.name()
+        )
+    }
+}
+
+impl OneShotWaker {
+    fn handle(&self) -> OneShotWakerHandle {
+        OneShotWakerHandle {
+            mode: self.mode,
+            state: self.state.clone(),
+            #[cfg(unix)]
+            pipe: self.pipe.clone(),
+            target: self.target.clone(),
+        }
+    }
+}
+
+/// Holder for the pipe or loop target that can be sent across threads
+struct OneShotWakerHandle {
+    mode: OneShotMode,
+    state: Arc<OneShotState>,
+    #[cfg(unix)]
+    pipe: Option<Arc<Pipe>>,
+    target: OneShotTarget,
+}
+
+impl OneShotWakerHandle {
+    fn wake(&self) {
+        if !self.state.claim() {
+            return;
+        }
+        #[cfg(unix)]
+        if let Some(pipe) = &self.pipe {
+            trace_scope!("oneshot.wake");
+            note_syscalls(1);
+            let token = &pipe.token;
+            let written = unsafe {
+                libc::write(
+                    pipe.write.as_raw_fd(),
+                    token.as_ptr() as *const libc::c_void,
+                    token.len(),
+                )
+            };
+            if written < 0 {
+                report_os_error(std::io::Error::last_os_error());
+            }
+            return;
+        }
+        Python::with_gil(|py| {
+            let Some((event_loop, deliver)) = self.target.lock().unwrap().take() else {
+                return;
+            };
+            if let Err(err) = event_loop.call_method1(py, "call_soon_threadsafe", (deliver,)) {
+                report_python_error(err);
+            }
+        });
+    }
+}
+
+// =============================================================================
+// Wake accounting: heap allocations and syscalls on producer threads
+// =============================================================================
+
+/// Whether benchmark tasks measure allocations and syscalls (see `set_accounting`)
+static ACCOUNTING: AtomicBool = AtomicBool::new(false);
+
+/// Per-thread tallies, bumped only while accounting is enabled
+#[derive(Clone, Copy, Default)]
+struct ThreadCounts {
+    allocations: u64,
+    allocated_bytes: u64,
+    syscalls: u64,
+}
+
+thread_local! {
+    // Const-initialised with no destructor, so the allocator can use it safely
+    static THREAD_COUNTS: std::cell::Cell<ThreadCounts> = const {
+        std::cell::Cell::new(ThreadCounts {
+            allocations: 0,
+            allocated_bytes: 0,
+            syscalls: 0,
+        })
+    };
+}
+
+fn bump_thread_counts(update: impl FnOnce(&mut ThreadCounts)) {
+    if !ACCOUNTING.load(Ordering::Relaxed) {
+        return;
+    }
+    let _ = THREAD_COUNTS.try_with(|counts| {
+        let mut value = counts.get();
+        update(&mut value);
+        counts.set(value);
+    });
+}
+
+/// Count `n` syscalls issued by a wake on the calling thread
+fn note_syscalls(n: u64) {
+    bump_thread_counts(|counts| counts.syscalls += n);
+}
+
+/// The system allocator, counting allocations per thread while accounting is on
+struct CountingAllocator;
+
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        bump_thread_counts(|counts| {
+            counts.allocations += 1;
+            counts.allocated_bytes += layout.size() as u64;
+        });
+        std::alloc::System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: std::alloc::Layout) -> *mut u8 {
+        bump_thread_counts(|counts| {
+            counts.allocations += 1;
+            counts.allocated_bytes += layout.size() as u64;
+        });
+        std::alloc::System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+        bump_thread_counts(|counts| {
+            counts.allocations += 1;
+            counts.allocated_bytes += new_size as u64;
+        });
+        std::alloc::System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Voluntary context switches of the calling thread: each is a blocking wait
+/// (a futex wait for the GIL, a pipe write waiting for space, ...)
+#[cfg(target_os = "linux")]
+fn voluntary_switches() -> Option<u64> {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(libc::RUSAGE_THREAD, &mut usage) } != 0 {
+        return None;
+    }
+    Some(usage.ru_nvcsw as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn voluntary_switches() -> Option<u64> {
+    None
+}
+
+/// Snapshot of the calling thread's counters, taken around a task body
+struct AccountingStart {
+    counts: ThreadCounts,
+    switches: Option<u64>,
+}
+
+impl AccountingStart {
+    fn now() -> Self {
+        Self {
+            counts: THREAD_COUNTS.with(|counts| counts.get()),
+            switches: voluntary_switches(),
+        }
+    }
+
+    fn finish(self) -> WakeAccounting {
+        let counts = THREAD_COUNTS.with(|counts| counts.get());
+        WakeAccounting {
+            allocations: counts.allocations - self.counts.allocations,
+            allocated_bytes: counts.allocated_bytes - self.counts.allocated_bytes,
+            syscalls: counts.syscalls - self.counts.syscalls,
+            blocking_waits: self
+                .switches
+                .zip(voluntary_switches())
+                .map(|(before, after)| after - before),
+        }
+    }
+}
+
+/// What a producer thread's wakes cost on the Rust side. Allocations made by
+/// CPython (e.g. under call_soon_threadsafe) use its own allocator and are not
+/// counted; syscalls are the ones wake paths issue directly.
+#[derive(Clone, Copy)]
+struct WakeAccounting {
+    allocations: u64,
+    allocated_bytes: u64,
+    syscalls: u64,
+    blocking_waits: Option<u64>,
+}
+
+impl WakeAccounting {
+    fn to_dict(self, py: Python<'_>, sent: u64) -> PyResult<Bound<'_, pyo3::types::PyDict>> {
+        let per_wake = |total: u64| {
+            if sent > 0 {
+                total as f64 / sent as f64
+            } else {
+                0.0
+            }
+        };
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("allocations", self.allocations)?;
+        dict.set_item("allocated_bytes", self.allocated_bytes)?;
+        dict.set_item("syscalls", self.syscalls)?;
+        dict.set_item("blocking_waits", self.blocking_waits)?;
+        dict.set_item("allocations_per_wake", per_wake(self.allocations))?;
+        dict.set_item("syscalls_per_wake", per_wake(self.syscalls))?;
+        dict.set_item("blocking_waits_per_wake", self.blocking_waits.map(per_wake))?;
+        Ok(dict)
+    }
+}
+
+/// Turn allocation and syscall accounting on or off for benchmark tasks
+/// started afterwards. Their `BenchmarkResult.accounting` reports what the
+/// producer thread's wakes cost; it is None for tasks started while off.
+#[pyfunction]
+fn set_accounting(enabled: bool) {
+    ACCOUNTING.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether allocation and syscall accounting is enabled
+#[pyfunction]
+fn accounting_enabled() -> bool {
+    ACCOUNTING.load(Ordering::Relaxed)
+}
+
+// =============================================================================
+// Hardware performance counters (optional `perf` feature, Linux)
+// =============================================================================
+
+/// Leading, version-0 part of the kernel's `struct perf_event_attr` (64 bytes)
+#[cfg(all(feature = "perf", target_os = "linux"))]
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    kind: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+mod perf_flags {
+    pub const DISABLED: u64 = 1 << 0;
+    pub const INHERIT: u64 = 1 << 1;
+    pub const EXCLUDE_KERNEL: u64 = 1 << 5;
+    pub const EXCLUDE_HV: u64 = 1 << 6;
+    pub const FD_CLOEXEC: libc::c_ulong = 1 << 3;
+    pub const IOC_ENABLE: u64 = 0x2400;
+    pub const IOC_DISABLE: u64 = 0x2401;
+    pub const IOC_RESET: u64 = 0x2403;
+}
+
+/// Counted events: name, perf type, perf config. Hardware events are often
+/// missing in VMs; those counters then read as None.
+#[cfg(all(feature = "perf", target_os = "linux"))]
+const PERF_EVENTS: [(&str, u32, u64); 3] = [
+    // PERF_TYPE_HARDWARE, PERF_COUNT_HW_CPU_CYCLES
+    ("cycles", 0, 0),
+    // PERF_TYPE_HARDWARE, PERF_COUNT_HW_INSTRUCTIONS
+    ("instructions", 0, 1),
+    // PERF_TYPE_SOFTWARE, PERF_COUNT_SW_CONTEXT_SWITCHES
+    ("context_switches", 1, 3),
+];
+
+/// What a set of counters measures
+#[cfg(all(feature = "perf", target_os = "linux"))]
+#[derive(Clone, Copy, PartialEq)]
+enum PerfScope {
+    /// The thread that opened the counters
+    Thread,
+    /// Every thread of the process, including ones started later
+    Process,
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+impl PerfScope {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "thread" => Ok(Self::Thread),
+            "process" => Ok(Self::Process),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "scope must be 'thread' or 'process', got {name:?}"
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Thread => "thread",
+            Self::Process => "process",
+        }
+    }
+
+    /// Thread IDs to attach to (0 = the calling thread)
+    fn targets(self) -> Vec<libc::pid_t> {
+        match self {
+            Self::Thread => vec![0],
+            Self::Process => std::fs::read_dir("/proc/self/task")
+                .map(|tasks| {
+                    tasks
+                        .filter_map(|task| task.ok()?.file_name().to_str()?.parse().ok())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+fn perf_event_open(kind: u32, config: u64, tid: libc::pid_t, inherit: bool) -> Option<OwnedFd> {
+    let mut flags = perf_flags::DISABLED | perf_flags::EXCLUDE_HV;
+    if inherit {
+        flags |= perf_flags::INHERIT;
+    }
+    // Retry user-only when kernel counting is not permitted
+    for extra in [0, perf_flags::EXCLUDE_KERNEL] {
+        let attr = PerfEventAttr {
+            kind,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            flags: flags | extra,
+            ..Default::default()
+        };
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const PerfEventAttr,
+                tid,
+                -1 as libc::c_int,
+                -1 as libc::c_int,
+                perf_flags::FD_CLOEXEC,
+            )
+        };
+        if fd >= 0 {
+            return Some(unsafe { OwnedFd::from_raw_fd(fd as RawFd) });
+        }
+    }
+    None
+}
+
+/// Counter values; None for events this machine can't count
+#[cfg(all(feature = "perf", target_os = "linux"))]
+#[derive(Clone, Copy, Default)]
+struct PerfReading {
+    cycles: Option<u64>,
+    instructions: Option<u64>,
+    context_switches: Option<u64>,
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+impl PerfReading {
+    /// Totals, plus per-wake figures when `wakes` is given
+    fn to_dict(
+        self,
+        py: Python<'_>,
+        wakes: Option<u64>,
+    ) -> PyResult<Bound<'_, pyo3::types::PyDict>> {
+        let dict = pyo3::types::PyDict::new(py);
+        let values = [
+            ("cycles", self.cycles),
+            ("instructions", self.instructions),
+            ("context_switches", self.context_switches),
+        ];
+        for (name, value) in values {
+            dict.set_item(name, value)?;
+            if let Some(wakes) = wakes {
+                let per_wake = value.filter(|_| wakes > 0).map(|v| v as f64 / wakes as f64);
+                dict.set_item(format!("{name}_per_wake"), per_wake)?;
+            }
+        }
+        Ok(dict)
+    }
+}
+
+/// Open counters for every event in PERF_EVENTS over one scope
+#[cfg(all(feature = "perf", target_os = "linux"))]
+struct PerfSet {
+    // Per event, one FD per attached thread; empty if the event is unavailable
+    events: Vec<Vec<OwnedFd>>,
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+impl PerfSet {
+    fn open(scope: PerfScope) -> Self {
+        let targets = scope.targets();
+        let events = PERF_EVENTS
+            .iter()
+            .map(|&(_, kind, config)| {
+                targets
+                    .iter()
+                    .filter_map(|&tid| {
+                        perf_event_open(kind, config, tid, scope == PerfScope::Process)
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { events }
+    }
+
+    fn ioctl(&self, request: u64) {
+        for fd in self.events.iter().flatten() {
+            unsafe { libc::ioctl(fd.as_raw_fd(), request as _, 0) };
+        }
+    }
+
+    /// Zero the counters and start counting
+    fn start(&self) {
+        self.ioctl(perf_flags::IOC_RESET);
+        self.ioctl(perf_flags::IOC_ENABLE);
+    }
+
+    fn stop(&self) {
+        self.ioctl(perf_flags::IOC_DISABLE);
+    }
+
+    fn available(&self) -> Vec<&'static str> {
+        PERF_EVENTS
+            .iter()
+            .zip(&self.events)
+            .filter(|(_, fds)| !fds.is_empty())
+            .map(|(&(name, _, _), _)| name)
+            .collect()
+    }
+
+    fn read(&self) -> PerfReading {
+        let total = |fds: &Vec<OwnedFd>| {
+            if fds.is_empty() {
+                return None;
+            }
+            let mut sum = 0u64;
+            for fd in fds {
+                let mut value = 0u64;
+                let n = unsafe {
+                    libc::read(
+                        fd.as_raw_fd(),
+                        &mut value as *mut u64 as *mut libc::c_void,
+                        std::mem::size_of::<u64>(),
+                    )
+                };
+                if n == std::mem::size_of::<u64>() as isize {
+                    sum += value;
+                }
+            }
+            Some(sum)
+        };
+        PerfReading {
+            cycles: total(&self.events[0]),
+            instructions: total(&self.events[1]),
+            context_switches: total(&self.events[2]),
+        }
+    }
+}
+
+/// Counters a benchmark task runs around its measured region: the producer
+/// thread's own, and the whole process's
+#[cfg(all(feature = "perf", target_os = "linux"))]
+struct PerfRegion {
+    thread: PerfSet,
+    process: PerfSet,
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+impl PerfRegion {
+    /// Open and start both sets; must run on the producer thread
+    fn start() -> Self {
+        let region = Self {
+            thread: PerfSet::open(PerfScope::Thread),
+            process: PerfSet::open(PerfScope::Process),
+        };
+        region.thread.start();
+        region.process.start();
+        region
+    }
+
+    fn finish(self) -> PerfStats {
+        self.thread.stop();
+        self.process.stop();
+        PerfStats {
+            thread: self.thread.read(),
+            process: self.process.read(),
+        }
+    }
+}
+
+/// Performance counters over a task's measured region
+#[cfg(all(feature = "perf", target_os = "linux"))]
+#[derive(Clone, Copy)]
+struct PerfStats {
+    thread: PerfReading,
+    process: PerfReading,
+}
+
+/// Whether benchmark tasks collect performance counters (see `set_perf_counters`)
+#[cfg(all(feature = "perf", target_os = "linux"))]
+static PERF_COUNTERS: AtomicBool = AtomicBool::new(false);
+
+/// Turn performance counter collection on or off for benchmark tasks
+/// started afterwards. Their `BenchmarkResult.perf` then reports cycles,
+/// instructions and context switches over the measured run.
+#[cfg(all(feature = "perf", target_os = "linux"))]
+#[pyfunction]
+fn set_perf_counters(enabled: bool) {
+    PERF_COUNTERS.store(enabled, Ordering::Relaxed);
+}
+
+/// Hardware and software performance counters (perf_event_open) around a
+/// region of code. `scope="thread"` counts the thread that created the
+/// object; `scope="process"` counts every thread, including ones started
+/// while counting. Counters this machine can't provide read as None.
+#[cfg(all(feature = "perf", target_os = "linux"))]
+#[pyclass]
+struct PerfCounters {
+    scope: PerfScope,
+    set: PerfSet,
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+#[pymethods]
+impl PerfCounters {
+    #[new]
+    #[pyo3(signature = (scope="thread"))]
+    fn new(scope: &str) -> PyResult<Self> {
+        let scope = PerfScope::parse(scope)?;
+        let set = PerfSet::open(scope);
+        if set.available().is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyOSError, _>(
+                "perf_event_open failed for every counter (check kernel.perf_event_paranoid)",
+            ));
+        }
+        Ok(Self { scope, set })
+    }
+
+    #[getter]
+    fn scope(&self) -> &'static str {
+        self.scope.name()
+    }
+
+    /// Names of the counters this machine provides
+    fn available(&self) -> Vec<&'static str> {
+        self.set.available()
+    }
+
+    /// Zero the counters and start counting
+    fn start(&self) {
+        self.set.start();
+    }
+
+    fn stop(&self) {
+        self.set.stop();
+    }
+
+    /// Current values: `cycles`, `instructions`, `context_switches`, with
+    /// `*_per_wake` figures too when `wakes` is given
+    #[pyo3(signature = (wakes=None))]
+    fn read<'py>(
+        &self,
+        py: Python<'py>,
+        wakes: Option<u64>,
+    ) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        self.set.read().to_dict(py, wakes)
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf.start();
+        slf
+    }
+
+    fn __exit__(&self, _exc_type: PyObject, _exc_value: PyObject, _traceback: PyObject) -> bool {
+        self.stop();
+        false
+    }
+}
+
+// =============================================================================
+// Tracing: Chrome trace-event export of wake, GIL and drain spans (optional `trace` feature)
+// =============================================================================
+
+/// Whether spans are being recorded (between start_trace and stop_trace)
+#[cfg(feature = "trace")]
+static TRACING: AtomicBool = AtomicBool::new(false);
+
+/// One span boundary: Chrome trace-event phase 'B' (begin) or 'E' (end)
+#[cfg(feature = "trace")]
+struct TraceEvent {
+    name: &'static str,
+    phase: char,
+    ns: u64,
+}
+
+/// Most span events one thread keeps per trace; later ones are dropped
+#[cfg(feature = "trace")]
+const MAX_TRACE_EVENTS_PER_THREAD: usize = 1 << 20;
+
+/// One thread's span events. Only that thread records into it, so its lock
+/// is uncontended until stop_trace collects it.
+#[cfg(feature = "trace")]
+struct TraceBuffer {
+    tid: u64,
+    name: String,
+    events: Mutex<Vec<TraceEvent>>,
+    dropped: AtomicU64,
+}
+
+#[cfg(feature = "trace")]
+impl TraceBuffer {
+    fn push(&self, event: TraceEvent) {
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        if events.len() < MAX_TRACE_EVENTS_PER_THREAD {
+            events.push(event);
+        } else {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The buffer of every thread that has recorded a span, including threads
+/// that have since exited; stop_trace prunes those once collected
+#[cfg(feature = "trace")]
+static TRACE_BUFFERS: Mutex<Vec<Arc<TraceBuffer>>> = Mutex::new(Vec::new());
+
+#[cfg(feature = "trace")]
+thread_local! {
+    static TRACE_BUFFER: Arc<TraceBuffer> = {
+        static NEXT_TID: AtomicU64 = AtomicU64::new(1);
+        let tid = NEXT_TID.fetch_add(1, Ordering::Relaxed);
+        let buffer = Arc::new(TraceBuffer {
+            tid,
+            name: std::thread::current()
+                .name()
+                .map_or_else(|| format!("thread-{tid}"), str::to_string),
+            events: Mutex::new(Vec::new()),
+            dropped: AtomicU64::new(0),
+        });
+        TRACE_BUFFERS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(buffer.clone());
+        buffer
+    };
+}
+
+/// An in-progress trace: where to write it and when it started
+#[cfg(feature = "trace")]
+struct TraceSession {
+    path: std::path::PathBuf,
+    origin_ns: u64,
+}
+
+#[cfg(feature = "trace")]
+static TRACE_SESSION: Mutex<Option<TraceSession>> = Mutex::new(None);
+
+/// Every thread's events from a finished trace, as (tid, thread name, events),
+/// and how many were dropped at the per-thread cap
+#[cfg(feature = "trace")]
+struct CollectedTrace {
+    threads: Vec<(u64, String, Vec<TraceEvent>)>,
+    dropped: u64,
+}
+
+#[cfg(feature = "trace")]
+impl CollectedTrace {
+    /// Take the events out of every buffer, forgetting exited threads' buffers
+    fn collect() -> Self {
+        let mut buffers = TRACE_BUFFERS.lock().unwrap_or_else(|e| e.into_inner());
+        let mut trace = CollectedTrace {
+            threads: Vec::new(),
+            dropped: 0,
+        };
+        for buffer in buffers.iter() {
+            let events =
+                std::mem::take(&mut *buffer.events.lock().unwrap_or_else(|e| e.into_inner()));
+            trace.dropped += buffer.dropped.swap(0, Ordering::Relaxed);
+            if !events.is_empty() {
+                trace
+                    .threads
+                    .push((buffer.tid, buffer.name.clone(), events));
+            }
+        }
+        // Only the thread-local and this list hold a live thread's buffer
+        buffers.retain(|buffer| Arc::strong_count(buffer) > 1);
+        trace
+    }
+
+    fn events(&self) -> usize {
+        self.threads.iter().map(|(_, _, events)| events.len()).sum()
+    }
+}
+
+/// Subscriber that records span enter/exit times while a trace is running.
+/// Span IDs are the address of the span's static metadata: only the name is
+/// needed to emit an event, so spans at one callsite may share an ID.
+#[cfg(feature = "trace")]
+struct ChromeTraceSubscriber;
+
+#[cfg(feature = "trace")]
+impl ChromeTraceSubscriber {
+    fn record(&self, id: &tracing::span::Id, phase: char) {
+        if !TRACING.load(Ordering::Relaxed) {
+            return;
+        }
+        let metadata = unsafe { &*(id.into_u64() as *const tracing::Metadata<'static>) };
+        let event = TraceEvent {
+            name: metadata.name(),
+            phase,
+            ns: monotonic_ns(),
+        };
+        // Fails only while the thread is being torn down
+        let _ = TRACE_BUFFER.try_with(|buffer| buffer.push(event));
+    }
+}
+
+#[cfg(feature = "trace")]
+impl tracing::Subscriber for ChromeTraceSubscriber {
+    fn register_callsite(
+        &self,
+        _metadata: &'static tracing::Metadata<'static>,
+    ) -> tracing::subscriber::Interest {
+        // Re-check enabled() on every span, since tracing is toggled at runtime
+        tracing::subscriber::Interest::sometimes()
+    }
+
+    fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+        TRACING.load(Ordering::Relaxed)
+    }
+
+    fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(span.metadata() as *const tracing::Metadata<'static> as u64)
+    }
+
+    fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+    fn event(&self, _event: &tracing::Event<'_>) {}
+
+    fn enter(&self, span: &tracing::span::Id) {
+        self.record(span, 'B');
+    }
+
+    fn exit(&self, span: &tracing::span::Id) {
+        self.record(span, 'E');
+    }
+}
+
+/// Escape a string for a JSON string literal
+#[cfg(feature = "trace")]
+fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(feature = "trace")]
+impl TraceSession {
+    /// Write `trace` as Chrome trace-event JSON (loadable in Perfetto or
+    /// chrome://tracing). Events dropped at the per-thread cap are counted in
+    /// `otherData.dropped_events`.
+    fn write(&self, trace: &CollectedTrace) -> std::io::Result<()> {
+        let pid = std::process::id();
+        let mut out = std::io::BufWriter::new(std::fs::File::create(&self.path)?);
+        write!(
+            out,
+            "{{\"displayTimeUnit\":\"ns\",\"otherData\":{{\"dropped_events\":{}}},\"traceEvents\":[",
+            trace.dropped
+        )?;
+        let mut first = true;
+        let mut separator = |out: &mut std::io::BufWriter<std::fs::File>| {
+            let comma = if first { "" } else { "," };
+            first = false;
+            writeln!(out, "{comma}")
+        };
+        for (tid, name, _) in &trace.threads {
+            separator(&mut out)?;
+            write!(
+                out,
+                "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":{pid},\"tid\":{tid},\"args\":{{\"name\":\"{}\"}}}}",
+                json_escape(name)
+            )?;
+        }
+        for (tid, _, events) in &trace.threads {
+            for event in events {
+                separator(&mut out)?;
+                write!(
+                    out,
+                    "{{\"name\":\"{}\",\"ph\":\"{}\",\"ts\":{:.3},\"pid\":{pid},\"tid\":{tid}}}",
+                    event.name,
+                    event.phase,
+                    event.ns.saturating_sub(self.origin_ns) as f64 / 1000.0,
+                )?;
+            }
+        }
+        write!(out, "\n]}}\n")?;
+        out.flush()
+    }
+}
+
+/// Start recording wake, GIL-acquire and drain spans from every thread, to
+/// be written to `path` as Chrome trace-event JSON by `stop_trace()`
+#[cfg(feature = "trace")]
+#[pyfunction]
+fn start_trace(path: std::path::PathBuf) -> PyResult<()> {
+    static INSTALLED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    let installed = *INSTALLED.get_or_init(|| {
+        tracing::dispatcher::set_global_default(tracing::Dispatch::new(ChromeTraceSubscriber))
+            .is_ok()
+    });
+    if !installed {
+        return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+            "another global tracing subscriber is already installed in this process",
+        ));
+    }
+    let mut session = TRACE_SESSION.lock().unwrap();
+    if session.is_some() {
+        return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+            "a trace is already running",
+        ));
+    }
+    // Discard spans recorded after the previous trace stopped
+    drop(CollectedTrace::collect());
+    *session = Some(TraceSession {
+        path,
+        origin_ns: monotonic_ns(),
+    });
+    TRACING.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Stop recording, collect every thread's spans and write the trace file
+/// (without the GIL). Returns the number of span events written.
+#[cfg(feature = "trace")]
+#[pyfunction]
+fn stop_trace(py: Python<'_>) -> PyResult<usize> {
+    TRACING.store(false, Ordering::SeqCst);
+    let session =
+        TRACE_SESSION.lock().unwrap().take().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("no trace is running")
+        })?;
+    let trace = py.allow_threads(|| {
+        let trace = CollectedTrace::collect();
+        session.write(&trace).map(|()| trace)
+    })?;
+    Ok(trace.events())
+}
+
+// =============================================================================
+// Benchmark tasks: cancellable handles for producer threads
+// =============================================================================
+
+/// State shared between a BenchTask and the thread running it
+#[derive(Default)]
+struct TaskState {
+    cancelled: AtomicBool,
+    finished: Mutex<bool>,
+    done: Condvar,
+    /// (requested, achieved) delay once the thread's initial delay has elapsed
+    delay: Mutex<Option<(Duration, Duration)>>,
+    /// Set when the task body returns
+    run: Mutex<Option<RunStats>>,
+    /// Whether accounting was enabled when the task was spawned
+    accounting: bool,
+    /// Whether performance counters were enabled when the task was spawned
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    perf: bool,
+    drops: Option<DropCounter>,
+    /// Start of the measured part of the run (restarted after a warmup phase)
+    measure_start: Mutex<Option<MeasureStart>>,
+    /// The first MAX_TASK_FAULTS faults reported while the task ran
+    faults: Mutex<Vec<Arc<ThreadFault>>>,
+    /// Every fault reported, including those not kept
+    fault_count: AtomicU64,
+}
+
+/// Where a task's measured run began: its run time, accounting and dropped
+/// wakes are all taken relative to this
+struct MeasureStart {
+    ns: u64,
+    dropped: Option<u64>,
+    counters: Option<AccountingStart>,
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    perf: Option<PerfRegion>,
+}
+
+impl TaskState {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn is_finished(&self) -> bool {
+        *self.finished.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Block until the thread exits or `timeout` elapses; true if it exited
+    fn wait(&self, timeout: Option<Duration>) -> bool {
+        let finished = self.finished.lock().unwrap_or_else(|e| e.into_inner());
+        match timeout {
+            Some(timeout) => {
+                let (finished, _) = self
+                    .done
+                    .wait_timeout_while(finished, timeout, |finished| !*finished)
+                    .unwrap_or_else(|e| e.into_inner());
+                *finished
+            }
+            None => *self
+                .done
+                .wait_while(finished, |finished| !*finished)
+                .unwrap_or_else(|e| e.into_inner()),
+        }
+    }
+
+    /// Wait out `requested` on a timer from `backend`, recording the delay achieved
+    fn delay(&self, backend: TimerBackend, requested: Duration) {
+        let actual = if requested.is_zero() {
+            Duration::ZERO
+        } else {
+            Timer::new(backend, Duration::ZERO).sleep(requested)
+        };
+        *self.delay.lock().unwrap_or_else(|e| e.into_inner()) = Some((requested, actual));
+    }
+
+    /// Wait at `start` until every party has arrived. If the barrier is
+    /// aborted instead, or the task cancelled while waiting, the task is
+    /// left cancelled so its body sends nothing.
+    fn wait_start(&self, start: &StartLine) {
+        if !start.arrive_and_wait(None, || self.is_cancelled()) {
+            self.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Start, or after a warmup phase restart, the measured part of the run.
+    /// Must be called on the producer thread.
+    fn begin_measurement(&self) {
+        let dropped = self.drops.as_ref().map(|drops| drops());
+        let counters = self.accounting.then(AccountingStart::now);
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        let perf = self.perf.then(PerfRegion::start);
+        let start = MeasureStart {
+            ns: monotonic_ns(),
+            dropped,
+            counters,
+            #[cfg(all(feature = "perf", target_os = "linux"))]
+            perf,
+        };
+        *self.measure_start.lock().unwrap_or_else(|e| e.into_inner()) = Some(start);
+    }
+}
+
+pyo3::create_exception!(
+    wakerbench,
+    ProducerPanic,
+    pyo3::exceptions::PyRuntimeError,
+    "A benchmark thread panicked; the message is the panic's."
+);
+
+/// Faults a task keeps for `BenchTask.exceptions()`; later ones are only counted
+const MAX_TASK_FAULTS: usize = 16;
+
+/// Faults `thread_errors()` keeps, the oldest being dropped first
+const MAX_QUEUED_FAULTS: usize = 256;
+
+/// Something that went wrong on a thread this module started
+enum ThreadFault {
+    /// The thread panicked, with this message
+    Panic(String),
+    /// A wake's syscall failed
+    Os(std::io::Error),
+    /// A wake's Python call raised
+    Python(PyErr),
+}
+
+impl ThreadFault {
+    fn from_panic(payload: Box<dyn std::any::Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "Box<dyn Any>".to_string(),
+            },
+        };
+        Self::Panic(message)
+    }
+
+    /// The fault as a Python exception: ProducerPanic, the OSError subclass
+    /// for the errno, or the exception the Python call raised
+    fn to_exception(&self, py: Python<'_>) -> PyObject {
+        let err = match self {
+            Self::Panic(message) => ProducerPanic::new_err(message.clone()),
+            Self::Os(error) => PyErr::from(match error.raw_os_error() {
+                Some(errno) => std::io::Error::from_raw_os_error(errno),
+                None => std::io::Error::new(error.kind(), error.to_string()),
+            }),
+            Self::Python(err) => err.clone_ref(py),
+        };
+        err.into_value(py).into_any()
+    }
+}
+
+/// Faults from every thread, for `thread_errors()`: (thread name, fault).
+/// The module's only static holding Python objects: a `ThreadFault::Python`
+/// keeps the exception as raised.
+static FAULT_QUEUE: Mutex<std::collections::VecDeque<(String, Arc<ThreadFault>)>> =
+    Mutex::new(std::collections::VecDeque::new());
+
+thread_local! {
+    /// The task running on this thread, which faults are reported to
+    static CURRENT_TASK: std::cell::RefCell<Option<Arc<TaskState>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+fn current_task() -> Option<Arc<TaskState>> {
+    CURRENT_TASK.with(|current| current.borrow().clone())
+}
+
+/// Run `body` as `state`'s thread: faults reported meanwhile are kept by
+/// `state`, and a panic is caught and kept as one. None if `body` panicked.
+fn run_as_task<R>(state: &Arc<TaskState>, body: impl FnOnce() -> R) -> Option<R> {
+    let previous = CURRENT_TASK.with(|current| current.replace(Some(state.clone())));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(body));
+    let result = result
+        .map_err(|payload| report_fault(ThreadFault::from_panic(payload)))
+        .ok();
+    CURRENT_TASK.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// Record `fault` against the task running on this thread, if any, and in
+/// the module's queue. Only called on failure paths.
+fn report_fault(fault: ThreadFault) {
+    let fault = Arc::new(fault);
+    if let Some(task) = current_task() {
+        task.fault_count.fetch_add(1, Ordering::Relaxed);
+        let mut faults = task.faults.lock().unwrap_or_else(|e| e.into_inner());
+        if faults.len() < MAX_TASK_FAULTS {
+            faults.push(fault.clone());
+        }
+    }
+    let thread = std::thread::current()
+        .name()
+        .unwrap_or("<unnamed>")
+        .to_string();
+    let mut queue = FAULT_QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+    if queue.len() == MAX_QUEUED_FAULTS {
+        queue.pop_front();
+    }
+    queue.push_back((thread, fault));
+}
+
+/// Report a failed wake syscall
+fn report_os_error(error: std::io::Error) {
+    report_fault(ThreadFault::Os(error));
+}
+
+/// Report a Python call that raised during a wake
+fn report_python_error(err: PyErr) {
+    report_fault(ThreadFault::Python(err));
+}
+
+/// Faults from every thread this module started, oldest first, as
+/// `(thread_name, exception)`: a ProducerPanic for a panic, an OSError for
+/// a failed syscall, or what a wake's Python call raised. The most recent
+/// 256 are kept. With `clear`, they are removed, so a pytest fixture can
+/// assert that a test left none behind.
+#[pyfunction]
+#[pyo3(signature = (clear=true))]
+fn thread_errors(py: Python<'_>, clear: bool) -> Vec<(String, PyObject)> {
+    let mut queue = FAULT_QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+    let errors = queue
+        .iter()
+        .map(|(thread, fault)| (thread.clone(), fault.to_exception(py)))
+        .collect();
+    if clear {
+        queue.clear();
+    }
+    errors
+}
+
+/// Marks the task finished when the thread exits, even if the body panics
+struct FinishOnDrop(Arc<TaskState>);
+
+impl Drop for FinishOnDrop {
+    fn drop(&mut self) {
+        *self.0.finished.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        self.0.done.notify_all();
+    }
+}
+
+/// Handle to a running benchmark thread
+#[pyclass]
+struct BenchTask {
+    state: Arc<TaskState>,
+    spec: BenchSpec,
+}
+
+#[pymethods]
+impl BenchTask {
+    /// Ask the thread to stop before its next wake. Does not wait for it.
+    fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.state.is_cancelled()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+
+    /// `(requested_ns, actual_ns)` for the thread's initial delay, or None until
+    /// it has elapsed (or if the task has no delay)
+    fn delay_ns(&self) -> Option<(u64, u64)> {
+        self.state
+            .delay
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .map(|(requested, actual)| (requested.as_nanos() as u64, actual.as_nanos() as u64))
+    }
+
+    /// The first fault the thread reported, as an exception, or None: a
+    /// ProducerPanic if it panicked, an OSError if a wake's syscall failed,
+    /// or what a wake's Python call raised. Doesn't wait for the thread.
+    fn exception(&self, py: Python<'_>) -> Option<PyObject> {
+        let faults = self.state.faults.lock().unwrap_or_else(|e| e.into_inner());
+        faults.first().map(|fault| fault.to_exception(py))
+    }
+
+    /// Every fault kept so far (the first 16), as for `exception()`;
+    /// `result().errors` counts them all
+    fn exceptions(&self, py: Python<'_>) -> Vec<PyObject> {
+        let faults = self.state.faults.lock().unwrap_or_else(|e| e.into_inner());
+        faults.iter().map(|fault| fault.to_exception(py)).collect()
+    }
+
+    /// Summary of the run as a BenchmarkResult, or None until the thread has finished
+    fn result(&self) -> Option<BenchmarkResult> {
+        let run = (*self.state.run.lock().unwrap_or_else(|e| e.into_inner()))?;
+        let latency = self
+            .spec
+            .recorder
+            .as_ref()
+            .map(|recording| LatencySummary::from_histogram(&recording.lock().unwrap().hist));
+        let steps = self
+            .spec
+            .steps
+            .as_ref()
+            .map(|steps| steps.lock().unwrap().clone());
+        Some(BenchmarkResult {
+            benchmark: self.spec.benchmark,
+            requested: self.spec.requested,
+            sent: run.sent,
+            cancelled: run.cancelled,
+            duration_ns: run.duration_ns,
+            dropped: run.dropped,
+            errors: run.errors,
+            released_ns: self
+                .spec
+                .start
+                .as_ref()
+                .and_then(|start| start.released_ns()),
+            accounting: run.accounting,
+            #[cfg(all(feature = "perf", target_os = "linux"))]
+            perf: run.perf,
+            latency,
+            steps,
+            config: self.spec.config.clone(),
+        })
+    }
+
+    /// Wait for the thread to exit, releasing the GIL while blocked.
+    /// Returns True if it finished, False if `timeout` (seconds) elapsed first.
+    #[pyo3(signature = (timeout=None))]
+    fn join(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<bool> {
+        let timeout = timeout
+            .map(|secs| {
+                Duration::try_from_secs_f64(secs).map_err(|_| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "timeout must be a non-negative number of seconds",
+                    )
+                })
+            })
+            .transpose()?;
+        let state = self.state.clone();
+
+        Ok(py.allow_threads(move || state.wait(timeout)))
+    }
+}
+
+/// Shared state of a StartBarrier
+struct StartLine {
+    parties: usize,
+    arrived: Mutex<usize>,
+    changed: Condvar,
+    /// `monotonic_ns()` at release; 0 until then
+    released_ns: AtomicU64,
+    aborted: AtomicBool,
+}
+
+impl StartLine {
+    /// How often a waiting thread re-checks its cancellation
+    const POLL: Duration = Duration::from_millis(10);
+
+    fn released_ns(&self) -> Option<u64> {
+        match self.released_ns.load(Ordering::SeqCst) {
+            0 => None,
+            ns => Some(ns),
+        }
+    }
+
+    /// Arrive, then block until every party has. The last to arrive releases
+    /// the rest. Returns false, withdrawing the arrival, if the barrier is
+    /// aborted, `cancelled` returns true or `timeout` passes first.
+    fn arrive_and_wait(&self, timeout: Option<Duration>, cancelled: impl Fn() -> bool) -> bool {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut arrived = self.arrived.lock().unwrap_or_else(|e| e.into_inner());
+        *arrived += 1;
+        if *arrived >= self.parties && self.released_ns().is_none() {
+            self.released_ns.store(monotonic_ns(), Ordering::SeqCst);
+            self.changed.notify_all();
+        }
+        loop {
+            if self.released_ns().is_some() {
+                return true;
+            }
+            let now = Instant::now();
+            if self.aborted.load(Ordering::SeqCst)
+                || cancelled()
+                || deadline.is_some_and(|deadline| now >= deadline)
+            {
+                *arrived -= 1;
+                return false;
+            }
+            let wait = deadline.map_or(Self::POLL, |deadline| (deadline - now).min(Self::POLL));
+            arrived = self
+                .changed
+                .wait_timeout(arrived, wait)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+}
+
+/// A start line for `parties` threads: benchmark producers given it as
+/// `barrier=` and Python threads calling `wait()`. Each blocks when it
+/// arrives, and the last arrival releases them all together, so
+/// concurrent runs begin at the same moment however long their threads
+/// took to spawn. Producers start measuring once released, and their
+/// results record `released_ns`. One use only.
+#[pyclass(frozen)]
+struct StartBarrier {
+    line: Arc<StartLine>,
+}
+
+#[pymethods]
+impl StartBarrier {
+    #[new]
+    fn new(parties: usize) -> PyResult<Self> {
+        if parties == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "parties must be at least 1",
+            ));
+        }
+        Ok(Self {
+            line: Arc::new(StartLine {
+                parties,
+                arrived: Mutex::new(0),
+                changed: Condvar::new(),
+                released_ns: AtomicU64::new(0),
+                aborted: AtomicBool::new(false),
+            }),
+        })
+    }
+
+    #[getter]
+    fn parties(&self) -> usize {
+        self.line.parties
+    }
+
+    /// Parties waiting so far (all of them once released)
+    fn arrived(&self) -> usize {
+        *self.line.arrived.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn is_released(&self) -> bool {
+        self.line.released_ns().is_some()
+    }
+
+    /// `monotonic_ns()` at which the barrier released, or None before then
+    #[getter]
+    fn released_ns(&self) -> Option<u64> {
+        self.line.released_ns()
+    }
+
+    /// Arrive from Python and block, with the GIL released, until every
+    /// party has. Returns False, withdrawing the arrival, if `timeout`
+    /// seconds pass or the barrier is aborted first.
+    #[pyo3(signature = (timeout=None))]
+    fn wait(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<bool> {
+        let timeout = timeout
+            .map(|secs| {
+                Duration::try_from_secs_f64(secs).map_err(|_| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "timeout must be a non-negative number of seconds",
+                    )
+                })
+            })
+            .transpose()?;
+        let line = self.line.clone();
+        Ok(py.allow_threads(move || line.arrive_and_wait(timeout, || false)))
+    }
+
+    /// Give up on the start: waiting producers are cancelled without
+    /// sending, and waiting Python threads return False
+    fn abort(&self) {
+        self.line.aborted.store(true, Ordering::SeqCst);
+        drop(self.line.arrived.lock().unwrap_or_else(|e| e.into_inner()));
+        self.line.changed.notify_all();
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StartBarrier(parties={}, arrived={}, released={})",
+            self.line.parties,
+            self.arrived(),
+            self.is_released()
+        )
+    }
+}
+
+/// Every thread this module has started and not yet seen exit, for
+/// `shutdown()`: benchmark tasks, bare producers, pool workers and noise
+static LIVE_THREADS: Mutex<Vec<Weak<TaskState>>> = Mutex::new(Vec::new());
+
+/// Every ProducerPool not yet dropped, so `shutdown()` can close them
+static LIVE_POOLS: Mutex<Vec<Weak<PoolQueue>>> = Mutex::new(Vec::new());
+
+/// Add `state` to LIVE_THREADS, forgetting threads that have exited
+fn register_thread(state: &Arc<TaskState>) {
+    let mut live = LIVE_THREADS.lock().unwrap_or_else(|e| e.into_inner());
+    live.retain(|state| state.upgrade().is_some_and(|state| !state.is_finished()));
+    live.push(Arc::downgrade(state));
+}
+
+/// Registered state for a thread that has no BenchTask. The thread keeps
+/// the guard until it exits and should stop once the state is cancelled.
+fn track_thread() -> FinishOnDrop {
+    let state = Arc::new(TaskState::default());
+    register_thread(&state);
+    FinishOnDrop(state)
+}
+
+/// Number of threads started by this module that are still running
+#[pyfunction]
+fn live_threads() -> usize {
+    let live = LIVE_THREADS.lock().unwrap_or_else(|e| e.into_inner());
+    live.iter()
+        .filter(|state| state.upgrade().is_some_and(|state| !state.is_finished()))
+        .count()
+}
+
+/// Stop every thread this module has started: cancel benchmark tasks and
+/// bare producers before their next wake, stop noise threads, and shut
+/// down every ProducerPool. Then wait, releasing the GIL, up to `timeout`
+/// seconds in all (forever if None) for them to exit. Returns how many are
+/// still running, so 0 means none leaked. Call it from a pytest fixture or
+/// before closing the loops and FDs the threads were waking. A thread
+/// blocked outside the module (e.g. waiting for the GIL) stops once it
+/// returns.
+#[pyfunction]
+#[pyo3(signature = (timeout=None))]
+fn shutdown(py: Python<'_>, timeout: Option<f64>) -> PyResult<usize> {
+    let timeout = timeout
+        .map(|secs| {
+            Duration::try_from_secs_f64(secs).map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "timeout must be a non-negative number of seconds",
+                )
+            })
+        })
+        .transpose()?;
+    let threads: Vec<_> =
+        std::mem::take(&mut *LIVE_THREADS.lock().unwrap_or_else(|e| e.into_inner()))
+            .into_iter()
+            .filter_map(|state| state.upgrade())
+            .collect();
+    for state in &threads {
+        state.cancelled.store(true, Ordering::Relaxed);
+    }
+    for pool in std::mem::take(&mut *LIVE_POOLS.lock().unwrap_or_else(|e| e.into_inner())) {
+        if let Some(pool) = pool.upgrade() {
+            pool.close();
+        }
+    }
+
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let running: Vec<_> = py.allow_threads(|| {
+        threads
+            .into_iter()
+            .filter(|state| {
+                let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+                !state.wait(remaining)
+            })
+            .collect()
+    });
+    // Keep tracking the stragglers so a later shutdown() can wait for them
+    let stragglers = running.len();
+    let mut live = LIVE_THREADS.lock().unwrap_or_else(|e| e.into_inner());
+    live.extend(running.iter().map(Arc::downgrade));
+    Ok(stragglers)
+}
+
+/// Thread name for a benchmark: its name without the "wakeup" nearly all
+/// share, which would otherwise use up Linux's 15 visible bytes
+fn task_thread_name(benchmark: &str) -> String {
+    benchmark
+        .strip_prefix("wakeup_")
+        .unwrap_or(benchmark)
+        .replacen("_wakeup", "", 1)
+}
+
+/// Run `body` on a new thread, or a pool worker; the body should poll
+/// `is_cancelled()` between wakes and return how many it sent
+fn spawn_bench_task<F>(thread: impl Into<TaskThread>, spec: BenchSpec, body: F) -> BenchTask
+where
+    F: FnOnce(&TaskState) -> u64 + Send + 'static,
+{
+    let state = Arc::new(TaskState {
+        accounting: ACCOUNTING.load(Ordering::Relaxed),
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        perf: PERF_COUNTERS.load(Ordering::Relaxed),
+        drops: spec.drops.clone(),
+        ..Default::default()
+    });
+    let guard = FinishOnDrop(state.clone());
+    register_thread(&state);
+    let start = spec.start.clone().filter(|_| !spec.start_in_body);
+
+    let spawned = thread
+        .into()
+        .run(&task_thread_name(spec.benchmark), move || {
+            let state = &guard.0;
+            if let Some(start) = &start {
+                state.wait_start(start);
+            }
+            state.begin_measurement();
+            // A panic ends the run; the wakes it sent are unknown
+            let sent = run_as_task(state, || body(state)).unwrap_or(0);
+            let end_ns = monotonic_ns();
+            let start = state
+                .measure_start
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take()
+                .expect("measurement started before the body ran");
+            let duration_ns = end_ns - start.ns;
+            #[cfg(all(feature = "perf", target_os = "linux"))]
+            let perf = start.perf.map(PerfRegion::finish);
+            let accounting = start.counters.map(AccountingStart::finish);
+            let dropped = state
+                .drops
+                .as_ref()
+                .zip(start.dropped)
+                .map(|(drops, before)| drops() - before);
+            *state.run.lock().unwrap_or_else(|e| e.into_inner()) = Some(RunStats {
+                sent,
+                cancelled: state.is_cancelled(),
+                duration_ns,
+                dropped,
+                errors: state.fault_count.load(Ordering::Relaxed),
+                accounting,
+                #[cfg(all(feature = "perf", target_os = "linux"))]
+                perf,
+            });
+            drop(guard);
+        });
+    // The job was dropped unrun, which finished the task; the error becomes
+    // its exception()
+    if let Err(error) = spawned {
+        run_as_task(&state, || report_os_error(error));
+    }
+
+    BenchTask { state, spec }
+}
+
+// =============================================================================
+// Producer pools: benchmark threads reused across runs
+// =============================================================================
+
+/// The thread a benchmark task runs on
+enum TaskThread {
+    /// A new thread, placed as given
+    Spawn(ThreadPlacement),
+    /// The next free worker of a ProducerPool, placed when the pool started
+    Pool(Arc<PoolQueue>),
+}
+
+impl From<ThreadPlacement> for TaskThread {
+    fn from(placement: ThreadPlacement) -> Self {
+        Self::Spawn(placement)
+    }
+}
+
+impl TaskThread {
+    fn placement(&self) -> ThreadPlacement {
+        match self {
+            Self::Spawn(placement) => *placement,
+            Self::Pool(queue) => queue.placement,
+        }
+    }
+
+    /// Run `job` on a new thread named `name`, or on a pool worker, which
+    /// keeps the pool's name
+    fn run(self, name: &str, job: impl FnOnce() + Send + 'static) -> std::io::Result<()> {
+        match self {
+            Self::Spawn(placement) => spawn_placed(placement, name, job),
+            Self::Pool(queue) => {
+                queue.submit(Box::new(job));
+                Ok(())
+            }
+        }
+    }
+}
+
+type PoolJob = Box<dyn FnOnce() + Send>;
+
+/// Jobs waiting for a ProducerPool worker
+struct PoolQueue {
+    placement: ThreadPlacement,
+    // None once the pool has been shut down
+    sender: Mutex<Option<std::sync::mpsc::Sender<PoolJob>>>,
+    pending: AtomicU64,
+}
+
+impl PoolQueue {
+    /// Stop accepting jobs; workers exit once the queue drains
+    fn close(&self) {
+        self.sender.lock().unwrap_or_else(|e| e.into_inner()).take();
+    }
+
+    /// Queue `job` for the next free worker. After shutdown the job is
+    /// dropped, which marks its task finished without a result.
+    fn submit(&self, job: PoolJob) {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        let sender = self.sender.lock().unwrap_or_else(|e| e.into_inner());
+        let sent = match sender.as_ref() {
+            Some(sender) => sender.send(job).is_ok(),
+            None => false,
+        };
+        if !sent {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Start pool worker `thread` of `name`: place it, then run jobs until the
+/// queue closes
+fn spawn_pool_worker(
+    queue: Arc<PoolQueue>,
+    receiver: Arc<Mutex<std::sync::mpsc::Receiver<PoolJob>>>,
+    name: &str,
+    thread: usize,
+) -> PyResult<std::thread::JoinHandle<()>> {
+    let builder = queue.placement.thread(name, Some(thread));
+    let guard = track_thread();
+    let worker = builder.spawn(move || {
+        let _guard = guard;
+        queue.placement.apply();
+        loop {
+            let job = receiver.lock().unwrap_or_else(|e| e.into_inner()).recv();
+            let Ok(job) = job else {
+                break;
+            };
+            queue.pending.fetch_sub(1, Ordering::SeqCst);
+            // A panicking job finishes its task (FinishOnDrop); keep the worker
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job));
+        }
+    })?;
+    Ok(worker)
+}
+
+/// Persistent producer threads, started (and optionally pinned or given a
+/// realtime priority) once, then reused by every task submitted. Spawning
+/// a thread per run adds tens of microseconds of noise to its start and
+/// piles up threads during sweeps; a pool pays that once.
+///
+/// Tasks run in submission order, one per worker at a time. Workers are
+/// named `name-0`, `name-1`, ... (after any `set_thread_options()` prefix)
+/// and get `stack_size` bytes of stack, or the configured default.
+#[pyclass]
+struct ProducerPool {
+    queue: Arc<PoolQueue>,
+    workers: Mutex<Vec<std::thread::JoinHandle<()>>>,
+    size: usize,
+}
+
+impl ProducerPool {
+    fn thread(&self) -> PyResult<TaskThread> {
+        if self.is_shutdown() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "ProducerPool is shut down",
+            ));
+        }
+        Ok(TaskThread::Pool(self.queue.clone()))
+    }
+}
+
+#[pymethods]
+impl ProducerPool {
+    #[new]
+    #[pyo3(signature = (workers=1, pin_cpu=None, rt_priority=None, rt_policy="fifo", name="pool", stack_size=None))]
+    fn new(
+        workers: usize,
+        pin_cpu: Option<usize>,
+        rt_priority: Option<i32>,
+        rt_policy: &str,
+        name: &str,
+        stack_size: Option<usize>,
+    ) -> PyResult<Self> {
+        if workers == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "workers must be at least 1",
+            ));
+        }
+        if stack_size == Some(0) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "stack_size must be positive",
+            ));
+        }
+        let placement =
+            ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?.with_stack_size(stack_size);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let queue = Arc::new(PoolQueue {
+            placement,
+            sender: Mutex::new(Some(sender)),
+            pending: AtomicU64::new(0),
+        });
+        let handles = (0..workers)
+            .map(|thread| spawn_pool_worker(queue.clone(), receiver.clone(), name, thread))
+            .collect::<PyResult<_>>()?;
+        LIVE_POOLS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::downgrade(&queue));
+        Ok(Self {
+            queue,
+            workers: Mutex::new(handles),
+            size: workers,
+        })
+    }
+
+    /// Number of worker threads
+    #[getter]
+    fn workers(&self) -> usize {
+        self.size
+    }
+
+    /// Tasks submitted but not yet picked up by a worker
+    fn pending(&self) -> u64 {
+        self.queue.pending.load(Ordering::SeqCst)
+    }
+
+    fn is_shutdown(&self) -> bool {
+        self.queue
+            .sender
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_none()
+    }
+
+    /// As `wakeup_burst()`, on the next free worker
+    #[pyo3(signature = (waker, count=None, duration_secs=None, barrier=None))]
+    fn submit_burst(
+        &self,
+        waker: &Bound<'_, PyAny>,
+        count: Option<usize>,
+        duration_secs: Option<f64>,
+        barrier: Option<&StartBarrier>,
+    ) -> PyResult<BenchTask> {
+        let length = RunLength::parse(count, duration_secs, "count")?;
+        Ok(spawn_burst(
+            "wakeup_burst",
+            resolve_mechanism(waker)?,
+            length,
+            self.thread()?,
+            barrier.map(|barrier| barrier.line.clone()),
+        ))
+    }
+
+    /// As `wakeup_sequence()`, on the next free worker
+    #[pyo3(signature = (waker, coordinator, iterations=None, recorder=None, spin_threshold_micros=0, warmup_iterations=0, duration_secs=None, mode="paced", interval_micros=100, barrier=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn submit_sequence(
+        &self,
+        waker: &Bound<'_, PyAny>,
+        coordinator: &BenchCoordinator,
+        iterations: Option<usize>,
+        recorder: Option<&LatencyRecorder>,
+        spin_threshold_micros: u64,
+        warmup_iterations: usize,
+        duration_secs: Option<f64>,
+        mode: &str,
+        interval_micros: u64,
+        barrier: Option<&StartBarrier>,
+    ) -> PyResult<BenchTask> {
+        let length = RunLength::parse(iterations, duration_secs, "iterations")?;
+        let pacing = SequencePacing::new(mode, interval_micros)?;
+        Ok(spawn_sequence(
+            "wakeup_sequence",
+            resolve_mechanism(waker)?,
+            coordinator,
+            length,
+            recorder,
+            self.thread()?,
+            spin_threshold_micros,
+            warmup_iterations,
+            pacing,
+            barrier.map(|barrier| barrier.line.clone()),
+        ))
+    }
+
+    /// Stop accepting tasks. Workers finish the tasks already submitted,
+    /// then exit; with `wait`, block (without the GIL) until they have.
+    #[pyo3(signature = (wait=true))]
+    fn shutdown(&self, py: Python<'_>, wait: bool) {
+        self.queue.close();
+        if !wait {
+            return;
+        }
+        let handles = std::mem::take(&mut *self.workers.lock().unwrap_or_else(|e| e.into_inner()));
+        py.allow_threads(|| {
+            for handle in handles {
+                let _ = handle.join();
+            }
+        });
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, py: Python<'_>, _args: &Bound<'_, pyo3::types::PyTuple>) {
+        self.shutdown(py, true);
+    }
+}
+
+impl Drop for ProducerPool {
+    /// Workers exit once the queue drains; they aren't joined here
+    fn drop(&mut self) {
+        self.queue.close();
+    }
+}
+
+// =============================================================================
+// Benchmark results: structured summaries with a stable JSON schema
+// =============================================================================
+
+/// Version of the `BenchmarkResult.to_json()` schema; bump on incompatible changes
+const RESULT_SCHEMA_VERSION: u32 = 1;
+
+/// `json.dumps` with sorted keys, so exported results can be archived and diffed
+fn dump_json(value: &Bound<'_, PyAny>, indent: Option<usize>) -> PyResult<String> {
+    let py = value.py();
+    let kwargs = pyo3::types::PyDict::new(py);
+    kwargs.set_item("sort_keys", true)?;
+    kwargs.set_item("indent", indent)?;
+    py.import("json")?
+        .call_method("dumps", (value,), Some(&kwargs))?
+        .extract()
+}
+
+/// A configuration value recorded with a benchmark result
+#[derive(Clone, Copy)]
+enum ConfigValue {
+    None,
+    Int(i64),
+    Float(f64),
+    Str(&'static str),
+}
+
+/// Reads a waker's cumulative dropped-wake counter
+type DropCounter = Arc<dyn Fn() -> u64 + Send + Sync>;
+
+/// What a benchmark task was asked to do, captured on the Python thread when
+/// it is spawned
+struct BenchSpec {
+    benchmark: &'static str,
+    requested: u64,
+    config: Vec<(&'static str, ConfigValue)>,
+    recorder: Option<SharedRecording>,
+    drops: Option<DropCounter>,
+    steps: Option<SharedSteps>,
+    start: Option<Arc<StartLine>>,
+    /// The body waits at `start` on each of its threads, rather than the
+    /// task waiting once before the body runs
+    start_in_body: bool,
+}
+
+impl BenchSpec {
+    fn new(benchmark: &'static str, requested: usize, placement: &ThreadPlacement) -> Self {
+        Self {
+            benchmark,
+            requested: requested as u64,
+            config: placement.describe(),
+            recorder: None,
+            drops: None,
+            steps: None,
+            start: None,
+            start_in_body: false,
+        }
+    }
+
+    fn config(mut self, name: &'static str, value: ConfigValue) -> Self {
+        self.config.push((name, value));
+        self
+    }
+
+    /// Record a producer delay: `delay_micros`, and the distribution it was
+    /// drawn from if any
+    fn delay(self, delay: DrawnDelay) -> Self {
+        match delay.distribution {
+            None => self.config(
+                "delay_micros",
+                ConfigValue::Int(delay.duration.as_micros() as i64),
+            ),
+            Some(name) => self
+                .config(
+                    "delay_micros",
+                    ConfigValue::Float(delay.duration.as_secs_f64() * 1e6),
+                )
+                .config("delay_distribution", ConfigValue::Str(name)),
+        }
+    }
+
+    /// Report latency percentiles from this recorder
+    fn recorder(mut self, recorder: Option<SharedRecording>) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
+    /// Report how many wakes the waker dropped while the task ran
+    fn drops(mut self, drops: DropCounter) -> Self {
+        self.drops = Some(drops);
+        self
+    }
+
+    /// Report the per-step results the task appends here
+    fn steps(mut self, steps: SharedSteps) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+
+    /// Hold the thread at `start` until every party has arrived, then begin
+    /// measuring, and report when it was released
+    fn start_line(mut self, start: Option<Arc<StartLine>>) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// As `start_line`, for a body that waits at `start` itself, once on
+    /// each of its threads
+    fn start_line_in_body(mut self, start: Option<Arc<StartLine>>) -> Self {
+        self.start = start;
+        self.start_in_body = true;
+        self
+    }
+}
+
+/// One step of a load ramp, as measured by the producer thread
+#[derive(Clone, Copy)]
+struct StepStats {
+    rate_per_sec: f64,
+    sent: u64,
+    duration_ns: u64,
+    corrected: u64,
+    latency: LatencySummary,
+    /// For a storm scenario, the phase (`"storm"` or `"quiet"`) and cycle
+    phase: Option<(&'static str, usize)>,
+}
+
+impl StepStats {
+    fn to_dict<'py>(self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let throughput = if self.duration_ns == 0 {
+            0.0
+        } else {
+            self.sent as f64 * 1e9 / self.duration_ns as f64
+        };
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("rate_per_sec", self.rate_per_sec)?;
+        dict.set_item("sent", self.sent)?;
+        dict.set_item("duration_ns", self.duration_ns)?;
+        dict.set_item("throughput_per_sec", throughput)?;
+        dict.set_item("corrected", self.corrected)?;
+        dict.set_item("latency_ns", self.latency.to_dict(py)?)?;
+        if let Some((phase, cycle)) = self.phase {
+            dict.set_item("phase", phase)?;
+            dict.set_item("cycle", cycle)?;
+        }
+        Ok(dict)
+    }
+}
+
+/// Per-step results shared between a ramp's producer thread and its BenchTask
+type SharedSteps = Arc<Mutex<Vec<StepStats>>>;
+
+/// What the producer thread measured about its own run
+#[derive(Clone, Copy)]
+struct RunStats {
+    sent: u64,
+    cancelled: bool,
+    duration_ns: u64,
+    dropped: Option<u64>,
+    /// Faults the thread reported, including a panic
+    errors: u64,
+    accounting: Option<WakeAccounting>,
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    perf: Option<PerfStats>,
+}
+
+/// Statistics (ns) of a latency histogram at the time they were taken.
+/// Read-only and dict-like: `summary["p99"]`, `dict(summary)`, `summary.p99`.
+#[pyclass(frozen)]
+#[derive(Clone, Copy)]
+struct LatencySummary {
+    #[pyo3(get)]
+    count: u64,
+    #[pyo3(get)]
+    min: u64,
+    #[pyo3(get)]
+    max: u64,
+    #[pyo3(get)]
+    mean: f64,
+    #[pyo3(get)]
+    stddev: f64,
+    #[pyo3(get)]
+    p50: u64,
+    #[pyo3(get)]
+    p90: u64,
+    #[pyo3(get)]
+    p99: u64,
+    #[pyo3(get)]
+    p999: u64,
+    #[pyo3(get)]
+    p9999: u64,
+}
+
+impl LatencySummary {
+    const KEYS: [&'static str; 10] = [
+        "count", "min", "max", "mean", "stddev", "p50", "p90", "p99", "p999", "p9999",
+    ];
+
+    fn from_histogram(hist: &Histogram<u64>) -> Self {
+        Self {
+            count: hist.len(),
+            min: hist.min(),
+            max: hist.max(),
+            mean: hist.mean(),
+            stddev: hist.stdev(),
+            p50: hist.value_at_quantile(0.5),
+            p90: hist.value_at_quantile(0.9),
+            p99: hist.value_at_quantile(0.99),
+            p999: hist.value_at_quantile(0.999),
+            p9999: hist.value_at_quantile(0.9999),
+        }
+    }
+
+    fn to_dict<'py>(self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("count", self.count)?;
+        dict.set_item("min", self.min)?;
+        dict.set_item("max", self.max)?;
+        dict.set_item("mean", self.mean)?;
+        dict.set_item("stddev", self.stddev)?;
+        dict.set_item("p50", self.p50)?;
+        dict.set_item("p90", self.p90)?;
+        dict.set_item("p99", self.p99)?;
+        dict.set_item("p999", self.p999)?;
+        dict.set_item("p9999", self.p9999)?;
+        Ok(dict)
+    }
+}
+
+#[pymethods]
+impl LatencySummary {
+    fn keys(&self) -> Vec<&'static str> {
+        Self::KEYS.to_vec()
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, key: &str) -> PyResult<Bound<'py, PyAny>> {
+        self.to_dict(py)?
+            .get_item(key)?
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>(key.to_string()))
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        Self::KEYS.contains(&key)
+    }
+
+    fn __len__(&self) -> usize {
+        Self::KEYS.len()
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyIterator>> {
+        pyo3::types::PyList::new(py, Self::KEYS)?.try_iter()
+    }
+
+    #[pyo3(name = "to_dict")]
+    fn py_to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        self.to_dict(py)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LatencySummary(count={}, min={}, p50={}, p99={}, p9999={}, max={}, mean={:.1}, stddev={:.1})",
+            self.count, self.min, self.p50, self.p99, self.p9999, self.max, self.mean, self.stddev
+        )
+    }
+}
+
+/// Outcome of a finished benchmark task: counts, duration, throughput, latency
+/// percentiles (when a recorder was attached), drop counters (for pipe-based
+/// wakers), faults, and the configuration it ran with
+#[pyclass(frozen)]
+struct BenchmarkResult {
+    #[pyo3(get)]
+    benchmark: &'static str,
+    #[pyo3(get)]
+    requested: u64,
+    #[pyo3(get)]
+    sent: u64,
+    #[pyo3(get)]
+    cancelled: bool,
+    #[pyo3(get)]
+    duration_ns: u64,
+    #[pyo3(get)]
+    dropped: Option<u64>,
+    /// Panics, failed wake syscalls and Python errors on the producer thread
+    #[pyo3(get)]
+    errors: u64,
+    /// `monotonic_ns()` at which the task's StartBarrier released it, or None
+    #[pyo3(get)]
+    released_ns: Option<u64>,
+    accounting: Option<WakeAccounting>,
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    perf: Option<PerfStats>,
+    latency: Option<LatencySummary>,
+    steps: Option<Vec<StepStats>>,
+    config: Vec<(&'static str, ConfigValue)>,
+}
+
+#[pymethods]
+impl BenchmarkResult {
+    /// Wakes sent per second of the producer thread's run time
+    #[getter]
+    fn throughput_per_sec(&self) -> f64 {
+        if self.duration_ns == 0 {
+            return 0.0;
+        }
+        self.sent as f64 * 1e9 / self.duration_ns as f64
+    }
+
+    /// Latency percentiles in ns, or None if no recorder was attached
+    #[getter]
+    fn latency<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, pyo3::types::PyDict>>> {
+        self.latency.map(|latency| latency.to_dict(py)).transpose()
+    }
+
+    /// Allocations, syscalls and blocking waits on the producer thread, in
+    /// total and per wake, or None unless `set_accounting(True)` was on
+    #[getter]
+    fn accounting<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<Option<Bound<'py, pyo3::types::PyDict>>> {
+        self.accounting
+            .map(|accounting| accounting.to_dict(py, self.sent))
+            .transpose()
+    }
+
+    /// Performance counters over the measured run, for the producer thread
+    /// (`thread`, with per-wake figures) and the whole process (`process`),
+    /// or None unless built with the `perf` feature and `set_perf_counters(True)`
+    #[getter]
+    fn perf<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, pyo3::types::PyDict>>> {
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        if let Some(perf) = self.perf {
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("thread", perf.thread.to_dict(py, Some(self.sent))?)?;
+            dict.set_item("process", perf.process.to_dict(py, Some(self.sent))?)?;
+            return Ok(Some(dict));
+        }
+        let _ = py;
+        Ok(None)
+    }
+
+    /// For a ramp, one dict per step that ran: `rate_per_sec`, `sent`,
+    /// `duration_ns`, `throughput_per_sec`, `corrected` and `latency_ns`.
+    /// For a storm, one per phase of each cycle, also tagged with `phase`
+    /// and `cycle`. None for other benchmarks.
+    #[getter]
+    fn steps<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, pyo3::types::PyList>>> {
+        let Some(steps) = &self.steps else {
+            return Ok(None);
+        };
+        let list = pyo3::types::PyList::empty(py);
+        for step in steps {
+            list.append(step.to_dict(py)?)?;
+        }
+        Ok(Some(list))
+    }
+
+    /// Options the task ran with (thread placement plus benchmark parameters)
+    #[getter]
+    fn config<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let config = pyo3::types::PyDict::new(py);
+        for &(name, value) in &self.config {
+            match value {
+                ConfigValue::None => config.set_item(name, py.None())?,
+                ConfigValue::Int(value) => config.set_item(name, value)?,
+                ConfigValue::Float(value) => config.set_item(name, value)?,
+                ConfigValue::Str(value) => config.set_item(name, value)?,
+            }
+        }
+        Ok(config)
+    }
+
+    /// All fields as a dict, in the same shape as `to_json()`
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("schema_version", RESULT_SCHEMA_VERSION)?;
+        dict.set_item("benchmark", self.benchmark)?;
+        dict.set_item("requested", self.requested)?;
+        dict.set_item("sent", self.sent)?;
+        dict.set_item("cancelled", self.cancelled)?;
+        dict.set_item("duration_ns", self.duration_ns)?;
+        dict.set_item("throughput_per_sec", self.throughput_per_sec())?;
+        dict.set_item("dropped", self.dropped)?;
+        dict.set_item("errors", self.errors)?;
+        dict.set_item("released_ns", self.released_ns)?;
+        dict.set_item("latency_ns", self.latency(py)?)?;
+        dict.set_item("steps", self.steps(py)?)?;
+        dict.set_item("accounting", self.accounting(py)?)?;
+        dict.set_item("perf", self.perf(py)?)?;
+        dict.set_item("config", self.config(py)?)?;
+        Ok(dict)
+    }
+
+    /// Serialize with sorted keys, so runs can be archived and diffed
+    #[pyo3(signature = (indent=None))]
+    fn to_json(&self, py: Python<'_>, indent: Option<usize>) -> PyResult<String> {
+        dump_json(self.to_dict(py)?.as_any(), indent)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "BenchmarkResult(benchmark={:?}, sent={}, duration_ns={}, throughput_per_sec={:.0})",
+            self.benchmark,
+            self.sent,
+            self.duration_ns,
+            self.throughput_per_sec()
+        )
+    }
+
+    /// Write this result to `path` as indented JSON, for `check_regression()`
+    /// in later runs
+    fn save_baseline(&self, py: Python<'_>, path: std::path::PathBuf) -> PyResult<()> {
+        let json = self.to_json(py, Some(2))?;
+        py.allow_threads(|| std::fs::write(&path, json + "\n"))
+            .map_err(PyErr::from)
+    }
+
+    /// Compare against the baseline saved at `path`. A metric regresses when
+    /// throughput falls, or a latency percentile rises, by more than
+    /// `tolerance_pct` percent. Raises ValueError if the baseline is for a
+    /// different benchmark or schema version.
+    #[pyo3(signature = (path, tolerance_pct=5.0))]
+    fn check_regression(
+        &self,
+        py: Python<'_>,
+        path: std::path::PathBuf,
+        tolerance_pct: f64,
+    ) -> PyResult<RegressionReport> {
+        if tolerance_pct.is_nan() || tolerance_pct < 0.0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "tolerance_pct must be non-negative",
+            ));
+        }
+        let text = py
+            .allow_threads(|| std::fs::read_to_string(&path))
+            .map_err(PyErr::from)?;
+        let baseline = py.import("json")?.call_method1("loads", (text,))?;
+        let field = |name: &str| -> PyResult<Bound<'_, PyAny>> {
+            baseline.get_item(name).map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "baseline {} has no {name:?}",
+                    path.display()
+                ))
+            })
+        };
+
+        let schema_version: u32 = field("schema_version")?.extract()?;
+        if schema_version != RESULT_SCHEMA_VERSION {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "baseline has schema version {schema_version}, expected {RESULT_SCHEMA_VERSION}"
+            )));
+        }
+        let benchmark: String = field("benchmark")?.extract()?;
+        if benchmark != self.benchmark {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "baseline is for {benchmark:?}, not {:?}",
+                self.benchmark
+            )));
+        }
+
+        let mut checks = vec![MetricCheck::new(
+            "throughput_per_sec",
+            field("throughput_per_sec")?.extract()?,
+            self.throughput_per_sec(),
+            true,
+            tolerance_pct,
+        )];
+        let baseline_latency = field("latency_ns")?;
+        if let (Some(latency), false) = (self.latency, baseline_latency.is_none()) {
+            let percentiles = [
+                ("p50", latency.p50),
+                ("p90", latency.p90),
+                ("p99", latency.p99),
+                ("p999", latency.p999),
+            ];
+            for (name, current) in percentiles {
+                checks.push(MetricCheck::new(
+                    name,
+                    baseline_latency.get_item(name)?.extract()?,
+                    current as f64,
+                    false,
+                    tolerance_pct,
+                ));
+            }
+        }
+
+        Ok(RegressionReport {
+            benchmark: self.benchmark,
+            tolerance_pct,
+            checks,
+        })
+    }
+}
+
+/// One metric compared against its baseline value
+struct MetricCheck {
+    metric: &'static str,
+    baseline: f64,
+    current: f64,
+    /// Relative change in percent, or None when the baseline is zero
+    change_pct: Option<f64>,
+    higher_is_better: bool,
+    regressed: bool,
+}
+
+impl MetricCheck {
+    fn new(
+        metric: &'static str,
+        baseline: f64,
+        current: f64,
+        higher_is_better: bool,
+        tolerance_pct: f64,
+    ) -> Self {
+        let change_pct = (baseline > 0.0).then(|| (current - baseline) / baseline * 100.0);
+        let regressed = change_pct.is_some_and(|change| {
+            if higher_is_better {
+                change < -tolerance_pct
+            } else {
+                change > tolerance_pct
+            }
+        });
+        Self {
+            metric,
+            baseline,
+            current,
+            change_pct,
+            higher_is_better,
+            regressed,
+        }
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("metric", self.metric)?;
+        dict.set_item("baseline", self.baseline)?;
+        dict.set_item("current", self.current)?;
+        dict.set_item("change_pct", self.change_pct)?;
+        dict.set_item("higher_is_better", self.higher_is_better)?;
+        dict.set_item("regressed", self.regressed)?;
+        Ok(dict)
+    }
+}
+
+/// Outcome of `BenchmarkResult.check_regression()`: throughput and latency
+/// percentiles compared against a stored baseline
+#[pyclass(frozen)]
+struct RegressionReport {
+    #[pyo3(get)]
+    benchmark: &'static str,
+    #[pyo3(get)]
+    tolerance_pct: f64,
+    checks: Vec<MetricCheck>,
+}
+
+#[pymethods]
+impl RegressionReport {
+    /// True if any metric regressed beyond the tolerance
+    #[getter]
+    fn regressed(&self) -> bool {
+        self.checks.iter().any(|check| check.regressed)
+    }
+
+    /// Names of the metrics that regressed
+    fn regressions(&self) -> Vec<&'static str> {
+        self.checks
+            .iter()
+            .filter(|check| check.regressed)
+            .map(|check| check.metric)
+            .collect()
+    }
+
+    /// Every metric compared: `metric`, `baseline`, `current`, `change_pct`
+    /// (None for a zero baseline), `higher_is_better` and `regressed`
+    fn checks<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, pyo3::types::PyDict>>> {
+        self.checks.iter().map(|check| check.to_dict(py)).collect()
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("benchmark", self.benchmark)?;
+        dict.set_item("tolerance_pct", self.tolerance_pct)?;
+        dict.set_item("regressed", self.regressed())?;
+        dict.set_item("checks", self.checks(py)?)?;
+        Ok(dict)
+    }
+
+    #[pyo3(signature = (indent=None))]
+    fn to_json(&self, py: Python<'_>, indent: Option<usize>) -> PyResult<String> {
+        dump_json(self.to_dict(py)?.as_any(), indent)
+    }
+
+    fn __str__(&self) -> String {
+        let mut table = format!(
+            "{:<20} {:>14} {:>14} {:>9}\n",
+            "Metric", "Baseline", "Current", "Change"
+        );
+        for check in &self.checks {
+            let change = check
+                .change_pct
+                .map_or_else(|| "n/a".to_string(), |change| format!("{change:+.1}%"));
+            table.push_str(&format!(
+                "{:<20} {:>14.1} {:>14.1} {:>9}{}\n",
+                check.metric,
+                check.baseline,
+                check.current,
+                change,
+                if check.regressed { "  REGRESSED" } else { "" }
+            ));
+        }
+        table
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RegressionReport(benchmark={:?}, tolerance_pct={}, regressions={:?})",
+            self.benchmark,
+            self.tolerance_pct,
+            self.regressions()
+        )
+    }
+}
+
+// =============================================================================
+// Wake mechanisms: one producer-side interface over every approach
+// =============================================================================
+
+/// Producer-side wake outcomes a mechanism tracks itself. `None` means the
+/// mechanism doesn't count that outcome (or can't lose a wake that way).
+#[derive(Clone, Copy, Default)]
+struct WakeStats {
+    dropped: Option<u64>,
+    errors: Option<u64>,
+}
+
+/// The producer side of a wake approach. Every benchmark driver (burst,
+/// sequence, rate, multi-producer) is written against this trait, so it runs
+/// with any registered mechanism.
+trait WakeMechanism: Send + Sync {
+    /// Send one wake from the calling thread
+    fn wake(&self);
+
+    /// Send `n` events. Mechanisms whose consumer can count events (pipe
+    /// bytes, the eventfd counter) carry them in as few syscalls as they can;
+    /// the rest send `n` separate wakes.
+    fn wake_many(&self, n: u64) {
+        (0..n).for_each(|_| self.wake());
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats::default()
+    }
+
+    /// Settings that change what the mechanism measures, for a benchmark
+    /// result's config (e.g. a pipe's behavior when full)
+    fn config(&self) -> Vec<(&'static str, ConfigValue)> {
+        Vec::new()
+    }
+
+    /// Called once by a producer after its last wake, e.g. to flush a batch
+    fn finish(&self) {}
+}
+
+/// A mechanism's producer handle paired with its registry name
+#[derive(Clone)]
+struct Mechanism {
+    name: &'static str,
+    handle: Arc<dyn WakeMechanism>,
+    events_per_wake: u64,
+}
+
+impl Mechanism {
+    fn new(name: &'static str, handle: impl WakeMechanism + 'static) -> Self {
+        Self {
+            name,
+            handle: Arc::new(handle),
+            events_per_wake: 1,
+        }
+    }
+
+    /// Cumulative drop count for `BenchSpec.drops`, if the mechanism counts drops
+    fn drop_counter(&self) -> Option<DropCounter> {
+        self.handle.stats().dropped?;
+        let handle = self.handle.clone();
+        Some(Arc::new(move || handle.stats().dropped.unwrap_or(0)))
+    }
+
+    /// The same mechanism, sending `events` events with each wake
+    fn with_events_per_wake(self, events: u64) -> Self {
+        if events == 1 {
+            return self;
+        }
+        Self {
+            name: self.name,
+            handle: Arc::new(ManyPerWake {
+                inner: self.handle,
+                events,
+            }),
+            events_per_wake: self.events_per_wake * events,
+        }
+    }
+
+    /// Describe the mechanism to `spec` and report its drops
+    fn annotate(&self, spec: BenchSpec) -> BenchSpec {
+        let mut spec = spec.config("mechanism", ConfigValue::Str(self.name));
+        for (name, value) in self.handle.config() {
+            spec = spec.config(name, value);
+        }
+        if self.events_per_wake != 1 {
+            spec = spec.config(
+                "events_per_wake",
+                ConfigValue::Int(self.events_per_wake as i64),
+            );
+        }
+        match self.drop_counter() {
+            Some(drops) => spec.drops(drops),
+            None => spec,
+        }
+    }
+}
+
+/// A mechanism whose every wake carries `events` events through `wake_many`
+struct ManyPerWake {
+    inner: Arc<dyn WakeMechanism>,
+    events: u64,
+}
+
+impl WakeMechanism for ManyPerWake {
+    fn wake(&self) {
+        self.inner.wake_many(self.events);
+    }
+
+    fn wake_many(&self, n: u64) {
+        self.inner.wake_many(n.saturating_mul(self.events));
+    }
+
+    fn stats(&self) -> WakeStats {
+        self.inner.stats()
+    }
+
+    fn config(&self) -> Vec<(&'static str, ConfigValue)> {
+        self.inner.config()
+    }
+
+    fn finish(&self) {
+        self.inner.finish();
+    }
+}
+
+#[cfg(unix)]
+impl WakeMechanism for FdWakerHandle {
+    fn wake(&self) {
+        FdWakerHandle::wake(self);
+    }
+
+    fn wake_many(&self, n: u64) {
+        FdWakerHandle::wake_many(self, n);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: Some(self.write_stats.dropped.load(Ordering::Relaxed)),
+            errors: Some(self.write_stats.errors.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn config(&self) -> Vec<(&'static str, ConfigValue)> {
+        self.on_full.config()
+    }
+}
+
+impl WakeMechanism for CallbackWakerHandle {
+    fn wake(&self) {
+        CallbackWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: None,
+            errors: Some(self.outcomes.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Callback wakes that pass the waker's next sequence number to the callback
+struct SequencedCallback(CallbackWakerHandle);
+
+impl WakeMechanism for SequencedCallback {
+    fn wake(&self) {
+        self.0.wake_sequenced();
+    }
+
+    fn stats(&self) -> WakeStats {
+        self.0.stats()
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl WakeMechanism for EventFdWakerHandle {
+    fn wake(&self) {
+        EventFdWakerHandle::wake(self);
+    }
+
+    fn wake_many(&self, n: u64) {
+        EventFdWakerHandle::wake_many(self, n);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: None,
+            errors: Some(self.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl WakeMechanism for SocketWakerHandle {
+    fn wake(&self) {
+        SocketWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        if !self.transport.counts_drops() {
+            return WakeStats::default();
+        }
+        WakeStats {
+            dropped: Some(self.stats.dropped.load(Ordering::Relaxed)),
+            errors: Some(self.stats.errors.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn config(&self) -> Vec<(&'static str, ConfigValue)> {
+        vec![("transport", ConfigValue::Str(self.transport.name()))]
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl WakeMechanism for KqueueUserWakerHandle {
+    fn wake(&self) {
+        KqueueUserWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: None,
+            errors: Some(self.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl WakeMechanism for CoalescingFdWakerHandle {
+    fn wake(&self) {
+        CoalescingFdWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        self.inner.stats()
+    }
+}
+
+impl WakeMechanism for BatchedCallbackWakerHandle {
+    fn wake(&self) {
+        BatchedCallbackWakerHandle::wake(self);
+    }
+
+    fn finish(&self) {
+        self.flush();
+    }
+}
+
+impl WakeMechanism for TrioWakerHandle {
+    fn wake(&self) {
+        TrioWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: None,
+            errors: Some(self.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl WakeMechanism for GeventWakerHandle {
+    fn wake(&self) {
+        GeventWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: None,
+            errors: Some(self.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl WakeMechanism for SignalWakerHandle {
+    fn wake(&self) {
+        SignalWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: None,
+            errors: Some(self.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl WakeMechanism for PendingCallWakerHandle {
+    fn wake(&self) {
+        PendingCallWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: Some(self.state.dropped.load(Ordering::Relaxed)),
+            errors: None,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl WakeMechanism for RemoteWakerClientHandle {
+    fn wake(&self) {
+        RemoteWakerClientHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: Some(self.stats.dropped.load(Ordering::Relaxed)),
+            errors: Some(self.stats.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl WakeMechanism for ShmWakerClientHandle {
+    fn wake(&self) {
+        ShmWakerClientHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: None,
+            errors: Some(self.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl WakeMechanism for FutexWakerHandle {
+    fn wake(&self) {
+        FutexWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: None,
+            errors: Some(self.state.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl WakeMechanism for CoroutineWakerHandle {
+    fn wake(&self) {
+        CoroutineWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: None,
+            errors: Some(self.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl WakeMechanism for ThreadingEventWakerHandle {
+    fn wake(&self) {
+        ThreadingEventWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeStats {
+            dropped: None,
+            errors: Some(self.errors.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(windows)]
+impl WakeMechanism for IocpWakerHandle {
+    fn wake(&self) {
+        IocpWakerHandle::wake(self);
+    }
+}
+
+impl WakeMechanism for OneShotWakerHandle {
+    fn wake(&self) {
+        OneShotWakerHandle::wake(self);
+    }
+
+    fn config(&self) -> Vec<(&'static str, ConfigValue)> {
+        vec![("mode", ConfigValue::Str(self.mode.name()))]
+    }
+}
+
+/// Each wake pushes the channel's next sequence number, then wakes the loop
+#[cfg(unix)]
+impl WakeMechanism for MpscChannelHandle {
+    fn wake(&self) {
+        let seq = self.next.fetch_add(1, Ordering::Relaxed);
+        self.send(Payload::Int(seq as i64));
+    }
+
+    fn stats(&self) -> WakeStats {
+        WakeMechanism::stats(&self.waker)
+    }
+
+    fn config(&self) -> Vec<(&'static str, ConfigValue)> {
+        self.waker.on_full.config()
+    }
+}
+
+#[cfg(unix)]
+impl WakeMechanism for SequencedFdWakerHandle {
+    fn wake(&self) {
+        SequencedFdWakerHandle::wake(self);
+    }
+
+    fn stats(&self) -> WakeStats {
+        self.inner.stats()
+    }
+
+    fn config(&self) -> Vec<(&'static str, ConfigValue)> {
+        self.inner.config()
+    }
+}
+
+type ResolveMechanism = Box<dyn Fn(&Bound<'_, PyAny>) -> Option<PyResult<Mechanism>> + Send + Sync>;
+
+/// Maps a Python waker class to its mechanism
+struct MechanismEntry {
+    name: &'static str,
+    resolve: ResolveMechanism,
+}
+
+impl MechanismEntry {
+    fn new<T, M>(name: &'static str, handle: fn(&T, Python<'_>) -> PyResult<M>) -> Self
+    where
+        T: pyo3::PyClass,
+        M: WakeMechanism + 'static,
+    {
+        Self {
+            name,
+            resolve: Box::new(move |waker| {
+                let waker = waker.downcast::<T>().ok()?;
+                Some((|| {
+                    let handle = handle(&*waker.try_borrow()?, waker.py())?;
+                    Ok(Mechanism::new(name, handle))
+                })())
+            }),
+        }
+    }
+}
+
+/// Every waker class the generic drivers accept. Adding a mechanism means
+/// implementing `WakeMechanism` for its handle and adding an entry here.
+fn mechanism_registry() -> &'static [MechanismEntry] {
+    static REGISTRY: std::sync::OnceLock<Vec<MechanismEntry>> = std::sync::OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut registry = Vec::new();
+        #[cfg(unix)]
+        registry.push(MechanismEntry::new("fd", |w: &FdWaker, _| w.handle()));
+        registry.push(MechanismEntry::new("callback", |w: &CallbackWaker, py| {
+            w.handle(py)
+        }));
+        #[cfg(target_os = "linux")]
+        registry.push(MechanismEntry::new("eventfd", |w: &EventFdWaker, _| {
+            Ok(w.handle())
+        }));
+        registry.push(MechanismEntry::new("socket", |w: &SocketWaker, _| {
+            Ok(w.handle())
+        }));
+        #[cfg(target_os = "macos")]
+        registry.push(MechanismEntry::new("kqueue", |w: &KqueueUserWaker, _| {
+            Ok(w.handle())
+        }));
+        #[cfg(unix)]
+        registry.push(MechanismEntry::new(
+            "coalescing",
+            |w: &CoalescingFdWaker, _| w.handle(),
+        ));
+        registry.push(MechanismEntry::new(
+            "batched_callback",
+            |w: &BatchedCallbackWaker, py| w.handle(py),
+        ));
+        registry.push(MechanismEntry::new("trio", |w: &TrioWaker, py| {
+            Ok(w.handle(py))
+        }));
+        registry.push(MechanismEntry::new("gevent", |w: &GeventWaker, py| {
+            w.handle(py)
+        }));
+        #[cfg(unix)]
+        registry.push(MechanismEntry::new("signal", |w: &SignalWaker, py| {
+            w.handle(py)
+        }));
+        registry.push(MechanismEntry::new(
+            "pending_call",
+            |w: &PendingCallWaker, _| Ok(w.handle()),
+        ));
+        #[cfg(unix)]
+        registry.push(MechanismEntry::new("remote", |w: &RemoteWakerClient, _| {
+            Ok(w.handle())
+        }));
+        #[cfg(unix)]
+        registry.push(MechanismEntry::new("shm", |w: &ShmWakerClient, _| {
+            Ok(w.handle())
+        }));
+        // Wakes the urgent pipe; priority_wakeup_inversion drives both
+        #[cfg(unix)]
+        registry.push(MechanismEntry::new("priority", |w: &PriorityFdWaker, _| {
+            w.urgent.handle()
+        }));
+        #[cfg(target_os = "linux")]
+        registry.push(MechanismEntry::new("futex", |w: &FutexWaker, _| {
+            Ok(w.handle())
+        }));
+        registry.push(MechanismEntry::new(
+            "coroutine",
+            |w: &CoroutineWaker, py| Ok(w.handle(py)),
+        ));
+        registry.push(MechanismEntry::new(
+            "threading_event",
+            |w: &ThreadingEventWaker, py| Ok(w.handle(py)),
+        ));
+        #[cfg(windows)]
+        registry.push(MechanismEntry::new("iocp", |w: &IocpWaker, _| w.handle()));
+        #[cfg(unix)]
+        registry.push(MechanismEntry::new(
+            "sequenced_fd",
+            |w: &SequencedFdWaker, _| w.handle(),
+        ));
+        registry.push(MechanismEntry::new("oneshot", |w: &OneShotWaker, _| {
+            Ok(w.handle())
+        }));
+        #[cfg(unix)]
+        registry.push(MechanismEntry::new("channel", |w: &MpscChannel, _| {
+            w.handle()
+        }));
+        registry.push(MechanismEntry::new("group", |w: &WakerGroup, _| {
+            Ok(w.handle())
+        }));
+        registry
+    })
+}
+
+/// Look up the mechanism for a Python waker object
+fn resolve_mechanism(waker: &Bound<'_, PyAny>) -> PyResult<Mechanism> {
+    // A Waker from best_for() drives the waker it wraps
+    if let Ok(chosen) = waker.downcast::<Waker>() {
+        return resolve_mechanism(chosen.get().waker.bind(waker.py()));
+    }
+    mechanism_registry()
+        .iter()
+        .find_map(|entry| (entry.resolve)(waker))
+        .unwrap_or_else(|| {
+            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "{} is not a supported waker; mechanisms: {}",
+                waker.get_type().name()?,
+                mechanisms().join(", ")
+            )))
+        })
+}
+
+/// Names of the wake mechanisms available on this platform, in registry order
+#[pyfunction]
+fn mechanisms() -> Vec<&'static str> {
+    mechanism_registry()
+        .iter()
+        .map(|entry| entry.name)
+        .collect()
+}
+
+/// The mechanism behind `waker` and its producer-side counters: `dropped`
+/// and `errors`, or None where the mechanism doesn't count them
+#[pyfunction]
+fn wake_stats<'py>(
+    py: Python<'py>,
+    waker: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+    let mechanism = resolve_mechanism(waker)?;
+    let stats = mechanism.handle.stats();
+    let dict = pyo3::types::PyDict::new(py);
+    dict.set_item("mechanism", mechanism.name)?;
+    dict.set_item("dropped", stats.dropped)?;
+    dict.set_item("errors", stats.errors)?;
+    Ok(dict)
+}
+
+// =============================================================================
+// Automatic mechanism selection: the lowest-latency waker for a given loop
+// =============================================================================
+
+/// The mechanism `Waker.best_for()` picks for a loop implementation, as named
+/// by `loop_implementation()` (or `"trio"`)
+fn best_mechanism(implementation: &str) -> &'static str {
+    match implementation {
+        "trio" => "trio",
+        #[cfg(windows)]
+        "proactor" => "iocp",
+        // Loops that implement add_reader: the cheapest readable-FD wake
+        #[cfg(target_os = "linux")]
+        "selector" | "uvloop" => "eventfd",
+        #[cfg(target_os = "macos")]
+        "selector" | "uvloop" => "kqueue",
+        #[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
+        "selector" | "uvloop" => "fd",
+        #[cfg(windows)]
+        "selector" => "socket",
+        _ => "callback",
+    }
+}
+
+/// A waker chosen and wired up for an event loop by `Waker.best_for()`, so
+/// applications don't need to branch on platform or loop implementation.
+/// Pass it to the generic drivers (`wakeup_burst`, `run_benchmark`, ...)
+/// like the waker it wraps.
+#[pyclass(frozen)]
+struct Waker {
+    /// The underlying waker, e.g. an EventFdWaker
+    #[pyo3(get)]
+    waker: PyObject,
+    /// Its mechanism name, as listed by `mechanisms()`
+    #[pyo3(get)]
+    mechanism: &'static str,
+    /// The loop implementation it was chosen for: `"selector"`, `"uvloop"`,
+    /// `"proactor"`, `"trio"`, or the loop class's qualified name
+    #[pyo3(get)]
+    implementation: String,
+    // (loop, fd) while a reader is registered for an FD-style waker
+    reader: Mutex<Option<(PyObject, PyObject)>>,
+}
+
+#[pymethods]
+impl Waker {
+    /// Build the lowest-latency waker `event_loop` supports, calling
+    /// `callback()` on the loop for each wake. `event_loop` is an asyncio loop
+    /// or a `trio.lowlevel.TrioToken`; by default the running asyncio loop, or
+    /// the current Trio run. FD-style wakers get a reader that drains them
+    /// before calling `callback`; `close()` removes it.
+    #[staticmethod]
+    #[pyo3(signature = (callback, event_loop=None))]
+    fn best_for(
+        py: Python<'_>,
+        callback: PyObject,
+        event_loop: Option<Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let event_loop = match event_loop {
+            Some(event_loop) => event_loop,
+            None => current_event_loop(py)?,
+        };
+        let module: String = event_loop.get_type().getattr("__module__")?.extract()?;
+        let implementation = if module == "trio" || module.starts_with("trio.") {
+            "trio".to_string()
+        } else {
+            loop_implementation(&event_loop)?
+        };
+        let mechanism = best_mechanism(&implementation);
+        let waker = match mechanism {
+            "trio" => Bound::new(py, TrioWaker::new(callback, event_loop.unbind()))?.into_any(),
+            #[cfg(windows)]
+            "iocp" => {
+                Bound::new(py, IocpWaker::new(py, callback, event_loop.unbind())?)?.into_any()
+            }
+            "callback" => {
+                Bound::new(py, CallbackWaker::new(py, callback, event_loop.unbind())?)?.into_any()
+            }
+            _ => {
+                let waker = match mechanism {
+                    #[cfg(target_os = "linux")]
+                    "eventfd" => Bound::new(py, EventFdWaker::new(false)?)?.into_any(),
+                    #[cfg(target_os = "macos")]
+                    "kqueue" => Bound::new(py, KqueueUserWaker::new()?)?.into_any(),
+                    #[cfg(unix)]
+                    "fd" => Bound::new(py, create_fd_waker()?)?.into_any(),
+                    _ => Bound::new(py, SocketWaker::new("pair")?)?.into_any(),
+                };
+                let fd = waker.call_method0("get_read_fd")?.unbind();
+                let drained = waker.clone().unbind();
+                let on_readable = pyo3::types::PyCFunction::new_closure(
+                    py,
+                    None,
+                    None,
+                    move |args, _kwargs| -> PyResult<PyObject> {
+                        let py = args.py();
+                        drained.call_method0(py, "drain")?;
+                        callback.call0(py)
+                    },
+                )?;
+                event_loop.call_method1("add_reader", (fd.clone_ref(py), on_readable))?;
+                return Ok(Self {
+                    waker: waker.unbind(),
+                    mechanism,
+                    implementation,
+                    reader: Mutex::new(Some((event_loop.unbind(), fd))),
+                });
+            }
+        };
+        Ok(Self {
+            waker: waker.unbind(),
+            mechanism,
+            implementation,
+            reader: Mutex::new(None),
+        })
+    }
+
+    /// The mechanism `best_for()` would pick for `event_loop` (by default the
+    /// running loop), without building anything
+    #[staticmethod]
+    #[pyo3(signature = (event_loop=None))]
+    fn mechanism_for(
+        py: Python<'_>,
+        event_loop: Option<Bound<'_, PyAny>>,
+    ) -> PyResult<&'static str> {
+        let event_loop = match event_loop {
+            Some(event_loop) => event_loop,
+            None => current_event_loop(py)?,
+        };
+        let module: String = event_loop.get_type().getattr("__module__")?.extract()?;
+        if module == "trio" || module.starts_with("trio.") {
+            return Ok(best_mechanism("trio"));
+        }
+        Ok(best_mechanism(&loop_implementation(&event_loop)?))
+    }
+
+    /// Remove the reader (if any) and close the underlying waker if it can be
+    /// closed. Idempotent.
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        if let Some((event_loop, fd)) = self.reader.lock().unwrap().take() {
+            if !event_loop.call_method0(py, "is_closed")?.is_truthy(py)? {
+                event_loop.call_method1(py, "remove_reader", (fd,))?;
+            }
+        }
+        let waker = self.waker.bind(py);
+        if waker.hasattr("close")? {
+            waker.call_method0("close")?;
+        }
+        Ok(())
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.close(py)?;
+        Ok(false)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Waker(mechanism={:?}, implementation={:?})",
+            self.mechanism, self.implementation
+        )
+    }
+}
+
+/// The running asyncio loop, or else the current Trio run's token
+fn current_event_loop(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    let running = py.import("asyncio")?.call_method0("get_running_loop");
+    match running {
+        Ok(event_loop) => Ok(event_loop),
+        Err(err) => py
+            .import("trio")
+            .and_then(|trio| trio.getattr("lowlevel")?.call_method0("current_trio_token"))
+            .map_err(|_| err),
+    }
+}
+
+// =============================================================================
+// Throughput benchmark: measure how many wakeups per second each approach can do
+// =============================================================================
+
+/// How long a burst or sequence runs: a number of wakes, or until a deadline
+#[derive(Clone, Copy)]
+enum RunLength {
+    Count(usize),
+    Duration(Duration),
+}
+
+impl RunLength {
+    /// Exactly one of `count` (named `count_name` in errors) and `duration_secs`
+    fn parse(count: Option<usize>, duration_secs: Option<f64>, count_name: &str) -> PyResult<Self> {
+        match (count, duration_secs) {
+            (Some(count), None) => Ok(Self::Count(count)),
+            (None, Some(secs)) => Duration::try_from_secs_f64(secs)
+                .map(Self::Duration)
+                .map_err(|_| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "duration_secs must be a non-negative number of seconds",
+                    )
+                }),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+                "pass exactly one of {count_name} and duration_secs"
+            ))),
+        }
+    }
+
+    /// Wakes to report as requested: 0 for a duration-bounded run
+    fn requested(self) -> usize {
+        match self {
+            Self::Count(count) => count,
+            Self::Duration(_) => 0,
+        }
+    }
+
+    /// Record the deadline of a duration-bounded run in the result's config
+    fn annotate(self, spec: BenchSpec) -> BenchSpec {
+        match self {
+            Self::Count(_) => spec,
+            Self::Duration(duration) => {
+                spec.config("duration_secs", ConfigValue::Float(duration.as_secs_f64()))
+            }
+        }
+    }
+
+    /// Whether a run that began at `start` and has sent `sent` wakes should
+    /// send another
+    fn more(self, sent: u64, start: Instant) -> bool {
+        match self {
+            Self::Count(count) => sent < count as u64,
+            Self::Duration(duration) => start.elapsed() < duration,
+        }
+    }
+}
+
+/// Spawn a thread that sends wakes via `mechanism` as fast as possible until
+/// `length` is reached
+fn spawn_burst(
+    benchmark: &'static str,
+    mechanism: Mechanism,
+    length: RunLength,
+    thread: impl Into<TaskThread>,
+    start: Option<Arc<StartLine>>,
+) -> BenchTask {
+    let thread = thread.into();
+    let spec = length
+        .annotate(BenchSpec::new(
+            benchmark,
+            length.requested(),
+            &thread.placement(),
+        ))
+        .start_line(start);
+    let spec = mechanism.annotate(spec);
+    let handle = mechanism.handle;
+
+    spawn_bench_task(thread, spec, move |task| {
+        let mut sent = 0;
+        let start = Instant::now();
+        while length.more(sent, start) {
+            if task.is_cancelled() {
+                break;
+            }
+            handle.wake();
+            sent += 1;
+        }
+        handle.finish();
+        sent
+    })
+}
+
+/// Spawn a Rust OS thread that sends one wake via any waker listed by
+/// `mechanisms()` after an optional delay. Whether the producer acquires the
+/// GIL depends on the mechanism.
+#[pyfunction]
+#[pyo3(signature = (waker, delay_micros, pin_cpu=None, rt_priority=None, rt_policy="fifo", timer="sleep"))]
+fn wakeup_from_thread(
+    waker: &Bound<'_, PyAny>,
+    delay_micros: &Bound<'_, PyAny>,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+    timer: &str,
+) -> PyResult<BenchTask> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let timer = TimerBackend::parse(timer)?;
+    let delay = draw_delay(delay_micros)?;
+    let mechanism = resolve_mechanism(waker)?;
+
+    let spec = mechanism.annotate(
+        BenchSpec::new("wakeup_from_thread", 1, &placement)
+            .delay(delay)
+            .config("timer", ConfigValue::Str(timer.name())),
+    );
+    let handle = mechanism.handle;
+
+    Ok(spawn_bench_task(placement, spec, move |task| {
+        task.delay(timer, delay.duration);
+        if task.is_cancelled() {
+            return 0;
+        }
+        handle.wake();
+        handle.finish();
+        1
+    }))
+}
+
+/// Spawn a Rust thread that sends N wakeups as fast as possible via any
+/// waker listed by `mechanisms()`. Returns a BenchTask immediately. Pass
+/// `duration_secs` instead of `count` to send for that long;
+/// `result().sent` reports how many wakes that achieved. With a `barrier`,
+/// the thread waits at that StartBarrier before starting. With
+/// `events_per_wake`, each wake carries that many events via `wake_many`
+/// (recorded in the result's config).
+#[pyfunction]
+#[pyo3(signature = (waker, count=None, pin_cpu=None, rt_priority=None, rt_policy="fifo", duration_secs=None, barrier=None, events_per_wake=1))]
+#[allow(clippy::too_many_arguments)]
+fn wakeup_burst(
+    waker: &Bound<'_, PyAny>,
+    count: Option<usize>,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+    duration_secs: Option<f64>,
+    barrier: Option<&StartBarrier>,
+    events_per_wake: u64,
+) -> PyResult<BenchTask> {
+    if events_per_wake == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "events_per_wake must be at least 1",
+        ));
+    }
+    let length = RunLength::parse(count, duration_secs, "count")?;
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    Ok(spawn_burst(
+        "wakeup_burst",
+        resolve_mechanism(waker)?.with_events_per_wake(events_per_wake),
+        length,
+        placement,
+        barrier.map(|barrier| barrier.line.clone()),
+    ))
+}
+
+/// Spawn a Rust thread that sends N wakeups via FD in batches of `batch_size`
+/// tokens per syscall: one write() of `batch_size` bytes, or with
+/// `vectored=True` one writev() of `batch_size` one-byte iovecs. Comparing
+/// against `fd_wakeup_burst` shows how much of its cost is per-syscall.
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (waker, count, batch_size=64, vectored=false, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn fd_wakeup_burst_batched(
+    waker: &FdWaker,
+    count: usize,
+    batch_size: usize,
+    vectored: bool,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<BenchTask> {
+    // writev() rejects more than IOV_MAX (1024 on Linux and macOS) entries
+    let max_batch = if vectored { 1024 } else { usize::MAX };
+    if batch_size == 0 || batch_size > max_batch {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "batch_size must be between 1 and {max_batch}, got {batch_size}"
+        )));
+    }
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let handle = waker.handle()?;
+
+    let spec = waker.annotate(
+        BenchSpec::new("fd_wakeup_burst_batched", count, &placement)
+            .config("batch_size", ConfigValue::Int(batch_size as i64))
+            .config(
+                "write_mode",
+                ConfigValue::Str(if vectored { "writev" } else { "write" }),
+            ),
+    );
+
+    Ok(spawn_bench_task(placement, spec, move |task| {
+        let tokens = vec![1u8; batch_size];
+        let iov: Vec<libc::iovec> = if vectored {
+            (0..batch_size)
+                .map(|i| libc::iovec {
+                    iov_base: tokens[i..].as_ptr() as *mut libc::c_void,
+                    iov_len: 1,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let mut sent = 0;
+        while sent < count {
+            if task.is_cancelled() {
+                break;
+            }
+            let batch = (count - sent).min(batch_size);
+            if vectored {
+                handle.wake_vectored(&iov[..batch]);
+            } else {
+                handle.wake_with_payload(&tokens[..batch], batch as u64);
+            }
+            sent += batch;
+        }
+        sent as u64
+    }))
+}
+
+// =============================================================================
+// Payload delivery: data + wake
+// =============================================================================
+
+/// A small payload delivered through an MpscChannel
+#[cfg(unix)]
+enum Payload {
+    Int(i64),
+    Bytes(Vec<u8>),
+}
+
+#[cfg(unix)]
+impl Payload {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        match self {
+            Payload::Int(value) => value.into_pyobject(py).unwrap().into_any().unbind(),
+            Payload::Bytes(data) => pyo3::types::PyBytes::new(py, &data).into_any().unbind(),
+        }
+    }
+}
+
+/// A multi-producer queue paired with a pipe waker: Rust threads push payloads
+/// and wake the loop, and Python collects everything queued with `drain()` in
+/// its add_reader callback. Benchmarks the realistic "data + wake" pattern.
+#[cfg(unix)]
+#[pyclass]
+struct MpscChannel {
+    waker: FdWaker,
+    sender: std::sync::mpsc::Sender<Payload>,
+    receiver: Mutex<std::sync::mpsc::Receiver<Payload>>,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl MpscChannel {
+    #[new]
+    fn new() -> PyResult<Self> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Ok(Self {
+            waker: create_fd_waker()?,
+            sender,
+            receiver: Mutex::new(receiver),
+        })
+    }
+
+    /// Get the read file descriptor for registering with the event loop
+    fn get_read_fd(&self) -> PyResult<RawFd> {
+        self.waker.get_read_fd()
+    }
+
+    /// Drain the wake pipe and return every queued payload (ints or bytes)
+    fn drain(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.waker.drain()?;
+        let receiver = self.receiver.lock().unwrap();
+        Ok(receiver.try_iter().map(|p| p.into_py(py)).collect())
+    }
+}
+
+#[cfg(unix)]
+impl MpscChannel {
+    fn handle(&self) -> PyResult<MpscChannelHandle> {
+        Ok(MpscChannelHandle {
+            waker: self.waker.handle()?,
+            sender: self.sender.clone(),
+            next: AtomicU64::new(0),
+        })
+    }
+}
+
+/// Producer side of an MpscChannel that can be sent across threads
+#[cfg(unix)]
+struct MpscChannelHandle {
+    waker: FdWakerHandle,
+    sender: std::sync::mpsc::Sender<Payload>,
+    // Payload of this producer's next generic-driver wake, counting from 0
+    next: AtomicU64,
+}
+
+#[cfg(unix)]
+impl MpscChannelHandle {
+    fn send(&self, payload: Payload) {
+        if self.sender.send(payload).is_ok() {
+            self.waker.wake();
+        }
+    }
+}
+
+/// Spawn a Rust thread that pushes `count` payloads as fast as possible, waking
+/// Python after each. Payloads are sequence numbers, or `payload_bytes`-byte
+/// buffers when `payload_bytes > 0`.
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (channel, count, payload_bytes=0, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn channel_send_burst(
+    channel: &MpscChannel,
+    count: usize,
+    payload_bytes: usize,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<()> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let handle = channel.handle()?;
+
+    spawn_producer(placement, "channel_burst", move |task| {
+        for seq in 0..count {
+            if task.is_cancelled() {
+                break;
+            }
+            let payload = if payload_bytes > 0 {
+                Payload::Bytes(vec![seq as u8; payload_bytes])
+            } else {
+                Payload::Int(seq as i64)
+            };
+            handle.send(payload);
+        }
+    })?;
+    Ok(())
+}
+
+// =============================================================================
+// Lock-free SPSC ring buffer data plane
+// =============================================================================
+
+/// Fixed-capacity single-producer/single-consumer ring of u64 values. The Rust
+/// producer pushes without allocating; when the ring is full the value is
+/// dropped and counted rather than blocking the producer.
+#[pyclass(frozen)]
+struct SpscRing {
+    slots: Box<[AtomicU64]>,
+    // Monotonic positions; slot index is position % capacity
+    head: AtomicU64,
+    tail: AtomicU64,
+    dropped: AtomicU64,
+    producer_active: AtomicBool,
+}
+
+#[pymethods]
+impl SpscRing {
+    #[new]
+    fn new(capacity: usize) -> PyResult<Self> {
+        if capacity == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "capacity must be positive",
+            ));
+        }
+        Ok(Self {
+            slots: (0..capacity).map(|_| AtomicU64::new(0)).collect(),
+            head: AtomicU64::new(0),
+            tail: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            producer_active: AtomicBool::new(false),
+        })
+    }
+
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn __len__(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        (tail - head) as usize
+    }
+
+    /// Pop every queued value and append it to `target`. Returns the number appended.
+    fn drain_into_list(&self, target: &Bound<'_, pyo3::types::PyList>) -> PyResult<usize> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        for pos in head..tail {
+            let value =
+                self.slots[(pos % self.slots.len() as u64) as usize].load(Ordering::Relaxed);
+            target.append(value)?;
+        }
+        self.head.store(tail, Ordering::Release);
+        Ok((tail - head) as usize)
+    }
+
+    /// Counters: capacity, pushed (accepted), popped, and dropped (overflows)
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        stats.set_item("capacity", self.slots.len())?;
+        stats.set_item("pushed", self.tail.load(Ordering::Acquire))?;
+        stats.set_item("popped", self.head.load(Ordering::Acquire))?;
+        stats.set_item("dropped", self.dropped.load(Ordering::Acquire))?;
+        Ok(stats)
+    }
+}
+
+impl SpscRing {
+    /// Push a value; returns false (and counts a drop) if the ring is full.
+    /// Must only be called from the single active producer.
+    fn push(&self, value: u64) -> bool {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if tail - head >= self.slots.len() as u64 {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        self.slots[(tail % self.slots.len() as u64) as usize].store(value, Ordering::Relaxed);
+        self.tail.store(tail + 1, Ordering::Release);
+        true
+    }
+}
+
+/// Claim the ring's single producer slot for a new thread
+fn claim_ring_producer(ring: &Py<SpscRing>, py: Python<'_>) -> PyResult<Py<SpscRing>> {
+    if ring.get().producer_active.swap(true, Ordering::AcqRel) {
+        return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+            "ring already has an active producer",
+        ));
+    }
+    Ok(ring.clone_ref(py))
+}
+
+/// Spawn a Rust thread that pushes `count` sequence numbers into the ring,
+/// waking Python via the FD after each push (including dropped ones).
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (waker, ring, count, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn fd_ring_burst(
+    py: Python<'_>,
+    waker: &FdWaker,
+    ring: Py<SpscRing>,
+    count: usize,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<()> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let handle = waker.handle()?;
+    let ring = claim_ring_producer(&ring, py)?;
+
+    spawn_producer(placement, "fd_ring_burst", move |task| {
+        let ring = ring.get();
+        for seq in 0..count {
+            if task.is_cancelled() {
+                break;
+            }
+            ring.push(seq as u64);
+            handle.wake();
+        }
+        ring.producer_active.store(false, Ordering::Release);
+    })?;
+    Ok(())
+}
+
+/// Spawn a Rust thread that pushes `count` sequence numbers into the ring,
+/// waking Python via call_soon_threadsafe after each push (including dropped ones).
+#[pyfunction]
+#[pyo3(signature = (waker, ring, count, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn callback_ring_burst(
+    py: Python<'_>,
+    waker: &CallbackWaker,
+    ring: Py<SpscRing>,
+    count: usize,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<()> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let ring = claim_ring_producer(&ring, py)?;
+    let handle = waker.handle(py)?;
+
+    spawn_producer(placement, "callback_ring_burst", move |task| {
+        let ring = ring.get();
+        for seq in 0..count {
+            if task.is_cancelled() {
+                break;
+            }
+            ring.push(seq as u64);
+            handle.wake();
+        }
+        ring.producer_active.store(false, Ordering::Release);
+    })?;
+    Ok(())
+}
+
+// =============================================================================
+// Multi-producer benchmark: N Rust threads sharing one waker
+// =============================================================================
+
+/// Per-thread completion counters for a multi-producer run
+#[pyclass]
+struct ProducerProgress {
+    counts: Arc<[AtomicU64]>,
+    finished: Arc<AtomicU64>,
+}
+
+#[pymethods]
+impl ProducerProgress {
+    /// Wakes sent so far by each producer thread
+    fn counts(&self) -> Vec<u64> {
+        self.counts
+            .iter()
+            .map(|c| c.load(Ordering::SeqCst))
+            .collect()
+    }
+
+    /// Wakes sent so far across all producer threads
+    fn total(&self) -> u64 {
+        self.counts.iter().map(|c| c.load(Ordering::SeqCst)).sum()
+    }
+
+    /// Number of producer threads that have sent all their wakes
+    fn finished_threads(&self) -> u64 {
+        self.finished.load(Ordering::SeqCst)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished_threads() as usize == self.counts.len()
+    }
+}
+
+/// Spawn `threads` producer threads sharing `mechanism`, each sending
+/// `count_per_thread` wakes as fast as possible. With a `recorder`, each
+/// wake's cost is recorded tagged with its thread's index. If a thread can't
+/// be spawned, those already started are cancelled.
+fn spawn_multi_producer(
+    placement: ThreadPlacement,
+    mechanism: Mechanism,
+    threads: usize,
+    count_per_thread: usize,
+    recorder: Option<&LatencyRecorder>,
+) -> std::io::Result<ProducerProgress> {
+    let counts: Arc<[AtomicU64]> = (0..threads).map(|_| AtomicU64::new(0)).collect();
+    let finished = Arc::new(AtomicU64::new(0));
+    let recorder = recorder.map(|r| (r.recording.clone(), r.clock));
+    let mut started: Vec<Arc<TaskState>> = Vec::with_capacity(threads);
+
+    for index in 0..threads {
+        let handle = mechanism.handle.clone();
+        let counts = counts.clone();
+        let finished = finished.clone();
+        let recorder = recorder.clone();
+        let guard = track_thread();
+        let state = guard.0.clone();
+        let spawned = placement.thread("multi", Some(index)).spawn(move || {
+            placement.apply();
+            run_as_task(&guard.0, || {
+                for _ in 0..count_per_thread {
+                    if guard.0.is_cancelled() {
+                        break;
+                    }
+                    timed_thread_wake(&recorder, index, || handle.wake());
+                    counts[index].fetch_add(1, Ordering::SeqCst);
+                }
+                handle.finish();
+            });
+            finished.fetch_add(1, Ordering::SeqCst);
+        });
+        if let Err(error) = spawned {
+            for state in &started {
+                state.cancel();
+            }
+            return Err(error);
+        }
+        started.push(state);
+    }
+
+    Ok(ProducerProgress { counts, finished })
+}
+
+/// Spawn `threads` Rust threads that each send `count_per_thread` wakeups via
+/// the same waker (any listed by `mechanisms()`) as fast as possible. Returns
+/// per-thread progress counters. A `recorder` receives each wake's cost
+/// tagged with its producer thread; see `LatencyRecorder.thread_summaries()`.
+#[pyfunction]
+#[pyo3(signature = (waker, threads, count_per_thread, pin_cpu=None, rt_priority=None, rt_policy="fifo", recorder=None))]
+#[allow(clippy::too_many_arguments)]
+fn wakeup_multi(
+    waker: &Bound<'_, PyAny>,
+    threads: usize,
+    count_per_thread: usize,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+    recorder: Option<&LatencyRecorder>,
+) -> PyResult<ProducerProgress> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    Ok(spawn_multi_producer(
+        placement,
+        resolve_mechanism(waker)?,
+        threads,
+        count_per_thread,
+        recorder,
+    )?)
+}
+
+// =============================================================================
+// Fan-out: one producer waking several event loops
+// =============================================================================
+
+/// How a WakerGroup's `wake()` picks members
+#[derive(Clone, Copy, PartialEq)]
+enum FanOut {
+    /// Every member, in order
+    All,
+    /// One member per wake, cycling through them
+    RoundRobin,
+}
+
+impl FanOut {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "all" => Ok(Self::All),
+            "round_robin" => Ok(Self::RoundRobin),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "fan_out must be 'all' or 'round_robin', got {name:?}"
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::RoundRobin => "round_robin",
+        }
+    }
+}
+
+/// One waker in a group, with the send time of its oldest undelivered wake
+struct GroupMember {
+    mechanism: Mechanism,
+    /// `monotonic_ns()` of the first wake since the last delivery, 0 if none
+    pending_ns: AtomicU64,
+    delivery: SharedRecording,
+}
+
+/// State shared between a WakerGroup and its producer handles
+struct GroupState {
+    members: Vec<GroupMember>,
+    fan_out: FanOut,
+    next: std::sync::atomic::AtomicUsize,
+}
+
+impl GroupState {
+    fn wake_member(&self, index: usize) {
+        let member = &self.members[index];
+        // Coalesced wakes are timed from the oldest one still undelivered
+        let _ = member.pending_ns.compare_exchange(
+            0,
+            monotonic_ns(),
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        );
+        member.mechanism.handle.wake();
+    }
+
+    fn wake_all(&self) {
+        for index in 0..self.members.len() {
+            self.wake_member(index);
+        }
+    }
+
+    /// Wake the next member in round-robin order; returns its index
+    fn wake_next(&self) -> usize {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.members.len();
+        self.wake_member(index);
+        index
+    }
+
+    fn member(&self, index: usize) -> PyResult<&GroupMember> {
+        self.members.get(index).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyIndexError, _>(format!(
+                "member {index} out of range for a group of {}",
+                self.members.len()
+            ))
+        })
+    }
+}
+
+/// Several wakers, typically each bound to an event loop in its own thread,
+/// woken together (`wake_all()`) or in turn (`wake_one()`). A group is
+/// itself a waker: the generic drivers fan each wake out according to
+/// `fan_out`, "all" or "round_robin".
+///
+/// Each loop calls `delivered(index)` when its wake arrives, which records
+/// that member's delivery latency: from the oldest undelivered wake sent to
+/// it, to the call.
+#[pyclass]
+struct WakerGroup {
+    wakers: Vec<PyObject>,
+    state: Arc<GroupState>,
+}
+
+impl WakerGroup {
+    fn handle(&self) -> WakerGroupHandle {
+        WakerGroupHandle {
+            state: self.state.clone(),
+        }
+    }
+}
+
+#[pymethods]
+impl WakerGroup {
+    #[new]
+    #[pyo3(signature = (wakers, fan_out="all"))]
+    fn new(wakers: Vec<Bound<'_, PyAny>>, fan_out: &str) -> PyResult<Self> {
+        let fan_out = FanOut::parse(fan_out)?;
+        if wakers.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "a WakerGroup needs at least one waker",
+            ));
+        }
+        let members = wakers
+            .iter()
+            .map(|waker| {
+                if waker.is_instance_of::<WakerGroup>() {
+                    return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                        "WakerGroups can't be nested",
+                    ));
+                }
+                Ok(GroupMember {
+                    mechanism: resolve_mechanism(waker)?,
+                    pending_ns: AtomicU64::new(0),
+                    delivery: Recording::shared(),
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Self {
+            wakers: wakers.into_iter().map(Bound::unbind).collect(),
+            state: Arc::new(GroupState {
+                members,
+                fan_out,
+                next: std::sync::atomic::AtomicUsize::new(0),
+            }),
+        })
+    }
+
+    /// The member wakers, in index order
+    #[getter]
+    fn wakers(&self, py: Python<'_>) -> Vec<PyObject> {
+        self.wakers.iter().map(|w| w.clone_ref(py)).collect()
+    }
+
+    /// Mechanism name of each member, in index order
+    #[getter]
+    fn mechanisms(&self) -> Vec<&'static str> {
+        self.state
+            .members
+            .iter()
+            .map(|m| m.mechanism.name)
+            .collect()
+    }
+
+    #[getter]
+    fn fan_out(&self) -> &'static str {
+        self.state.fan_out.name()
+    }
+
+    fn __len__(&self) -> usize {
+        self.state.members.len()
+    }
+
+    /// Wake every member, in index order
+    fn wake_all(&self, py: Python<'_>) {
+        let state = self.state.clone();
+        py.allow_threads(|| state.wake_all());
+    }
+
+    /// Wake member `index`, or the next one in round-robin order when
+    /// `index` is None. Returns the index woken.
+    #[pyo3(signature = (index=None))]
+    fn wake_one(&self, py: Python<'_>, index: Option<usize>) -> PyResult<usize> {
+        let state = self.state.clone();
+        match index {
+            Some(index) => {
+                state.member(index)?;
+                py.allow_threads(|| state.wake_member(index));
+                Ok(index)
+            }
+            None => Ok(py.allow_threads(|| state.wake_next())),
+        }
+    }
+
+    /// Record that member `index`'s loop received its wake. Returns the
+    /// delivery latency in ns, or None if no wake was outstanding (already
+    /// counted by an earlier call).
+    fn delivered(&self, index: usize) -> PyResult<Option<u64>> {
+        let member = self.state.member(index)?;
+        let sent = member.pending_ns.swap(0, Ordering::SeqCst);
+        if sent == 0 {
+            return Ok(None);
+        }
+        let latency = monotonic_ns().saturating_sub(sent);
+        let _ = member.delivery.lock().unwrap().record(latency);
+        Ok(Some(latency))
+    }
+
+    /// Delivery latency summary of member `index`
+    fn delivery_latency(&self, index: usize) -> PyResult<LatencySummary> {
+        let member = self.state.member(index)?;
+        Ok(LatencySummary::from_histogram(
+            &member.delivery.lock().unwrap().hist,
+        ))
+    }
+
+    /// Delivery latency summary of every member, in index order
+    fn delivery_summaries(&self) -> Vec<LatencySummary> {
+        self.state
+            .members
+            .iter()
+            .map(|m| LatencySummary::from_histogram(&m.delivery.lock().unwrap().hist))
+            .collect()
+    }
+
+    /// Clear recorded delivery latencies and outstanding wakes
+    fn reset_delivery(&self) {
+        for member in &self.state.members {
+            member.pending_ns.store(0, Ordering::SeqCst);
+            member.delivery.lock().unwrap().reset();
+        }
+    }
+}
+
+/// Producer handle for a WakerGroup
+#[derive(Clone)]
+struct WakerGroupHandle {
+    state: Arc<GroupState>,
+}
+
+impl WakeMechanism for WakerGroupHandle {
+    fn wake(&self) {
+        match self.state.fan_out {
+            FanOut::All => self.state.wake_all(),
+            FanOut::RoundRobin => {
+                self.state.wake_next();
+            }
+        }
+    }
+
+    /// Sums of the members' counters, where any member counts them
+    fn stats(&self) -> WakeStats {
+        fn sum(values: impl Iterator<Item = Option<u64>>) -> Option<u64> {
+            values.fold(None, |total, value| match (total, value) {
+                (None, None) => None,
+                (total, value) => Some(total.unwrap_or(0) + value.unwrap_or(0)),
+            })
+        }
+        let stats: Vec<WakeStats> = self
+            .state
+            .members
+            .iter()
+            .map(|m| m.mechanism.handle.stats())
+            .collect();
+        WakeStats {
+            dropped: sum(stats.iter().map(|s| s.dropped)),
+            errors: sum(stats.iter().map(|s| s.errors)),
+        }
+    }
+
+    fn finish(&self) {
+        for member in &self.state.members {
+            member.mechanism.handle.finish();
+        }
+    }
+}
+
+// =============================================================================
+// Sequence-numbered wakes: prove every wake was delivered
+// =============================================================================
+
+/// Largest sequence number a SequenceValidator will track (512 MiB of bitmap)
+const MAX_TRACKED_SEQUENCE: u64 = 1 << 32;
+
+/// A pipe waker whose wakes each write an 8-byte little-endian sequence number.
+/// Writes of up to PIPE_BUF bytes are atomic, so numbers from concurrent
+/// producers never interleave; `drain()` returns them in arrival order.
+#[cfg(unix)]
+#[pyclass]
+struct SequencedFdWaker {
+    inner: FdWaker,
+    sequence: Arc<AtomicU64>,
+}
+
+#[cfg(unix)]
+#[pymethods]
+impl SequencedFdWaker {
+    #[new]
+    #[pyo3(signature = (block_timeout_ms=None, on_full=None))]
+    fn new(block_timeout_ms: Option<u64>, on_full: Option<&FullPolicy>) -> PyResult<Self> {
+        Ok(Self {
+            inner: FdWaker::new(
+                block_timeout_ms,
+                false,
+                None,
+                DEFAULT_DRAIN_BUFFER_SIZE,
+                on_full,
+            )?,
+            sequence: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Get the read file descriptor for registering with the event loop
+    fn get_read_fd(&self) -> PyResult<RawFd> {
+        self.inner.get_read_fd()
+    }
+
+    /// Read every sequence number currently in the pipe (call this in the callback)
+    fn drain(&self) -> PyResult<Vec<u64>> {
+        let read_fd = self.inner.pipe()?.read.as_raw_fd();
+        let mut seqs = Vec::new();
+        // A multiple of 8 bytes, so reads never split a record
+        let mut buf = [0u8; 512];
+        loop {
+            let result =
+                unsafe { libc::read(read_fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+            if result <= 0 {
+                break;
+            }
+            seqs.extend(
+                buf[..result as usize]
+                    .chunks_exact(8)
+                    .map(|record| u64::from_le_bytes(record.try_into().unwrap())),
+            );
+        }
+        Ok(seqs)
+    }
+
+    /// Sequence number the next wake will carry
+    fn next_sequence(&self) -> u64 {
+        self.sequence.load(Ordering::SeqCst)
+    }
+
+    /// Pipe write outcomes; a `dropped` wake is a sequence number that never arrives
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        self.inner.stats(py)
+    }
+
+    fn close(&self, py: Python<'_>) {
+        self.inner.close(py);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// Monotonic ns at which the most recent wake started, or None
+    fn last_wake_ns(&self) -> Option<u64> {
+        self.inner.stamps.last()
+    }
+
+    /// Start times (ns on the timestamp clock) of the most recent wakes, oldest first
+    fn wake_timestamps(&self) -> Vec<u64> {
+        self.inner.stamps.snapshot()
+    }
+}
+
+#[cfg(unix)]
+impl SequencedFdWaker {
+    fn handle(&self) -> PyResult<SequencedFdWakerHandle> {
+        Ok(SequencedFdWakerHandle {
+            inner: self.inner.handle()?,
+            sequence: self.sequence.clone(),
+        })
+    }
+}
+
+/// Holder for the sequenced waker's write side that can be sent across threads
+#[cfg(unix)]
+struct SequencedFdWakerHandle {
+    inner: FdWakerHandle,
+    sequence: Arc<AtomicU64>,
+}
+
+#[cfg(unix)]
+impl SequencedFdWakerHandle {
+    fn wake(&self) {
+        let seq = self.sequence.fetch_add(1, Ordering::SeqCst);
+        self.inner.wake_with_payload(&seq.to_le_bytes(), 1);
+    }
+}
+
+/// Checks a stream of received sequence numbers for gaps, duplicates, and
+/// reorderings. Feed it from the Python callback with `observe()`.
+#[pyclass]
+#[derive(Default)]
+struct SequenceValidator {
+    /// Bitmap of sequence numbers received at least once
+    seen: Vec<u64>,
+    received: u64,
+    duplicates: u64,
+    reordered: u64,
+    highest: Option<u64>,
+}
+
+#[pymethods]
+impl SequenceValidator {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one received sequence number
+    fn observe(&mut self, seq: u64) -> PyResult<()> {
+        if seq >= MAX_TRACKED_SEQUENCE {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "sequence number {seq} exceeds the tracked maximum {MAX_TRACKED_SEQUENCE}"
+            )));
+        }
+        let (word, bit) = ((seq / 64) as usize, seq % 64);
+        if word >= self.seen.len() {
+            self.seen.resize(word + 1, 0);
+        }
+
+        self.received += 1;
+        if self.seen[word] & (1 << bit) != 0 {
+            self.duplicates += 1;
+            return Ok(());
+        }
+        self.seen[word] |= 1 << bit;
+        match self.highest {
+            Some(highest) if seq < highest => self.reordered += 1,
+            _ => self.highest = Some(seq),
+        }
+        Ok(())
+    }
+
+    /// Record a batch of sequence numbers, e.g. from `SequencedFdWaker.drain()`
+    fn observe_all(&mut self, seqs: Vec<u64>) -> PyResult<()> {
+        seqs.into_iter().try_for_each(|seq| self.observe(seq))
+    }
+
+    /// Sequence numbers never received, below `expected` (default: one past the
+    /// highest received)
+    #[pyo3(signature = (expected=None))]
+    fn missing(&self, expected: Option<u64>) -> Vec<u64> {
+        let end = expected
+            .unwrap_or(self.highest.map_or(0, |h| h + 1))
+            .min(MAX_TRACKED_SEQUENCE);
+        (0..end)
+            .filter(|&seq| {
+                let word = (seq / 64) as usize;
+                word >= self.seen.len() || self.seen[word] & (1 << (seq % 64)) == 0
+            })
+            .collect()
+    }
+
+    /// Counts of `received`, `unique`, `duplicates`, `reordered` (first
+    /// arrivals after a higher number), and `missing` (below `expected`), plus
+    /// `highest` and whether every wake arrived exactly once (`complete`)
+    #[pyo3(signature = (expected=None))]
+    fn report<'py>(
+        &self,
+        py: Python<'py>,
+        expected: Option<u64>,
+    ) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let missing = self.missing(expected).len();
+        let report = pyo3::types::PyDict::new(py);
+        report.set_item("received", self.received)?;
+        report.set_item("unique", self.received - self.duplicates)?;
+        report.set_item("duplicates", self.duplicates)?;
+        report.set_item("reordered", self.reordered)?;
+        report.set_item("missing", missing)?;
+        report.set_item("highest", self.highest)?;
+        report.set_item("complete", missing == 0 && self.duplicates == 0)?;
+        Ok(report)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Spawn a Rust thread that sends `count` sequence-numbered wakes via the pipe
+/// as fast as possible. Returns a BenchTask immediately.
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (waker, count, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn sequenced_fd_wakeup_burst(
+    waker: &SequencedFdWaker,
+    count: usize,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<BenchTask> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    Ok(spawn_burst(
+        "sequenced_fd_wakeup_burst",
+        Mechanism::new("sequenced_fd", waker.handle()?),
+        RunLength::Count(count),
+        placement,
+        None,
+    ))
+}
+
+/// Spawn `threads` Rust threads that each send `count_per_thread`
+/// sequence-numbered wakes via the same pipe. Numbers are drawn from one
+/// shared counter, so the merged stream should contain each exactly once.
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (waker, threads, count_per_thread, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn sequenced_fd_wakeup_multi(
+    waker: &SequencedFdWaker,
+    threads: usize,
+    count_per_thread: usize,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<ProducerProgress> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    Ok(spawn_multi_producer(
+        placement,
+        Mechanism::new("sequenced_fd", waker.handle()?),
+        threads,
+        count_per_thread,
+        None,
+    )?)
+}
+
+/// Spawn a Rust thread that sends `count` wakes via call_soon_threadsafe, each
+/// passing the next sequence number to the callback. Returns a BenchTask immediately.
+#[pyfunction]
+#[pyo3(signature = (waker, count, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn sequenced_callback_wakeup_burst(
+    py: Python<'_>,
+    waker: &CallbackWaker,
+    count: usize,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<BenchTask> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    Ok(spawn_burst(
+        "sequenced_callback_wakeup_burst",
+        Mechanism::new("sequenced_callback", SequencedCallback(waker.handle(py)?)),
+        RunLength::Count(count),
+        placement,
+        None,
+    ))
+}
+
+/// Spawn `threads` Rust threads that each send `count_per_thread` wakes via
+/// call_soon_threadsafe, passing sequence numbers from the waker's shared counter.
+#[pyfunction]
+#[pyo3(signature = (waker, threads, count_per_thread, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn sequenced_callback_wakeup_multi(
+    py: Python<'_>,
+    waker: &CallbackWaker,
+    threads: usize,
+    count_per_thread: usize,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<ProducerProgress> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    Ok(spawn_multi_producer(
+        placement,
+        Mechanism::new("sequenced_callback", SequencedCallback(waker.handle(py)?)),
+        threads,
+        count_per_thread,
+        None,
+    )?)
+}
+
+// =============================================================================
+// Rate-limited load generation
+// =============================================================================
+
+/// Validate and convert a wake rate into the interval between wakes
+fn rate_interval(rate_per_sec: f64) -> PyResult<Duration> {
+    if !(rate_per_sec > 0.0 && rate_per_sec.is_finite()) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "rate_per_sec must be a positive number",
+        ));
+    }
+    Ok(Duration::from_secs_f64(1.0 / rate_per_sec))
+}
+
+/// Validate and convert how long a paced run lasts
+fn run_duration(duration_secs: f64) -> PyResult<Duration> {
+    Duration::try_from_secs_f64(duration_secs).map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "duration_secs must be a non-negative number of seconds",
+        )
+    })
+}
+
+/// Small, seedable PRNG (SplitMix64) so load patterns are reproducible
+/// across runs and platforms.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform sample in (0, 1]
+    fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Exponentially distributed sample with the given mean
+    fn next_exponential(&mut self, mean: f64) -> f64 {
+        -self.next_f64().ln() * mean
+    }
+
+    /// Standard normal sample (Box-Muller)
+    fn next_normal(&mut self) -> f64 {
+        let radius = (-2.0 * self.next_f64().ln()).sqrt();
+        radius * (2.0 * std::f64::consts::PI * self.next_f64()).cos()
+    }
+
+    /// Gamma(shape, 1) sample for shape >= 1 (Marsaglia-Tsang)
+    fn next_gamma(&mut self, shape: f64) -> f64 {
+        let d = shape - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+        loop {
+            let x = self.next_normal();
+            let v = (1.0 + c * x).powi(3);
+            if v <= 0.0 {
+                continue;
+            }
+            if self.next_f64().ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+                return d * v;
+            }
+        }
+    }
+
+    /// Beta(a, b) sample for a, b >= 1
+    fn next_beta(&mut self, a: f64, b: f64) -> f64 {
+        let x = self.next_gamma(a);
+        x / (x + self.next_gamma(b))
+    }
+}
+
+/// Spawn a thread that wakes for `duration`, with the gap before each wake
+/// drawn from `next_interval`. Wakes are scheduled at absolute offsets from the
+/// start, so sleep overshoot on one iteration doesn't accumulate; a late wake
+/// is sent immediately.
+fn spawn_scheduled<I>(
+    placement: ThreadPlacement,
+    spin_threshold: Duration,
+    mechanism: Mechanism,
+    mut next_interval: I,
+    duration: Duration,
+    recorder: Option<&LatencyRecorder>,
+) -> std::io::Result<()>
+where
+    I: FnMut() -> Duration + Send + 'static,
+{
+    let handle = mechanism.handle;
+    let recorder = recorder.map(|r| (r.recording.clone(), r.clock));
+    spawn_producer(placement, "rate", move |task| {
+        run_scheduled(
+            &*handle,
+            spin_threshold,
+            &mut next_interval,
+            duration,
+            &recorder,
+            || task.is_cancelled(),
+        );
+    })
+}
+
+/// Wake on the schedule drawn from `next_interval` for `duration`, or until
+/// `cancelled` returns true, recording each wake as for `scheduled_wake`.
+/// Returns the number of wakes sent.
+fn run_scheduled(
+    handle: &dyn WakeMechanism,
+    spin_threshold: Duration,
+    next_interval: &mut dyn FnMut() -> Duration,
+    duration: Duration,
+    recorder: &Option<(SharedRecording, ClockSource)>,
+    cancelled: impl Fn() -> bool,
+) -> u64 {
+    let timer = Timer::new(TimerBackend::Sleep, spin_threshold);
+    let duration = duration.as_nanos() as u64;
+    let start = monotonic_ns();
+    let mut next = start + next_interval().as_nanos() as u64;
+    let mut sent = 0;
+    while next - start < duration && !cancelled() {
+        let behind = monotonic_ns() >= next;
+        timer.sleep_until(next);
+        scheduled_wake(recorder, next, behind, || handle.wake());
+        sent += 1;
+        next += next_interval().as_nanos() as u64;
+    }
+    handle.finish();
+    sent
+}
+
+/// Exponentially distributed gaps (a Poisson arrival process) averaging
+/// `mean_interval`, reproducible from `seed`
+fn poisson_intervals(mean_interval: Duration, seed: u64) -> impl FnMut() -> Duration + Send {
+    let mut rng = SplitMix64::new(seed);
+    let mean = mean_interval.as_secs_f64();
+    move || Duration::from_secs_f64(rng.next_exponential(mean))
+}
+
+/// Spawn a thread that wakes at a fixed rate for `duration`
+fn spawn_paced(
+    placement: ThreadPlacement,
+    spin_threshold: Duration,
+    mechanism: Mechanism,
+    interval: Duration,
+    duration: Duration,
+    recorder: Option<&LatencyRecorder>,
+) -> std::io::Result<()> {
+    spawn_scheduled(
+        placement,
+        spin_threshold,
+        mechanism,
+        move || interval,
+        duration,
+        recorder,
+    )
+}
+
+/// Spawn a thread that wakes with exponentially distributed gaps (a Poisson
+/// arrival process) averaging `mean_interval`, for `duration`
+fn spawn_poisson(
+    placement: ThreadPlacement,
+    spin_threshold: Duration,
+    mechanism: Mechanism,
+    mean_interval: Duration,
+    seed: u64,
+    duration: Duration,
+    recorder: Option<&LatencyRecorder>,
+) -> std::io::Result<()> {
+    spawn_scheduled(
+        placement,
+        spin_threshold,
+        mechanism,
+        poisson_intervals(mean_interval, seed),
+        duration,
+        recorder,
+    )
+}
+
+/// Spawn a Rust thread that wakes Python via any waker listed by `mechanisms()`
+/// `rate_per_sec` times per second for `duration_secs`. Returns immediately.
+#[pyfunction]
+#[pyo3(signature = (waker, rate_per_sec, duration_secs, pin_cpu=None, rt_priority=None, rt_policy="fifo", spin_threshold_micros=0, recorder=None))]
+#[allow(clippy::too_many_arguments)]
+fn wakeup_rate(
+    waker: &Bound<'_, PyAny>,
+    rate_per_sec: f64,
+    duration_secs: f64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+    spin_threshold_micros: u64,
+    recorder: Option<&LatencyRecorder>,
+) -> PyResult<()> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let spin_threshold = Duration::from_micros(spin_threshold_micros);
+    let interval = rate_interval(rate_per_sec)?;
+    spawn_paced(
+        placement,
+        spin_threshold,
+        resolve_mechanism(waker)?,
+        interval,
+        run_duration(duration_secs)?,
+        recorder,
+    )?;
+    Ok(())
+}
+
+/// Spawn a Rust thread that wakes Python via any waker listed by `mechanisms()`
+/// with Poisson arrivals averaging `rate_per_sec`, for `duration_secs`.
+#[pyfunction]
+#[pyo3(signature = (waker, rate_per_sec, duration_secs, seed=0, pin_cpu=None, rt_priority=None, rt_policy="fifo", spin_threshold_micros=0, recorder=None))]
+#[allow(clippy::too_many_arguments)]
+fn wakeup_poisson(
+    waker: &Bound<'_, PyAny>,
+    rate_per_sec: f64,
+    duration_secs: f64,
+    seed: u64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+    spin_threshold_micros: u64,
+    recorder: Option<&LatencyRecorder>,
+) -> PyResult<()> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let spin_threshold = Duration::from_micros(spin_threshold_micros);
+    let mean_interval = rate_interval(rate_per_sec)?;
+    spawn_poisson(
+        placement,
+        spin_threshold,
+        resolve_mechanism(waker)?,
+        mean_interval,
+        seed,
+        run_duration(duration_secs)?,
+        recorder,
+    )?;
+    Ok(())
+}
+
+/// Spawn a Rust thread that wakes Python via any waker listed by `mechanisms()`
+/// once per `interval_micros`, `ticks` times, and return a BenchTask. Tick k
+/// is due at `start_ns + k * interval` on the monotonic clock (`start_ns`
+/// defaults to one interval from now), so overshoot on one tick never shifts
+/// the next, and a tick whose deadline has passed is sent at once. Each
+/// tick's overshoot, from its deadline to the moment its wake was sent, goes
+/// into `recorder`. `timer` defaults to `"timerfd"` on Linux and `"sleep"`
+/// elsewhere.
+#[pyfunction]
+#[pyo3(signature = (waker, interval_micros, ticks, recorder=None, start_ns=None, timer=None, spin_threshold_micros=0, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+#[allow(clippy::too_many_arguments)]
+fn spawn_periodic(
+    waker: &Bound<'_, PyAny>,
+    interval_micros: u64,
+    ticks: usize,
+    recorder: Option<&LatencyRecorder>,
+    start_ns: Option<u64>,
+    timer: Option<&str>,
+    spin_threshold_micros: u64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<BenchTask> {
+    if interval_micros == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "interval_micros must be at least 1",
+        ));
+    }
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let timer = match timer {
+        Some(name) => TimerBackend::parse(name)?,
+        #[cfg(target_os = "linux")]
+        None => TimerBackend::TimerFd,
+        #[cfg(not(target_os = "linux"))]
+        None => TimerBackend::Sleep,
+    };
+    let interval_ns = interval_micros.saturating_mul(1000);
+    let start_ns = start_ns.unwrap_or_else(|| monotonic_ns() + interval_ns);
+    let mechanism = resolve_mechanism(waker)?;
+    let recording = recorder.map(|r| r.recording.clone());
+
+    let spec = mechanism.annotate(
+        BenchSpec::new("spawn_periodic", ticks, &placement)
+            .config("interval_micros", ConfigValue::Int(interval_micros as i64))
+            .config("start_ns", ConfigValue::Int(start_ns as i64))
+            .config("timer", ConfigValue::Str(timer.name()))
+            .config(
+                "spin_threshold_micros",
+                ConfigValue::Int(spin_threshold_micros as i64),
+            )
+            .recorder(recording.clone()),
+    );
+    let handle = mechanism.handle;
+
+    Ok(spawn_bench_task(placement, spec, move |task| {
+        let timer = Timer::new(timer, Duration::from_micros(spin_threshold_micros));
+        let mut sent = 0;
+        for tick in 0..ticks as u64 {
+            if task.is_cancelled() {
+                break;
+            }
+            let deadline = start_ns + tick * interval_ns;
+            timer.sleep_until(deadline);
+            let overshoot = monotonic_ns().saturating_sub(deadline);
+            handle.wake();
+            if let Some(recording) = &recording {
+                let _ = recording.lock().unwrap().record(overshoot);
+            }
+            sent += 1;
+        }
+        handle.finish();
+        sent
+    }))
+}
+
+// =============================================================================
+// Latency recording
+// =============================================================================
+
+/// A raw sample kept for export; its sequence number is its index
+#[derive(Clone, Copy)]
+struct RawSample {
+    /// Monotonic ns since the recording started (or was last reset)
+    offset_ns: u64,
+    value_ns: u64,
+    /// Measured from the intended rather than the actual send time
+    corrected: bool,
+    /// Index of the producer thread that took the sample, if tagged
+    thread: Option<u32>,
+}
+
+/// Samples behind a LatencyRecorder: always a histogram, plus every raw
+/// sample in arrival order when the recorder was created with `keep_samples`
+#[derive(Clone)]
+struct Recording {
+    hist: Histogram<u64>,
+    samples: Option<Vec<RawSample>>,
+    origin_ns: u64,
+    /// Samples corrected for coordinated omission
+    corrected: u64,
+    /// Samples tagged with a producer thread index, by index
+    threads: std::collections::BTreeMap<usize, Histogram<u64>>,
+}
+
+impl Recording {
+    /// Histogram-only recording with 3 significant figures, for recordings
+    /// kept internally rather than through a LatencyRecorder
+    fn shared() -> SharedRecording {
+        Arc::new(Mutex::new(Self {
+            hist: Histogram::new(3).expect("3 significant figures is valid"),
+            samples: None,
+            origin_ns: monotonic_ns(),
+            corrected: 0,
+            threads: Default::default(),
+        }))
+    }
+
+    fn record(&mut self, value_ns: u64) -> Result<(), hdrhistogram::RecordError> {
+        self.record_sample(value_ns, false)
+    }
+
+    /// Add all of `other`'s samples. Raw samples are appended after this
+    /// recording's, with offsets rebased onto its start.
+    fn absorb(&mut self, other: Recording) -> PyResult<()> {
+        let origin_ns = self.origin_ns;
+        if let Some(samples) = &mut self.samples {
+            let Some(other_samples) = other.samples else {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "cannot merge a recorder without raw samples into one with keep_samples=True",
+                ));
+            };
+            samples.extend(other_samples.into_iter().map(|sample| RawSample {
+                offset_ns: (other.origin_ns + sample.offset_ns).saturating_sub(origin_ns),
+                value_ns: sample.value_ns,
+                corrected: sample.corrected,
+                thread: sample.thread,
+            }));
+        }
+        self.corrected += other.corrected;
+        for (thread, other_hist) in other.threads {
+            self.threads
+                .entry(thread)
+                .or_insert_with(|| Histogram::new_from(&other_hist))
+                .add(&other_hist)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        }
+        self.hist
+            .add(&other.hist)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Record a sample, flagging it if it was corrected for coordinated
+    /// omission (timed from when it should have been sent)
+    fn record_sample(
+        &mut self,
+        value_ns: u64,
+        corrected: bool,
+    ) -> Result<(), hdrhistogram::RecordError> {
+        self.record_tagged(value_ns, corrected, None)
+    }
+
+    /// Record a sample, also into the histogram of producer `thread` if given
+    fn record_tagged(
+        &mut self,
+        value_ns: u64,
+        corrected: bool,
+        thread: Option<usize>,
+    ) -> Result<(), hdrhistogram::RecordError> {
+        self.hist.record(value_ns)?;
+        if let Some(thread) = thread {
+            self.threads
+                .entry(thread)
+                .or_insert_with(|| Histogram::new_from(&self.hist))
+                .record(value_ns)?;
+        }
+        self.corrected += corrected as u64;
+        if let Some(samples) = &mut self.samples {
+            samples.push(RawSample {
+                offset_ns: monotonic_ns().saturating_sub(self.origin_ns),
+                value_ns,
+                corrected,
+                thread: thread.map(|thread| thread as u32),
+            });
+        }
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.hist.reset();
+        self.corrected = 0;
+        self.threads.clear();
+        if let Some(samples) = &mut self.samples {
+            samples.clear();
+        }
+        self.origin_ns = monotonic_ns();
+    }
+
+    /// Stream samples as `sequence,offset_ns,latency_ns,corrected,thread`
+    /// rows; returns the row count
+    fn write_csv(&self, path: &std::path::Path) -> std::io::Result<usize> {
+        let Some(samples) = &self.samples else {
+            return Ok(0);
+        };
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(out, "sequence,offset_ns,latency_ns,corrected,thread")?;
+        for (sequence, sample) in samples.iter().enumerate() {
+            write!(
+                out,
+                "{},{},{},{},",
+                sequence, sample.offset_ns, sample.value_ns, sample.corrected as u8
+            )?;
+            match sample.thread {
+                Some(thread) => writeln!(out, "{thread}")?,
+                None => writeln!(out)?,
+            }
+        }
+        out.flush()?;
+        Ok(samples.len())
+    }
+}
+
+/// Recording shared between a LatencyRecorder and the producer threads feeding it
+type SharedRecording = Arc<Mutex<Recording>>;
+
+/// Record a nanosecond latency sample into an optional shared recording
+fn record_latency(recorder: &Option<SharedRecording>, elapsed_ns: u64) {
+    if let Some(recording) = recorder {
+        let _ = recording.lock().unwrap().record(elapsed_ns);
+    }
+}
+
+/// Wake via `wake`, recording its cost with `clock` tagged as producer `thread`
+fn timed_thread_wake(
+    recorder: &Option<(SharedRecording, ClockSource)>,
+    thread: usize,
+    wake: impl FnOnce(),
+) {
+    let Some((recording, clock)) = recorder else {
+        wake();
+        return;
+    };
+    let start = clock.read_ns();
+    wake();
+    let elapsed_ns = clock.read_ns().saturating_sub(start);
+    let _ = recording
+        .lock()
+        .unwrap()
+        .record_tagged(elapsed_ns, false, Some(thread));
+}
+
+/// Wake via `wake` on behalf of a schedule slot due at `due_ns` (monotonic),
+/// recording the wake's cost with `clock`. If the producer was already
+/// `behind` (the slot passed before it could wait for it), the sample is
+/// timed from the slot instead, so the stall isn't hidden (coordinated
+/// omission), and flagged as corrected.
+fn scheduled_wake(
+    recorder: &Option<(SharedRecording, ClockSource)>,
+    due_ns: u64,
+    behind: bool,
+    wake: impl FnOnce(),
+) {
+    let Some((recording, clock)) = recorder else {
+        wake();
+        return;
+    };
+    let lag_ns = if behind {
+        monotonic_ns().saturating_sub(due_ns)
+    } else {
+        0
+    };
+    let start = clock.read_ns();
+    wake();
+    let elapsed_ns = clock.read_ns().saturating_sub(start);
+    let _ = recording
+        .lock()
+        .unwrap()
+        .record_sample(elapsed_ns + lag_ns, behind);
+}
+
+/// Records nanosecond latencies into an HDR histogram held in Rust, so long
+/// runs never materialize a Python object per sample.
+///
+/// When passed to the `*_wakeup_sequence` functions, the recorder receives the
+/// producer-side cost of each wake (the pipe write, or GIL acquisition plus
+/// call_soon_threadsafe), timed with the recorder's `clock`. Python-side
+/// latencies can be added with `record()`.
+///
+/// With `keep_samples=True` every sample is also kept in arrival order (32
+/// bytes each) so `write_csv()` can export the full distribution.
+///
+/// Samples from the multi-producer drivers are tagged with the index of the
+/// producer thread that took them; `thread_summaries()` breaks them down.
+#[pyclass]
+struct LatencyRecorder {
+    recording: SharedRecording,
+    clock: ClockSource,
+    /// Converts Python receive times onto the Rust clock for `record_delivery()`
+    #[pyo3(get, set)]
+    calibration: Option<ClockCalibration>,
+}
+
+#[pymethods]
+impl LatencyRecorder {
+    #[new]
+    #[pyo3(signature = (significant_figures=3, clock=ClockSource::Monotonic, keep_samples=false, calibration=None))]
+    fn new(
+        significant_figures: u8,
+        clock: ClockSource,
+        keep_samples: bool,
+        calibration: Option<ClockCalibration>,
+    ) -> PyResult<Self> {
+        let hist = Histogram::new(significant_figures)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(Self {
+            recording: Arc::new(Mutex::new(Recording {
+                hist,
+                samples: keep_samples.then(Vec::new),
+                origin_ns: monotonic_ns(),
+                corrected: 0,
+                threads: Default::default(),
+            })),
+            clock: clock.check()?,
+            calibration,
+        })
+    }
+
+    /// Whether raw samples are kept for `write_csv()`
+    #[getter]
+    fn keep_samples(&self) -> bool {
+        self.recording.lock().unwrap().samples.is_some()
+    }
+
+    /// Write every raw sample to `path` as CSV with columns `sequence`
+    /// (arrival order from 0), `offset_ns` (monotonic time since the recorder
+    /// was created or reset), `latency_ns`, `corrected` (1 for samples
+    /// corrected for coordinated omission) and `thread` (the producer thread
+    /// index, empty for untagged samples). Returns the number of rows.
+    /// The file is written without the GIL, but producers recording into this
+    /// recorder wait until it is done.
+    fn write_csv(&self, py: Python<'_>, path: std::path::PathBuf) -> PyResult<usize> {
+        if !self.keep_samples() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "write_csv needs a recorder created with keep_samples=True",
+            ));
+        }
+        let recording = self.recording.clone();
+        py.allow_threads(move || recording.lock().unwrap().write_csv(&path))
+            .map_err(PyErr::from)
+    }
+
+    /// Clock producer threads use to time samples for this recorder
+    #[getter]
+    fn clock(&self) -> ClockSource {
+        self.clock
+    }
+
+    /// Read this recorder's `clock` in nanoseconds, as its producer threads do
+    fn now_ns(&self) -> u64 {
+        self.clock.read_ns()
+    }
+
+    /// Record a single latency sample in nanoseconds. Pass `corrected=True`
+    /// for a sample timed from its intended rather than actual send time, and
+    /// `thread` to tag it with a producer thread index.
+    #[pyo3(signature = (value_ns, corrected=false, thread=None))]
+    fn record(&self, value_ns: u64, corrected: bool, thread: Option<u32>) -> PyResult<()> {
+        self.recording
+            .lock()
+            .unwrap()
+            .record_tagged(value_ns, corrected, thread.map(|thread| thread as usize))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Record the latency from `sent_ns`, stamped in Rust (e.g.
+    /// `waker.last_wake_ns()`), to `received_ns`, stamped in Python (e.g.
+    /// `time.monotonic_ns()`). With a `calibration`, `received_ns` is first
+    /// converted onto the Rust clock. Returns the latency recorded, clamped
+    /// at 0.
+    fn record_delivery(&self, sent_ns: u64, received_ns: i64) -> PyResult<u64> {
+        let received_ns = match &self.calibration {
+            Some(calibration) => calibration.to_rust(received_ns),
+            None => received_ns.max(0) as u64,
+        };
+        let latency = received_ns.saturating_sub(sent_ns);
+        self.recording
+            .lock()
+            .unwrap()
+            .record(latency)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(latency)
+    }
+
+    /// Producer thread indices with tagged samples, in order
+    fn threads(&self) -> Vec<usize> {
+        self.recording
+            .lock()
+            .unwrap()
+            .threads
+            .keys()
+            .copied()
+            .collect()
+    }
+
+    /// Summary of the samples tagged with producer `thread`
+    fn thread_summary(&self, thread: usize) -> PyResult<LatencySummary> {
+        let recording = self.recording.lock().unwrap();
+        let hist = recording.threads.get(&thread).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!(
+                "no samples tagged with thread {thread}"
+            ))
+        })?;
+        Ok(LatencySummary::from_histogram(hist))
+    }
+
+    /// `{thread: LatencySummary}` for every producer thread with tagged
+    /// samples. Aggregate numbers hide a thread that keeps losing a race;
+    /// this shows it.
+    fn thread_summaries(&self) -> std::collections::BTreeMap<usize, LatencySummary> {
+        self.recording
+            .lock()
+            .unwrap()
+            .threads
+            .iter()
+            .map(|(&thread, hist)| (thread, LatencySummary::from_histogram(hist)))
+            .collect()
+    }
+
+    /// Samples corrected for coordinated omission: sent behind schedule, so
+    /// timed from when they should have been sent
+    fn corrected_count(&self) -> u64 {
+        self.recording.lock().unwrap().corrected
+    }
+
+    /// Add all samples from another recorder into this one. Raw samples are
+    /// appended after this recorder's, with offsets rebased onto its start.
+    fn merge(&self, other: &LatencyRecorder) -> PyResult<()> {
+        // Copy first: `other` may be this recorder
+        let other = other.recording.lock().unwrap().clone();
+        self.recording.lock().unwrap().absorb(other)
+    }
+
+    fn reset(&self) {
+        self.recording.lock().unwrap().reset();
+    }
+
+    fn count(&self) -> u64 {
+        self.recording.lock().unwrap().hist.len()
+    }
+
+    fn min(&self) -> u64 {
+        self.recording.lock().unwrap().hist.min()
+    }
+
+    fn max(&self) -> u64 {
+        self.recording.lock().unwrap().hist.max()
+    }
+
+    fn mean(&self) -> f64 {
+        self.recording.lock().unwrap().hist.mean()
+    }
+
+    /// Latency at the given percentile (0-100)
+    fn percentile(&self, percentile: f64) -> u64 {
+        self.recording
+            .lock()
+            .unwrap()
+            .hist
+            .value_at_quantile(percentile / 100.0)
+    }
+
+    fn p50(&self) -> u64 {
+        self.percentile(50.0)
+    }
+
+    fn p90(&self) -> u64 {
+        self.percentile(90.0)
+    }
+
+    fn p99(&self) -> u64 {
+        self.percentile(99.0)
+    }
+
+    fn p999(&self) -> u64 {
+        self.percentile(99.9)
+    }
+
+    /// count, min, max, mean, stddev and p50/p90/p99/p99.9/p99.99 (`p999`,
+    /// `p9999`), computed from the histogram in Rust without exporting samples
+    fn summary(&self) -> LatencySummary {
+        LatencySummary::from_histogram(&self.recording.lock().unwrap().hist)
+    }
+}
+
+// =============================================================================
+// Statistical comparison of two latency recordings
+// =============================================================================
+
+/// Complementary error function (Numerical Recipes' erfcc, |error| < 1.2e-7)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let result = t * poly.exp();
+    if x >= 0.0 {
+        result
+    } else {
+        2.0 - result
+    }
+}
+
+/// Mann-Whitney U for two histograms. Values in the same histogram bucket
+/// are ties. Returns (U for `a`, z with continuity and tie correction).
+fn mann_whitney(a: &Histogram<u64>, b: &Histogram<u64>) -> (f64, f64) {
+    let (n_a, n_b) = (a.len() as f64, b.len() as f64);
+    let mut values: Vec<(u64, u64, u64)> = a
+        .iter_recorded()
+        .map(|v| (v.value_iterated_to(), v.count_at_value(), 0))
+        .chain(
+            b.iter_recorded()
+                .map(|v| (v.value_iterated_to(), 0, v.count_at_value())),
+        )
+        .collect();
+    values.sort_unstable_by_key(|&(value, _, _)| value);
+
+    // Walk distinct values in order, giving each tie group its average rank
+    let (mut below, mut rank_sum_a, mut ties) = (0.0, 0.0, 0.0);
+    let mut i = 0;
+    while i < values.len() {
+        let (mut count_a, mut count_b) = (0u64, 0u64);
+        let value = values[i].0;
+        while i < values.len() && values[i].0 == value {
+            count_a += values[i].1;
+            count_b += values[i].2;
+            i += 1;
+        }
+        let group = (count_a + count_b) as f64;
+        rank_sum_a += count_a as f64 * (below + (group + 1.0) / 2.0);
+        ties += group * group * group - group;
+        below += group;
+    }
+
+    let u_a = rank_sum_a - n_a * (n_a + 1.0) / 2.0;
+    let n = n_a + n_b;
+    let mean = n_a * n_b / 2.0;
+    let variance = n_a * n_b / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)));
+    let z = if variance > 0.0 {
+        let diff = u_a - mean;
+        (diff - 0.5 * diff.signum()) / variance.sqrt()
+    } else {
+        0.0
+    };
+    (u_a, z)
+}
+
+/// Bootstrap `resamples` differences of medians (b - a). The median of a
+/// size-n resample from a histogram is its quantile at the k-th order
+/// statistic of n uniforms, which is Beta(k, n + 1 - k) distributed, so each
+/// resample costs two Beta draws instead of n samples.
+fn bootstrap_median_diffs(
+    a: &Histogram<u64>,
+    b: &Histogram<u64>,
+    resamples: usize,
+    seed: u64,
+) -> Vec<f64> {
+    let mut rng = SplitMix64::new(seed);
+    let mut resampled_median = |hist: &Histogram<u64>| {
+        let n = hist.len() as f64;
+        let k = ((n + 1.0) / 2.0).floor();
+        hist.value_at_quantile(rng.next_beta(k, n + 1.0 - k)) as f64
+    };
+    let mut diffs: Vec<f64> = (0..resamples)
+        .map(|_| {
+            let median_a = resampled_median(a);
+            resampled_median(b) - median_a
+        })
+        .collect();
+    diffs.sort_unstable_by(f64::total_cmp);
+    diffs
+}
+
+/// Outcome of `compare_results(a, b)`: a two-sided Mann-Whitney U test of
+/// whether `b`'s latencies differ from `a`'s, plus a bootstrap confidence
+/// interval for the difference in medians (b - a, ns)
+#[pyclass(frozen)]
+struct SignificanceTest {
+    #[pyo3(get)]
+    n_a: u64,
+    #[pyo3(get)]
+    n_b: u64,
+    /// Mann-Whitney U statistic for `a`
+    #[pyo3(get)]
+    u: f64,
+    #[pyo3(get)]
+    z: f64,
+    #[pyo3(get)]
+    p_value: f64,
+    #[pyo3(get)]
+    alpha: f64,
+    /// P(a sample of b > a sample of a), counting ties as half
+    #[pyo3(get)]
+    probability_b_greater: f64,
+    #[pyo3(get)]
+    median_a: u64,
+    #[pyo3(get)]
+    median_b: u64,
+    #[pyo3(get)]
+    confidence: f64,
+    #[pyo3(get)]
+    ci_low: f64,
+    #[pyo3(get)]
+    ci_high: f64,
+}
+
+#[pymethods]
+impl SignificanceTest {
+    /// True if p_value < alpha
+    #[getter]
+    fn significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+
+    /// median_b - median_a in ns; positive means `b` is slower
+    #[getter]
+    fn median_diff(&self) -> i64 {
+        self.median_b as i64 - self.median_a as i64
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("method", "mann-whitney-u")?;
+        dict.set_item("n_a", self.n_a)?;
+        dict.set_item("n_b", self.n_b)?;
+        dict.set_item("u", self.u)?;
+        dict.set_item("z", self.z)?;
+        dict.set_item("p_value", self.p_value)?;
+        dict.set_item("alpha", self.alpha)?;
+        dict.set_item("significant", self.significant())?;
+        dict.set_item("probability_b_greater", self.probability_b_greater)?;
+        dict.set_item("median_a", self.median_a)?;
+        dict.set_item("median_b", self.median_b)?;
+        dict.set_item("median_diff", self.median_diff())?;
+        dict.set_item("confidence", self.confidence)?;
+        dict.set_item("ci_low", self.ci_low)?;
+        dict.set_item("ci_high", self.ci_high)?;
+        Ok(dict)
+    }
+
+    #[pyo3(signature = (indent=None))]
+    fn to_json(&self, py: Python<'_>, indent: Option<usize>) -> PyResult<String> {
+        dump_json(self.to_dict(py)?.as_any(), indent)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SignificanceTest(significant={}, p_value={:.3e}, median_diff={}, ci=[{:.0}, {:.0}] @ {})",
+            if self.significant() { "True" } else { "False" },
+            self.p_value,
+            self.median_diff(),
+            self.ci_low,
+            self.ci_high,
+            self.confidence
+        )
+    }
+}
+
+/// Test whether the latencies recorded in `b` differ from those in `a`.
+/// Runs a two-sided Mann-Whitney U test over the two histograms (values in
+/// one bucket count as ties) and bootstraps a `confidence` interval for the
+/// difference in medians from `resamples` resamples. `seed` makes the
+/// interval reproducible. Runs without the GIL.
+#[pyfunction]
+#[pyo3(signature = (a, b, alpha=0.05, confidence=0.95, resamples=2000, seed=0))]
+fn compare_results(
+    py: Python<'_>,
+    a: &LatencyRecorder,
+    b: &LatencyRecorder,
+    alpha: f64,
+    confidence: f64,
+    resamples: usize,
+    seed: u64,
+) -> PyResult<SignificanceTest> {
+    let probability = |name: &str, value: f64| {
+        if value > 0.0 && value < 1.0 {
+            Ok(())
+        } else {
+            Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "{name} must be between 0 and 1, got {value}"
+            )))
+        }
+    };
+    probability("alpha", alpha)?;
+    probability("confidence", confidence)?;
+    if resamples == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "resamples must be positive",
+        ));
+    }
+    let hist_a = a.recording.lock().unwrap().hist.clone();
+    let hist_b = b.recording.lock().unwrap().hist.clone();
+    if hist_a.is_empty() || hist_b.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "both recorders need at least one sample",
+        ));
+    }
+
+    Ok(py.allow_threads(move || {
+        let (u, z) = mann_whitney(&hist_a, &hist_b);
+        let (n_a, n_b) = (hist_a.len(), hist_b.len());
+        let diffs = bootstrap_median_diffs(&hist_a, &hist_b, resamples, seed);
+        let tail = (1.0 - confidence) / 2.0;
+        let at = |q: f64| diffs[((q * resamples as f64) as usize).min(resamples - 1)];
+        SignificanceTest {
+            n_a,
+            n_b,
+            u,
+            z,
+            p_value: erfc(z.abs() / std::f64::consts::SQRT_2),
+            alpha,
+            probability_b_greater: 1.0 - u / (n_a as f64 * n_b as f64),
+            median_a: hist_a.value_at_quantile(0.5),
+            median_b: hist_b.value_at_quantile(0.5),
+            confidence,
+            ci_low: at(tail),
+            ci_high: at(1.0 - tail),
+        }
+    }))
+}
+
+// =============================================================================
+// Latency benchmark helpers
+// =============================================================================
+
+/// Phases of a sequence benchmark, as reported by `BenchCoordinator.phase`
+const PHASE_NAMES: [&str; 4] = ["idle", "warmup", "measured", "finished"];
+const PHASE_WARMUP: u8 = 1;
+const PHASE_MEASURED: u8 = 2;
+const PHASE_FINISHED: u8 = 3;
+
+/// Phase boundaries of a sequence benchmark, shared with its producer thread
+#[derive(Default)]
+struct SequencePhases {
+    phase: std::sync::atomic::AtomicU8,
+    warmup_wakes: AtomicU64,
+    // Monotonic ns at which the measured phase began, 0 until then
+    measured_start_ns: AtomicU64,
+}
+
+impl SequencePhases {
+    fn enter(&self, phase: u8) {
+        if phase == PHASE_MEASURED {
+            self.measured_start_ns
+                .store(monotonic_ns(), Ordering::SeqCst);
+        }
+        self.phase.store(phase, Ordering::SeqCst);
+    }
+
+    fn reset(&self) {
+        self.phase.store(0, Ordering::SeqCst);
+        self.warmup_wakes.store(0, Ordering::SeqCst);
+        self.measured_start_ns.store(0, Ordering::SeqCst);
+    }
+}
+
+/// Warmup phase of a sequence benchmark: `iterations` wakes, paced like the
+/// measured ones, that neither the coordinator's counter nor the recorder
+/// sees. Then moves the coordinator to the measured phase and restarts the
+/// task's run timing. Returns false (finishing the sequence) if cancelled.
+fn sequence_warmup(
+    task: &TaskState,
+    phases: &SequencePhases,
+    timer: &Timer,
+    iterations: usize,
+    wake: impl Fn(),
+) -> bool {
+    if iterations > 0 {
+        phases.enter(PHASE_WARMUP);
+    }
+    for _ in 0..iterations {
+        if task.is_cancelled() {
+            phases.enter(PHASE_FINISHED);
+            return false;
+        }
+        phases.warmup_wakes.fetch_add(1, Ordering::SeqCst);
+        wake();
+        timer.sleep(Duration::from_micros(100));
+    }
+    phases.enter(PHASE_MEASURED);
+    task.begin_measurement();
+    true
+}
+
+/// Preallocated ring of the most recent (sequence, send time) pairs.
+/// Written lock-free by the producer; read once the run is over.
+struct SendHistory {
+    sequences: Box<[AtomicU64]>,
+    send_ns: Box<[AtomicU64]>,
+    next: AtomicU64,
+}
+
+impl SendHistory {
+    fn new(capacity: usize) -> Self {
+        Self {
+            sequences: (0..capacity).map(|_| AtomicU64::new(0)).collect(),
+            send_ns: (0..capacity).map(|_| AtomicU64::new(0)).collect(),
+            next: AtomicU64::new(0),
+        }
+    }
+
+    /// Stamp the send of wake `sequence`; call immediately before waking
+    fn record(&self, sequence: u64) {
+        let now = timestamp_clock_source().read_ns();
+        let index = self.next.fetch_add(1, Ordering::Relaxed) as usize % self.sequences.len();
+        self.sequences[index].store(sequence, Ordering::Relaxed);
+        self.send_ns[index].store(now, Ordering::Release);
+    }
+
+    /// Retained (sequences, send times), oldest first
+    fn snapshot(&self) -> (Vec<u64>, Vec<u64>) {
+        let capacity = self.sequences.len();
+        let count = self.next.load(Ordering::Acquire) as usize;
+        (count.saturating_sub(capacity)..count)
+            .map(|i| {
+                let send_ns = self.send_ns[i % capacity].load(Ordering::Acquire);
+                (
+                    self.sequences[i % capacity].load(Ordering::Relaxed),
+                    send_ns,
+                )
+            })
+            .unzip()
+    }
+}
+
+/// Memory ordering of a BenchCoordinator's hot-path counter operations
+#[derive(Clone, Copy, Default, PartialEq)]
+enum CounterOrdering {
+    Relaxed,
+    /// Release increments, acquire loads
+    AcqRel,
+    #[default]
+    SeqCst,
+}
+
+impl CounterOrdering {
+    const ALL: [Self; 3] = [Self::Relaxed, Self::AcqRel, Self::SeqCst];
+
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "relaxed" => Ok(Self::Relaxed),
+            "acq_rel" => Ok(Self::AcqRel),
+            "seq_cst" => Ok(Self::SeqCst),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "ordering must be 'relaxed', 'acq_rel' or 'seq_cst', got {other:?}"
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Relaxed => "relaxed",
+            Self::AcqRel => "acq_rel",
+            Self::SeqCst => "seq_cst",
+        }
+    }
+
+    /// For read-modify-writes, e.g. `fetch_add`
+    fn rmw(self) -> Ordering {
+        match self {
+            Self::Relaxed => Ordering::Relaxed,
+            Self::AcqRel => Ordering::AcqRel,
+            Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    fn load(self) -> Ordering {
+        match self {
+            Self::Relaxed => Ordering::Relaxed,
+            Self::AcqRel => Ordering::Acquire,
+            Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    /// How often a blocked waiter re-checks the count. Below SeqCst a
+    /// producer may miss a waiter that just registered and skip the notify
+    /// (see `WakeCount::increment`), so waiters poll as a backstop.
+    fn recheck_interval(self) -> Option<Duration> {
+        match self {
+            Self::SeqCst => None,
+            Self::Relaxed | Self::AcqRel => Some(Duration::from_millis(1)),
+        }
+    }
+}
+
+/// A coordinator's count of wakes sent, which Python can block on, and
+/// optionally when each was sent
+#[derive(Default)]
+struct WakeCount {
+    count: AtomicU64,
+    ordering: CounterOrdering,
+    /// Threads in `wait_for()`; producers only notify while there are any
+    waiters: AtomicU64,
+    lock: Mutex<()>,
+    changed: Condvar,
+    history: Option<SendHistory>,
+}
+
+impl WakeCount {
+    fn new(capacity: usize, ordering: CounterOrdering) -> Self {
+        Self {
+            ordering,
+            history: (capacity > 0).then(|| SendHistory::new(capacity)),
+            ..Default::default()
+        }
+    }
+
+    fn load(&self) -> u64 {
+        self.count.load(self.ordering.load())
+    }
+
+    /// Count one wake, returning its sequence number. Free of locks unless
+    /// someone is waiting: under SeqCst a waiter registers before checking
+    /// the count, so either it sees this increment or this sees it. Weaker
+    /// orderings don't guarantee that, and rely on the waiter re-checking.
+    fn increment(&self) -> u64 {
+        let sequence = self.count.fetch_add(1, self.ordering.rmw());
+        if self.waiters.load(self.ordering.load()) > 0 {
+            self.notify();
+        }
+        sequence
+    }
+
+    /// Record, if keeping a history, that wake `sequence` is being sent now
+    fn record_send(&self, sequence: u64) {
+        if let Some(history) = &self.history {
+            history.record(sequence);
+        }
+    }
+
+    fn reset(&self) {
+        self.count.store(0, Ordering::SeqCst);
+        if let Some(history) = &self.history {
+            history.next.store(0, Ordering::SeqCst);
+        }
+        self.notify();
+    }
+
+    /// Taking the lock orders this after a waiter's check
+    fn notify(&self) {
+        drop(self.lock.lock().unwrap_or_else(|e| e.into_inner()));
+        self.changed.notify_all();
+    }
+
+    /// Block until the count reaches `target` or `timeout` passes; returns
+    /// whether it was reached
+    fn wait_for(&self, target: u64, timeout: Option<Duration>) -> bool {
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        while self.load() < target {
+            let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            if remaining == Some(Duration::ZERO) {
+                break;
+            }
+            let slice = match (remaining, self.ordering.recheck_interval()) {
+                (Some(remaining), Some(interval)) => Some(remaining.min(interval)),
+                (remaining, interval) => remaining.or(interval),
+            };
+            guard = match slice {
+                Some(slice) => {
+                    self.changed
+                        .wait_timeout(guard, slice)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0
+                }
+                None => self.changed.wait(guard).unwrap_or_else(|e| e.into_inner()),
+            };
+        }
+        drop(guard);
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+        self.load() >= target
+    }
+}
+
+/// Acks and completion of each event loop sharing a coordinator
+struct LoopCounters {
+    acks: Box<[AtomicU64]>,
+    finished: Box<[AtomicBool]>,
+    ordering: CounterOrdering,
+    /// Threads in `wait_all()`; acks only notify while there are any
+    waiters: AtomicU64,
+    lock: Mutex<()>,
+    changed: Condvar,
+}
+
+impl LoopCounters {
+    fn new(loops: usize, ordering: CounterOrdering) -> Self {
+        Self {
+            acks: (0..loops).map(|_| AtomicU64::new(0)).collect(),
+            finished: (0..loops).map(|_| AtomicBool::new(false)).collect(),
+            ordering,
+            waiters: AtomicU64::new(0),
+            lock: Mutex::new(()),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Count an ack from loop `index`. Free of locks unless someone is in
+    /// `wait_all()`, as for `WakeCount::increment`.
+    fn ack(&self, index: usize) {
+        self.acks[index].fetch_add(1, self.ordering.rmw());
+        if self.waiters.load(self.ordering.load()) > 0 {
+            self.notify();
+        }
+    }
+
+    /// Block until `complete(expected)` holds or `timeout` passes; returns
+    /// whether it does. Polls below SeqCst, as `WakeCount::wait_for` does.
+    fn wait_all(&self, expected: &[u64], timeout: Option<Duration>) -> bool {
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        while !self.complete(expected) {
+            let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            if remaining == Some(Duration::ZERO) {
+                break;
+            }
+            let slice = match (remaining, self.ordering.recheck_interval()) {
+                (Some(remaining), Some(interval)) => Some(remaining.min(interval)),
+                (remaining, interval) => remaining.or(interval),
+            };
+            guard = match slice {
+                Some(slice) => {
+                    self.changed
+                        .wait_timeout(guard, slice)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0
+                }
+                None => self.changed.wait(guard).unwrap_or_else(|e| e.into_inner()),
+            };
+        }
+        drop(guard);
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+        self.complete(expected)
+    }
+
+    fn check(&self, index: usize) -> PyResult<()> {
+        if index < self.acks.len() {
+            return Ok(());
+        }
+        Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(format!(
+            "loop {index} out of range for a coordinator of {} loops",
+            self.acks.len()
+        )))
+    }
+
+    /// Wake `wait_all()` callers to re-check. Taking the lock orders this
+    /// after a waiter's check, so the change can't slip in unnoticed.
+    fn notify(&self) {
+        drop(self.lock.lock().unwrap_or_else(|e| e.into_inner()));
+        self.changed.notify_all();
+    }
+
+    /// Whether every loop has finished or acked its `expected` count
+    fn complete(&self, expected: &[u64]) -> bool {
+        self.acks
+            .iter()
+            .zip(&self.finished)
+            .zip(expected)
+            .all(|((acks, finished), &expected)| {
+                finished.load(Ordering::SeqCst) || acks.load(Ordering::SeqCst) >= expected
+            })
+    }
+
+    fn reset(&self) {
+        for (acks, finished) in self.acks.iter().zip(&self.finished) {
+            acks.store(0, Ordering::SeqCst);
+            finished.store(false, Ordering::SeqCst);
+        }
+        self.notify();
+    }
+}
+
+/// Counters every coordinator has besides `sent` and `acks`
+const DEFAULT_COUNTERS: [&str; 3] = ["received", "dropped", "errors"];
+
+/// Times `BenchCoordinator.snapshot()` re-reads before settling for the
+/// latest reading
+const SNAPSHOT_ATTEMPTS: usize = 1000;
+
+/// Counters a harness updates with `BenchCoordinator.count()`, by name
+struct NamedCounters {
+    names: Vec<String>,
+    values: Box<[AtomicU64]>,
+}
+
+impl NamedCounters {
+    /// The default counters followed by `extra`
+    fn new(extra: Vec<String>) -> PyResult<Self> {
+        let mut names: Vec<String> = DEFAULT_COUNTERS.map(String::from).to_vec();
+        for name in extra {
+            if name == "sent" || name == "acks" || names.contains(&name) {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "counter {name:?} already exists"
+                )));
+            }
+            names.push(name);
+        }
+        let values = names.iter().map(|_| AtomicU64::new(0)).collect();
+        Ok(Self { names, values })
+    }
+
+    fn get(&self, name: &str) -> PyResult<&AtomicU64> {
+        match self.names.iter().position(|n| n == name) {
+            Some(index) => Ok(&self.values[index]),
+            None if name == "sent" || name == "acks" => {
+                Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "{name} is counted by the {}",
+                    if name == "sent" {
+                        "producer"
+                    } else {
+                        "ack() method"
+                    }
+                )))
+            }
+            None => Err(PyErr::new::<pyo3::exceptions::PyKeyError, _>(
+                name.to_string(),
+            )),
+        }
+    }
+
+    fn reset(&self) {
+        for value in self.values.iter() {
+            value.store(0, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Shared counter for coordinating benchmark iterations. With `loops` > 1,
+/// several event loops (e.g. fed by a WakerGroup) ack into their own
+/// counters, and `wait_all()` blocks until every loop is done. With
+/// `history` > 0, the producer also records when it sent each of the last
+/// `history` wakes, for `send_history()`. `ordering` is the memory ordering
+/// of the wake and ack counters: `"seq_cst"` (the default), `"acq_rel"` or
+/// `"relaxed"`. Below `"seq_cst"`, `wait_for()` may return up to a
+/// millisecond after the count is reached.
+///
+/// Besides `sent` (the producer's wake count) and `acks`, the coordinator
+/// keeps `received`, `dropped` and `errors` counters, plus any names in
+/// `counters`, for the harness to bump with `count()`. `snapshot()` reads
+/// them all at once.
+#[pyclass]
+struct BenchCoordinator {
+    counter: Arc<WakeCount>,
+    acks: Arc<AtomicU64>,
+    phases: Arc<SequencePhases>,
+    loops: Arc<LoopCounters>,
+    named: NamedCounters,
+}
+
+#[pymethods]
+impl BenchCoordinator {
+    #[new]
+    #[pyo3(signature = (loops=1, history=0, ordering="seq_cst", counters=None))]
+    fn new(
+        loops: usize,
+        history: usize,
+        ordering: &str,
+        counters: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        if loops == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "loops must be at least 1",
+            ));
+        }
+        let ordering = CounterOrdering::parse(ordering)?;
+        Ok(Self {
+            counter: Arc::new(WakeCount::new(history, ordering)),
+            acks: Arc::new(AtomicU64::new(0)),
+            phases: Arc::new(SequencePhases::default()),
+            loops: Arc::new(LoopCounters::new(loops, ordering)),
+            named: NamedCounters::new(counters.unwrap_or_default())?,
+        })
+    }
+
+    /// Names of the counters in `snapshot()`, in order
+    #[getter]
+    fn counters(&self) -> Vec<String> {
+        let builtin = ["sent", "acks"].map(String::from);
+        builtin
+            .into_iter()
+            .chain(self.named.names.clone())
+            .collect()
+    }
+
+    /// Add `n` to the counter `name`, e.g. `count("received")` in a wake
+    /// callback. Raises KeyError for an unknown name.
+    #[pyo3(signature = (name, n=1))]
+    fn count(&self, name: &str, n: u64) -> PyResult<()> {
+        self.named
+            .get(name)?
+            .fetch_add(n, self.counter.ordering.rmw());
+        Ok(())
+    }
+
+    /// Every counter as a dict, with values that all held at one instant:
+    /// the counters are re-read until two readings in a row agree. If they
+    /// keep changing, the latest reading is returned; it reads `sent` last,
+    /// so `received <= sent` still holds.
+    fn snapshot<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let read = || -> Vec<u64> {
+            let mut values = vec![0, self.acks.load(Ordering::SeqCst)];
+            values.extend(self.named.values.iter().map(|v| v.load(Ordering::SeqCst)));
+            values[0] = self.counter.count.load(Ordering::SeqCst);
+            values
+        };
+        let mut values = read();
+        for _ in 0..SNAPSHOT_ATTEMPTS {
+            let again = read();
+            if again == values {
+                break;
+            }
+            values = again;
+        }
+        let dict = pyo3::types::PyDict::new(py);
+        for (name, value) in self.counters().into_iter().zip(values) {
+            dict.set_item(name, value)?;
+        }
+        Ok(dict)
+    }
+
+    /// Memory ordering of the wake and ack counters
+    #[getter]
+    fn ordering(&self) -> &'static str {
+        self.counter.ordering.name()
+    }
+
+    /// Number of event loops acking into this coordinator
+    #[getter]
+    fn loops(&self) -> usize {
+        self.loops.acks.len()
+    }
+
+    /// Phase of the running sequence benchmark: "idle", "warmup",
+    /// "measured", or "finished"
+    #[getter]
+    fn phase(&self) -> &'static str {
+        PHASE_NAMES[self.phases.phase.load(Ordering::SeqCst) as usize]
+    }
+
+    /// Warmup wakes sent so far; these don't advance `get_count()`
+    fn get_warmup_count(&self) -> u64 {
+        self.phases.warmup_wakes.load(Ordering::SeqCst)
+    }
+
+    /// `monotonic_ns()` at which the measured phase began, or None before then
+    fn measured_start_ns(&self) -> Option<u64> {
+        match self.phases.measured_start_ns.load(Ordering::SeqCst) {
+            0 => None,
+            ns => Some(ns),
+        }
+    }
+
+    fn get_count(&self) -> u64 {
+        self.counter.load()
+    }
+
+    /// `(sequences, send_ns)` for the most recent `history` wakes, oldest
+    /// first, as two `array.array("Q")` of equal length. `send_ns[i]` is
+    /// when wake `sequences[i]` was sent, on the timestamp clock (see
+    /// `set_timestamp_clock()`), so it lines up with `wake_timestamps()` and
+    /// TimestampingCallback receipts. Read it once the run is over. Raises
+    /// ValueError if the coordinator was created without a history.
+    fn send_history<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        let Some(history) = &self.counter.history else {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "BenchCoordinator was created with history=0",
+            ));
+        };
+        let (sequences, send_ns) = history.snapshot();
+        let array = py.import("array")?.getattr("array")?;
+        let to_array = |values: Vec<u64>| -> PyResult<Bound<'py, PyAny>> {
+            let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_ne_bytes()).collect();
+            array.call1(("Q", pyo3::types::PyBytes::new(py, &bytes)))
+        };
+        Ok((to_array(sequences)?, to_array(send_ns)?))
+    }
+
+    /// Block, with the GIL released, until `get_count()` reaches `count`.
+    /// Returns False if `timeout` seconds pass first. Unlike polling
+    /// `get_count()`, this uses no CPU while waiting and returns as soon as
+    /// the producer sends the wake.
+    #[pyo3(signature = (count, timeout=None))]
+    fn wait_for(&self, py: Python<'_>, count: u64, timeout: Option<f64>) -> PyResult<bool> {
+        let timeout = timeout
+            .map(|secs| {
+                Duration::try_from_secs_f64(secs).map_err(|_| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "timeout must be a non-negative number of seconds",
+                    )
+                })
+            })
+            .transpose()?;
+        let counter = self.counter.clone();
+        Ok(py.allow_threads(move || counter.wait_for(count, timeout)))
+    }
+
+    /// Acknowledge a wake (call this in the callback during round-trip
+    /// benchmarks). Counts toward `get_ack_count()` and loop `loop_index`.
+    #[pyo3(signature = (loop_index=0))]
+    fn ack(&self, loop_index: usize) -> PyResult<()> {
+        self.loops.check(loop_index)?;
+        let ordering = self.counter.ordering;
+        self.acks.fetch_add(1, ordering.rmw());
+        self.loops.ack(loop_index);
+        Ok(())
+    }
+
+    fn get_ack_count(&self) -> u64 {
+        self.acks.load(self.counter.ordering.load())
+    }
+
+    /// Acks from each loop, in index order
+    fn loop_ack_counts(&self) -> Vec<u64> {
+        let ordering = self.counter.ordering.load();
+        self.loops
+            .acks
+            .iter()
+            .map(|acks| acks.load(ordering))
+            .collect()
+    }
+
+    /// Mark loop `loop_index` done, whatever its ack count, e.g. when it
+    /// exits early. `wait_all()` stops waiting for it.
+    fn finish_loop(&self, loop_index: usize) -> PyResult<()> {
+        self.loops.check(loop_index)?;
+        self.loops.finished[loop_index].store(true, Ordering::SeqCst);
+        self.loops.notify();
+        Ok(())
+    }
+
+    /// Indices of the loops marked done by `finish_loop()`
+    fn finished_loops(&self) -> Vec<usize> {
+        (0..self.loops.finished.len())
+            .filter(|&index| self.loops.finished[index].load(Ordering::SeqCst))
+            .collect()
+    }
+
+    /// Block, with the GIL released, until every loop has acked `expected`
+    /// wakes (an int for all loops, or a list with one count per loop) or
+    /// called `finish_loop()`. Returns False if `timeout` seconds pass first.
+    #[pyo3(signature = (expected, timeout=None))]
+    fn wait_all(
+        &self,
+        py: Python<'_>,
+        expected: &Bound<'_, PyAny>,
+        timeout: Option<f64>,
+    ) -> PyResult<bool> {
+        let loops = self.loops.acks.len();
+        let expected: Vec<u64> = match expected.extract::<u64>() {
+            Ok(count) => vec![count; loops],
+            Err(_) => expected.extract()?,
+        };
+        if expected.len() != loops {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "expected has {} counts for {loops} loops",
+                expected.len()
+            )));
+        }
+        let timeout = timeout
+            .map(|secs| {
+                Duration::try_from_secs_f64(secs).map_err(|_| {
+                    PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                        "timeout must be a non-negative number of seconds",
+                    )
+                })
+            })
+            .transpose()?;
+        let counters = self.loops.clone();
+        Ok(py.allow_threads(move || counters.wait_all(&expected, timeout)))
+    }
+
+    fn reset(&self) {
+        self.counter.reset();
+        self.acks.store(0, Ordering::SeqCst);
+        self.phases.reset();
+        self.loops.reset();
+        self.named.reset();
+    }
+}
+
+/// Mean ns per `BenchCoordinator` wake-count increment under each memory
+/// ordering, as `{"relaxed": ..., "acq_rel": ..., "seq_cst": ...}`. Each of
+/// `threads` threads increments one shared counter `iterations` times and
+/// times itself; with more than one thread the figures include cache-line
+/// contention.
+/// Runs with the GIL released.
+#[pyfunction]
+#[pyo3(signature = (iterations=1_000_000, threads=1))]
+fn measure_counter_orderings<'py>(
+    py: Python<'py>,
+    iterations: u64,
+    threads: usize,
+) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+    if iterations == 0 || threads == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "iterations and threads must be positive",
+        ));
+    }
+    let costs = py.allow_threads(|| {
+        CounterOrdering::ALL.map(|ordering| {
+            let count = WakeCount::new(0, ordering);
+            let elapsed_ns: u128 = std::thread::scope(|scope| {
+                let workers: Vec<_> = (0..threads)
+                    .map(|_| {
+                        scope.spawn(|| {
+                            let start = Instant::now();
+                            for _ in 0..iterations {
+                                std::hint::black_box(count.increment());
+                            }
+                            start.elapsed().as_nanos()
+                        })
+                    })
+                    .collect();
+                workers.into_iter().map(|w| w.join().unwrap()).sum()
+            });
+            let increments = iterations as f64 * threads as f64;
+            (ordering.name(), elapsed_ns as f64 / increments)
+        })
+    });
+    let dict = pyo3::types::PyDict::new(py);
+    for (name, ns) in costs {
+        dict.set_item(name, ns)?;
+    }
+    Ok(dict)
+}
+
+/// How a sequence paces its measured wakes
+#[derive(Clone, Copy, PartialEq)]
+enum SequenceMode {
+    /// Sleep `interval` after each wake, whether or not Python kept up
+    Paced,
+    /// Wake every `interval` on an absolute schedule from the start; late
+    /// wakes go out at once, regardless of Python keeping up
+    Open,
+    /// Wait for the coordinator to be acked for each wake, then `interval`
+    Closed,
+}
+
+impl SequenceMode {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "paced" => Ok(Self::Paced),
+            "open" => Ok(Self::Open),
+            "closed" => Ok(Self::Closed),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "mode must be 'paced', 'open' or 'closed', got {name:?}"
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Paced => "paced",
+            Self::Open => "open",
+            Self::Closed => "closed",
+        }
+    }
+}
+
+/// Load mode and gap between a sequence's wakes
+#[derive(Clone, Copy)]
+struct SequencePacing {
+    mode: SequenceMode,
+    interval: Duration,
+}
+
+impl SequencePacing {
+    fn new(mode: &str, interval_micros: u64) -> PyResult<Self> {
+        Ok(Self {
+            mode: SequenceMode::parse(mode)?,
+            interval: Duration::from_micros(interval_micros),
+        })
+    }
+}
+
+impl Default for SequencePacing {
+    /// The historical behaviour: 100 µs after each wake
+    fn default() -> Self {
+        Self {
+            mode: SequenceMode::Paced,
+            interval: Duration::from_micros(100),
+        }
+    }
+}
+
+/// Spin until `acks` reaches `target` or the task is cancelled; returns
+/// whether it was reached
+fn wait_for_ack(task: &TaskState, acks: &AtomicU64, target: u64) -> bool {
+    while acks.load(Ordering::SeqCst) < target {
+        if task.is_cancelled() {
+            return false;
+        }
+        std::thread::yield_now();
+    }
+    true
+}
+
+/// Spawn a thread that performs `iterations` wakeups via `mechanism`, paced
+/// by `pacing`, after `warmup_iterations` unmeasured ones. Each measured
+/// wakeup increments the coordinator's counter first.
+#[allow(clippy::too_many_arguments)]
+fn spawn_sequence(
+    benchmark: &'static str,
+    mechanism: Mechanism,
+    coordinator: &BenchCoordinator,
+    iterations: RunLength,
+    recorder: Option<&LatencyRecorder>,
+    thread: impl Into<TaskThread>,
+    spin_threshold_micros: u64,
+    warmup_iterations: usize,
+    pacing: SequencePacing,
+    start: Option<Arc<StartLine>>,
+) -> BenchTask {
+    let thread = thread.into();
+    let spin_threshold = Duration::from_micros(spin_threshold_micros);
+    let counter = coordinator.counter.clone();
+    let acks = coordinator.acks.clone();
+    let phases = coordinator.phases.clone();
+    let clock = recorder.map_or(ClockSource::Monotonic, |r| r.clock);
+    let recorder = recorder.map(|r| r.recording.clone());
+    let scheduled = recorder.clone().map(|recording| (recording, clock));
+
+    let spec = iterations
+        .annotate(BenchSpec::new(
+            benchmark,
+            iterations.requested(),
+            &thread.placement(),
+        ))
+        .config(
+            "spin_threshold_micros",
+            ConfigValue::Int(spin_threshold_micros as i64),
+        )
+        .config(
+            "warmup_iterations",
+            ConfigValue::Int(warmup_iterations as i64),
+        )
+        .config("mode", ConfigValue::Str(pacing.mode.name()))
+        .config(
+            "interval_micros",
+            ConfigValue::Int(pacing.interval.as_micros() as i64),
+        )
+        .recorder(recorder.clone())
+        .start_line(start);
+    let spec = mechanism.annotate(spec);
+    let handle = mechanism.handle;
+
+    spawn_bench_task(thread, spec, move |task| {
+        let timer = Timer::new(TimerBackend::Sleep, spin_threshold);
+        // In closed mode every wake, warmup included, waits for its ack
+        let closed = pacing.mode == SequenceMode::Closed;
+        let acked = acks.load(Ordering::SeqCst);
+        let warmed = std::cell::Cell::new(0);
+        let warmup_wake = || {
+            handle.wake();
+            if closed {
+                warmed.set(warmed.get() + 1);
+                wait_for_ack(task, &acks, acked + warmed.get());
+            }
+        };
+        if !sequence_warmup(task, &phases, &timer, warmup_iterations, warmup_wake) {
+            handle.finish();
+            return 0;
+        }
+        let acked = acked + warmed.get();
+        let mut sent = 0;
+        let start = Instant::now();
+        let start_ns = monotonic_ns();
+        while iterations.more(sent, start) {
+            if task.is_cancelled() {
+                break;
+            }
+            let sequence = counter.increment();
+            if pacing.mode == SequenceMode::Open {
+                // Open loop runs on a schedule, so correct for coordinated omission
+                let due = start_ns + pacing.interval.as_nanos() as u64 * (sent + 1);
+                let behind = monotonic_ns() >= due;
+                timer.sleep_until(due);
+                scheduled_wake(&scheduled, due, behind, || {
+                    counter.record_send(sequence);
+                    handle.wake();
+                });
+            } else {
+                counter.record_send(sequence);
+                let start = clock.read_ns();
+                handle.wake();
+                record_latency(&recorder, clock.read_ns().saturating_sub(start));
+            }
+            sent += 1;
+            match pacing.mode {
+                // Small delay to allow Python to process
+                SequenceMode::Paced => {
+                    timer.sleep(pacing.interval);
+                }
+                SequenceMode::Open => {}
+                SequenceMode::Closed => {
+                    if !wait_for_ack(task, &acks, acked + sent) {
+                        break;
+                    }
+                    timer.sleep(pacing.interval);
+                }
+            }
+        }
+        handle.finish();
+        phases.enter(PHASE_FINISHED);
+        sent
+    })
+}
+
+/// Spawn a thread that will perform `iterations` wakeups via any waker listed
+/// by `mechanisms()`, with a small delay between each. Each wakeup increments
+/// the coordinator's counter, allowing Python to verify receipt. Pass
+/// `duration_secs` instead of `iterations` to keep going until that much time
+/// has passed after warmup.
+///
+/// `mode` picks the load model, with `interval_micros` as the gap:
+/// - "paced" (the default) sleeps the gap after each wake
+/// - "open" sends a wake every `interval` on an absolute schedule, whether
+///   or not Python keeps up; a late wake goes out at once
+/// - "closed" waits for `coordinator.ack()` after each wake, then sleeps the
+///   gap; the callback must ack every wake
+///
+/// With a `barrier`, the thread waits at that StartBarrier before starting.
+#[pyfunction]
+#[pyo3(signature = (waker, coordinator, iterations=None, recorder=None, pin_cpu=None, rt_priority=None, rt_policy="fifo", spin_threshold_micros=0, warmup_iterations=0, duration_secs=None, mode="paced", interval_micros=100, barrier=None))]
+#[allow(clippy::too_many_arguments)]
+fn wakeup_sequence(
+    waker: &Bound<'_, PyAny>,
+    coordinator: &BenchCoordinator,
+    iterations: Option<usize>,
+    recorder: Option<&LatencyRecorder>,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+    spin_threshold_micros: u64,
+    warmup_iterations: usize,
+    duration_secs: Option<f64>,
+    mode: &str,
+    interval_micros: u64,
+    barrier: Option<&StartBarrier>,
+) -> PyResult<BenchTask> {
+    let length = RunLength::parse(iterations, duration_secs, "iterations")?;
+    let pacing = SequencePacing::new(mode, interval_micros)?;
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    Ok(spawn_sequence(
+        "wakeup_sequence",
+        resolve_mechanism(waker)?,
+        coordinator,
+        length,
+        recorder,
+        placement,
+        spin_threshold_micros,
+        warmup_iterations,
+        pacing,
+        barrier.map(|barrier| barrier.line.clone()),
+    ))
+}
 
 // =============================================================================
-// Approach 1: FD-based wakeup (no GIL acquisition on Rust side)
+// Scenario-driven benchmarks: any waker under any load
+// =============================================================================
+
+/// The load a `Scenario` applies
+enum ScenarioKind {
+    Burst {
+        length: RunLength,
+    },
+    Sequence {
+        coordinator: Py<BenchCoordinator>,
+        length: RunLength,
+        recorder: Option<Py<LatencyRecorder>>,
+        spin_threshold_micros: u64,
+        warmup_iterations: usize,
+        pacing: SequencePacing,
+    },
+    Rate {
+        rate_per_sec: f64,
+        duration_secs: f64,
+        // Poisson arrivals from this seed; fixed gaps when None
+        seed: Option<u64>,
+        spin_threshold_micros: u64,
+        recorder: Option<Py<LatencyRecorder>>,
+    },
+    Multi {
+        threads: usize,
+        count_per_thread: usize,
+        recorder: Option<Py<LatencyRecorder>>,
+    },
+    Ramp {
+        rates: Vec<f64>,
+        step_secs: f64,
+        // Poisson arrivals from this seed; fixed gaps when None
+        seed: Option<u64>,
+        spin_threshold_micros: u64,
+    },
+    Storm {
+        rate_per_sec: f64,
+        storm_secs: f64,
+        quiet_secs: f64,
+        // Idle between storms when None
+        quiet_rate_per_sec: Option<f64>,
+        cycles: usize,
+        // Poisson arrivals from this seed; fixed gaps when None
+        seed: Option<u64>,
+        spin_threshold_micros: u64,
+        storm_recorder: Option<Py<LatencyRecorder>>,
+        quiet_recorder: Option<Py<LatencyRecorder>>,
+    },
+}
+
+/// A load pattern for `run_benchmark`, built with one of the static
+/// constructors: `burst`, `sequence`, `rate`, `poisson`, `multi`, `ramp` or
+/// `storm`
+#[pyclass(frozen)]
+struct Scenario {
+    kind: ScenarioKind,
+}
+
+#[pymethods]
+impl Scenario {
+    /// `count` wakes sent as fast as possible, or as many as fit in
+    /// `duration_secs`
+    #[staticmethod]
+    #[pyo3(signature = (count=None, duration_secs=None))]
+    fn burst(count: Option<usize>, duration_secs: Option<f64>) -> PyResult<Self> {
+        Ok(Self {
+            kind: ScenarioKind::Burst {
+                length: RunLength::parse(count, duration_secs, "count")?,
+            },
+        })
+    }
+
+    /// `iterations` paced wakes (or as many as fit in `duration_secs`)
+    /// counted by `coordinator`, with optional latency recording and
+    /// unmeasured warmup wakes. `mode` is "paced", "open" or "closed", as for
+    /// `wakeup_sequence`.
+    #[staticmethod]
+    #[pyo3(signature = (coordinator, iterations=None, recorder=None, spin_threshold_micros=0, warmup_iterations=0, duration_secs=None, mode="paced", interval_micros=100))]
+    #[allow(clippy::too_many_arguments)]
+    fn sequence(
+        coordinator: Py<BenchCoordinator>,
+        iterations: Option<usize>,
+        recorder: Option<Py<LatencyRecorder>>,
+        spin_threshold_micros: u64,
+        warmup_iterations: usize,
+        duration_secs: Option<f64>,
+        mode: &str,
+        interval_micros: u64,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            kind: ScenarioKind::Sequence {
+                coordinator,
+                length: RunLength::parse(iterations, duration_secs, "iterations")?,
+                recorder,
+                spin_threshold_micros,
+                warmup_iterations,
+                pacing: SequencePacing::new(mode, interval_micros)?,
+            },
+        })
+    }
+
+    /// Wakes at a fixed `rate_per_sec` for `duration_secs`. A `recorder`
+    /// receives each wake's cost, corrected for coordinated omission.
+    #[staticmethod]
+    #[pyo3(signature = (rate_per_sec, duration_secs, spin_threshold_micros=0, recorder=None))]
+    fn rate(
+        rate_per_sec: f64,
+        duration_secs: f64,
+        spin_threshold_micros: u64,
+        recorder: Option<Py<LatencyRecorder>>,
+    ) -> PyResult<Self> {
+        rate_interval(rate_per_sec)?;
+        run_duration(duration_secs)?;
+        Ok(Self {
+            kind: ScenarioKind::Rate {
+                rate_per_sec,
+                duration_secs,
+                seed: None,
+                spin_threshold_micros,
+                recorder,
+            },
+        })
+    }
+
+    /// Poisson arrivals averaging `rate_per_sec` for `duration_secs`; the
+    /// same `seed` reproduces the same schedule. A `recorder` is fed as for
+    /// `rate`.
+    #[staticmethod]
+    #[pyo3(signature = (rate_per_sec, duration_secs, seed=0, spin_threshold_micros=0, recorder=None))]
+    fn poisson(
+        rate_per_sec: f64,
+        duration_secs: f64,
+        seed: u64,
+        spin_threshold_micros: u64,
+        recorder: Option<Py<LatencyRecorder>>,
+    ) -> PyResult<Self> {
+        rate_interval(rate_per_sec)?;
+        run_duration(duration_secs)?;
+        Ok(Self {
+            kind: ScenarioKind::Rate {
+                rate_per_sec,
+                duration_secs,
+                seed: Some(seed),
+                spin_threshold_micros,
+                recorder,
+            },
+        })
+    }
+
+    /// `threads` producers sharing the waker, each sending
+    /// `count_per_thread` wakes as fast as possible. A `recorder` receives
+    /// each wake's cost tagged with its producer thread (the task's own
+    /// thread is 0).
+    #[staticmethod]
+    #[pyo3(signature = (threads, count_per_thread, recorder=None))]
+    fn multi(
+        threads: usize,
+        count_per_thread: usize,
+        recorder: Option<Py<LatencyRecorder>>,
+    ) -> PyResult<Self> {
+        if threads == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "threads must be at least 1",
+            ));
+        }
+        Ok(Self {
+            kind: ScenarioKind::Multi {
+                threads,
+                count_per_thread,
+                recorder,
+            },
+        })
+    }
+
+    /// Offered load stepping through `rates` (wakes per second), `step_secs`
+    /// at each, to find where a mechanism saturates. Fixed gaps, or Poisson
+    /// arrivals when `seed` is given. Each step's wake cost is recorded
+    /// separately, corrected for coordinated omission, and reported in
+    /// `result().steps`.
+    #[staticmethod]
+    #[pyo3(signature = (rates, step_secs, seed=None, spin_threshold_micros=0))]
+    fn ramp(
+        rates: Vec<f64>,
+        step_secs: f64,
+        seed: Option<u64>,
+        spin_threshold_micros: u64,
+    ) -> PyResult<Self> {
+        if rates.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "rates must not be empty",
+            ));
+        }
+        for &rate in &rates {
+            rate_interval(rate)?;
+        }
+        if !(step_secs > 0.0 && step_secs.is_finite()) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "step_secs must be a positive number",
+            ));
+        }
+        Ok(Self {
+            kind: ScenarioKind::Ramp {
+                rates,
+                step_secs,
+                seed,
+                spin_threshold_micros,
+            },
+        })
+    }
+
+    /// Storms of `rate_per_sec` wakes per second lasting `storm_secs`, each
+    /// followed by `quiet_secs` idle (or at `quiet_rate_per_sec`), repeated
+    /// `cycles` times on an absolute schedule. Fixed gaps, or Poisson
+    /// arrivals when `seed` is given. Each phase of each cycle is reported
+    /// in `result().steps`, tagged with its phase and cycle; the recorders
+    /// receive every storm or quiet phase's wake costs, corrected for
+    /// coordinated omission.
+    #[staticmethod]
+    #[pyo3(signature = (rate_per_sec, storm_secs, quiet_secs, cycles, quiet_rate_per_sec=None, seed=None, spin_threshold_micros=0, storm_recorder=None, quiet_recorder=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn storm(
+        rate_per_sec: f64,
+        storm_secs: f64,
+        quiet_secs: f64,
+        cycles: usize,
+        quiet_rate_per_sec: Option<f64>,
+        seed: Option<u64>,
+        spin_threshold_micros: u64,
+        storm_recorder: Option<Py<LatencyRecorder>>,
+        quiet_recorder: Option<Py<LatencyRecorder>>,
+    ) -> PyResult<Self> {
+        rate_interval(rate_per_sec)?;
+        if let Some(rate) = quiet_rate_per_sec {
+            rate_interval(rate)?;
+        }
+        if !(storm_secs > 0.0 && storm_secs.is_finite()) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "storm_secs must be a positive number",
+            ));
+        }
+        if !(quiet_secs >= 0.0 && quiet_secs.is_finite()) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "quiet_secs must be a non-negative number",
+            ));
+        }
+        if cycles == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "cycles must be at least 1",
+            ));
+        }
+        Ok(Self {
+            kind: ScenarioKind::Storm {
+                rate_per_sec,
+                storm_secs,
+                quiet_secs,
+                quiet_rate_per_sec,
+                cycles,
+                seed,
+                spin_threshold_micros,
+                storm_recorder,
+                quiet_recorder,
+            },
+        })
+    }
+
+    /// "burst", "sequence", "rate", "poisson", "multi", "ramp" or "storm"
+    #[getter]
+    fn kind(&self) -> &'static str {
+        match self.kind {
+            ScenarioKind::Burst { .. } => "burst",
+            ScenarioKind::Sequence { .. } => "sequence",
+            ScenarioKind::Rate { seed: None, .. } => "rate",
+            ScenarioKind::Rate { seed: Some(_), .. } => "poisson",
+            ScenarioKind::Multi { .. } => "multi",
+            ScenarioKind::Ramp { .. } => "ramp",
+            ScenarioKind::Storm { .. } => "storm",
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.kind {
+            ScenarioKind::Burst { length } => match length {
+                RunLength::Count(count) => format!("Scenario.burst(count={count})"),
+                RunLength::Duration(duration) => format!(
+                    "Scenario.burst(duration_secs={})",
+                    duration.as_secs_f64()
+                ),
+            },
+            ScenarioKind::Sequence {
+                length,
+                warmup_iterations,
+                pacing,
+                ..
+            } => {
+                let length = match length {
+                    RunLength::Count(iterations) => format!("iterations={iterations}"),
+                    RunLength::Duration(duration) => {
+                        format!("duration_secs={}", duration.as_secs_f64())
+                    }
+                };
+                format!(
+                    "Scenario.sequence({length}, warmup_iterations={warmup_iterations}, mode='{}')",
+                    pacing.mode.name()
+                )
+            }
+            ScenarioKind::Rate {
+                rate_per_sec,
+                duration_secs,
+                seed,
+                ..
+            } => match seed {
+                None => format!(
+                    "Scenario.rate(rate_per_sec={rate_per_sec}, duration_secs={duration_secs})"
+                ),
+                Some(seed) => format!(
+                    "Scenario.poisson(rate_per_sec={rate_per_sec}, duration_secs={duration_secs}, seed={seed})"
+                ),
+            },
+            ScenarioKind::Multi {
+                threads,
+                count_per_thread,
+                ..
+            } => format!("Scenario.multi(threads={threads}, count_per_thread={count_per_thread})"),
+            ScenarioKind::Ramp {
+                rates,
+                step_secs,
+                seed,
+                ..
+            } => match seed {
+                None => format!("Scenario.ramp(rates={rates:?}, step_secs={step_secs})"),
+                Some(seed) => {
+                    format!("Scenario.ramp(rates={rates:?}, step_secs={step_secs}, seed={seed})")
+                }
+            },
+            ScenarioKind::Storm {
+                rate_per_sec,
+                storm_secs,
+                quiet_secs,
+                quiet_rate_per_sec,
+                cycles,
+                seed,
+                ..
+            } => {
+                let mut repr = format!(
+                    "Scenario.storm(rate_per_sec={rate_per_sec}, storm_secs={storm_secs}, quiet_secs={quiet_secs}, cycles={cycles}"
+                );
+                if let Some(rate) = quiet_rate_per_sec {
+                    repr.push_str(&format!(", quiet_rate_per_sec={rate}"));
+                }
+                if let Some(seed) = seed {
+                    repr.push_str(&format!(", seed={seed}"));
+                }
+                repr + ")"
+            }
+        }
+    }
+}
+
+/// Spawn a task that wakes on a fixed or Poisson schedule until `duration_secs`
+/// elapses or it is cancelled
+#[allow(clippy::too_many_arguments)]
+fn spawn_rate_task(
+    benchmark: &'static str,
+    mechanism: Mechanism,
+    placement: ThreadPlacement,
+    rate_per_sec: f64,
+    duration_secs: f64,
+    seed: Option<u64>,
+    spin_threshold_micros: u64,
+    recorder: Option<&LatencyRecorder>,
+    start: Option<Arc<StartLine>>,
+) -> PyResult<BenchTask> {
+    let interval = rate_interval(rate_per_sec)?;
+    let recorder = recorder.map(|r| (r.recording.clone(), r.clock));
+    let duration = run_duration(duration_secs)?;
+    let expected = (rate_per_sec * duration_secs) as usize;
+    let mut spec = BenchSpec::new(benchmark, expected, &placement)
+        .config("rate_per_sec", ConfigValue::Float(rate_per_sec))
+        .config("duration_secs", ConfigValue::Float(duration_secs))
+        .config(
+            "spin_threshold_micros",
+            ConfigValue::Int(spin_threshold_micros as i64),
+        )
+        .recorder(recorder.as_ref().map(|(recording, _)| recording.clone()))
+        .start_line(start);
+    if let Some(seed) = seed {
+        spec = spec.config("seed", ConfigValue::Int(seed as i64));
+    }
+    let spec = mechanism.annotate(spec);
+    let handle = mechanism.handle;
+
+    Ok(spawn_bench_task(placement, spec, move |task| {
+        let mut next_interval: Box<dyn FnMut() -> Duration + Send> = match seed {
+            Some(seed) => Box::new(poisson_intervals(interval, seed)),
+            None => Box::new(move || interval),
+        };
+        run_scheduled(
+            &*handle,
+            Duration::from_micros(spin_threshold_micros),
+            &mut *next_interval,
+            duration,
+            &recorder,
+            || task.is_cancelled(),
+        )
+    }))
+}
+
+/// Spawn a task that runs each rate in `rates` for `step_secs`, recording
+/// every step into its own histogram (and all of them into the result's
+/// overall latency). A cancelled ramp reports the steps it started.
+#[allow(clippy::too_many_arguments)]
+fn spawn_ramp_task(
+    benchmark: &'static str,
+    mechanism: Mechanism,
+    placement: ThreadPlacement,
+    rates: Vec<f64>,
+    step_secs: f64,
+    seed: Option<u64>,
+    spin_threshold_micros: u64,
+    start: Option<Arc<StartLine>>,
+) -> PyResult<BenchTask> {
+    let intervals = rates
+        .iter()
+        .map(|&rate| rate_interval(rate))
+        .collect::<PyResult<Vec<_>>>()?;
+    let step = Duration::from_secs_f64(step_secs);
+    let expected = rates.iter().map(|rate| rate * step_secs).sum::<f64>() as usize;
+    let overall = Recording::shared();
+    let steps = SharedSteps::default();
+    let mut spec = BenchSpec::new(benchmark, expected, &placement)
+        .config("steps", ConfigValue::Int(rates.len() as i64))
+        .config("step_secs", ConfigValue::Float(step_secs))
+        .config(
+            "spin_threshold_micros",
+            ConfigValue::Int(spin_threshold_micros as i64),
+        )
+        .recorder(Some(overall.clone()))
+        .steps(steps.clone())
+        .start_line(start);
+    if let Some(seed) = seed {
+        spec = spec.config("seed", ConfigValue::Int(seed as i64));
+    }
+    let spec = mechanism.annotate(spec);
+    let handle = mechanism.handle;
+
+    Ok(spawn_bench_task(placement, spec, move |task| {
+        let mut total = 0;
+        for (i, (&rate_per_sec, &interval)) in rates.iter().zip(&intervals).enumerate() {
+            if task.is_cancelled() {
+                break;
+            }
+            let mut next_interval: Box<dyn FnMut() -> Duration + Send> = match seed {
+                // Offset the seed so steps don't replay the same gaps
+                Some(seed) => Box::new(poisson_intervals(interval, seed.wrapping_add(i as u64))),
+                None => Box::new(move || interval),
+            };
+            let recording = Recording::shared();
+            let start = monotonic_ns();
+            let sent = run_scheduled(
+                &*handle,
+                Duration::from_micros(spin_threshold_micros),
+                &mut *next_interval,
+                step,
+                &Some((recording.clone(), ClockSource::Monotonic)),
+                || task.is_cancelled(),
+            );
+            let duration_ns = monotonic_ns() - start;
+            let recording = recording.lock().unwrap();
+            let _ = overall.lock().unwrap().hist.add(&recording.hist);
+            steps.lock().unwrap().push(StepStats {
+                rate_per_sec,
+                sent,
+                duration_ns,
+                corrected: recording.corrected,
+                latency: LatencySummary::from_histogram(&recording.hist),
+                phase: None,
+            });
+            total += sent;
+        }
+        total
+    }))
+}
+
+/// One phase of a storm scenario's cycle
+struct StormPhase {
+    name: &'static str,
+    /// Offered load; idle when None
+    rate_per_sec: Option<f64>,
+    secs: f64,
+    /// Collects the phase's samples across all cycles
+    recording: Option<SharedRecording>,
+}
+
+/// Sleep until `deadline_ns` on the monotonic clock, waking every 10ms to
+/// check `cancelled`
+fn idle_until(deadline_ns: u64, cancelled: impl Fn() -> bool) {
+    const SLICE_NS: u64 = 10_000_000;
+    let timer = Timer::new(TimerBackend::Sleep, Duration::ZERO);
+    while !cancelled() {
+        let now = monotonic_ns();
+        if now >= deadline_ns {
+            break;
+        }
+        timer.sleep_until(deadline_ns.min(now + SLICE_NS));
+    }
+}
+
+/// Spawn a task that runs `phases` in turn, `cycles` times. Phase
+/// boundaries are absolute offsets from the start, so a late phase doesn't
+/// shift the cycles after it. Each phase of each cycle is recorded into its
+/// own step, tagged with its phase and cycle, and into the phase's recording
+/// if it has one. A cancelled storm reports the phases it started.
+#[allow(clippy::too_many_arguments)]
+fn spawn_storm_task(
+    benchmark: &'static str,
+    mechanism: Mechanism,
+    placement: ThreadPlacement,
+    phases: [StormPhase; 2],
+    cycles: usize,
+    seed: Option<u64>,
+    spin_threshold_micros: u64,
+    start: Option<Arc<StartLine>>,
+) -> PyResult<BenchTask> {
+    let intervals = phases
+        .iter()
+        .map(|phase| phase.rate_per_sec.map(rate_interval).transpose())
+        .collect::<PyResult<Vec<_>>>()?;
+    let per_cycle = phases
+        .iter()
+        .map(|phase| phase.rate_per_sec.unwrap_or(0.0) * phase.secs)
+        .sum::<f64>();
+    let [storm, quiet] = &phases;
+    let overall = Recording::shared();
+    let steps = SharedSteps::default();
+    let mut spec = BenchSpec::new(benchmark, (per_cycle * cycles as f64) as usize, &placement)
+        .config(
+            "rate_per_sec",
+            ConfigValue::Float(storm.rate_per_sec.unwrap_or(0.0)),
+        )
+        .config("storm_secs", ConfigValue::Float(storm.secs))
+        .config("quiet_secs", ConfigValue::Float(quiet.secs))
+        .config(
+            "quiet_rate_per_sec",
+            quiet
+                .rate_per_sec
+                .map_or(ConfigValue::None, ConfigValue::Float),
+        )
+        .config("cycles", ConfigValue::Int(cycles as i64))
+        .config(
+            "spin_threshold_micros",
+            ConfigValue::Int(spin_threshold_micros as i64),
+        )
+        .recorder(Some(overall.clone()))
+        .steps(steps.clone())
+        .start_line(start);
+    if let Some(seed) = seed {
+        spec = spec.config("seed", ConfigValue::Int(seed as i64));
+    }
+    let spec = mechanism.annotate(spec);
+    let handle = mechanism.handle;
+
+    Ok(spawn_bench_task(placement, spec, move |task| {
+        let mut phase_end = monotonic_ns();
+        let mut total = 0;
+        let schedule = phases.iter().zip(&intervals).cycle();
+        for (i, (phase, interval)) in schedule.take(cycles * phases.len()).enumerate() {
+            if task.is_cancelled() {
+                break;
+            }
+            let phase_start = monotonic_ns();
+            phase_end += (phase.secs * 1e9) as u64;
+            let recording = Recording::shared();
+            if let Some(target) = &phase.recording {
+                if target.lock().unwrap().samples.is_some() {
+                    recording.lock().unwrap().samples = Some(Vec::new());
+                }
+            }
+            let sent = match *interval {
+                Some(interval) => {
+                    let mut next_interval: Box<dyn FnMut() -> Duration + Send> = match seed {
+                        // Offset the seed so phases don't replay the same gaps
+                        Some(seed) => {
+                            Box::new(poisson_intervals(interval, seed.wrapping_add(i as u64)))
+                        }
+                        None => Box::new(move || interval),
+                    };
+                    run_scheduled(
+                        &*handle,
+                        Duration::from_micros(spin_threshold_micros),
+                        &mut *next_interval,
+                        Duration::from_nanos(phase_end.saturating_sub(phase_start)),
+                        &Some((recording.clone(), ClockSource::Monotonic)),
+                        || task.is_cancelled(),
+                    )
+                }
+                None => {
+                    idle_until(phase_end, || task.is_cancelled());
+                    0
+                }
+            };
+            let duration_ns = monotonic_ns() - phase_start;
+            let recording = Arc::into_inner(recording)
+                .expect("the phase's recording is no longer shared")
+                .into_inner()
+                .unwrap();
+            let _ = overall.lock().unwrap().hist.add(&recording.hist);
+            steps.lock().unwrap().push(StepStats {
+                rate_per_sec: phase.rate_per_sec.unwrap_or(0.0),
+                sent,
+                duration_ns,
+                corrected: recording.corrected,
+                latency: LatencySummary::from_histogram(&recording.hist),
+                phase: Some((phase.name, i / phases.len())),
+            });
+            if let Some(target) = &phase.recording {
+                let _ = target.lock().unwrap().absorb(recording);
+            }
+            total += sent;
+        }
+        total
+    }))
+}
+
+/// Spawn a task whose thread and `threads - 1` helpers share `mechanism`,
+/// each sending `count_per_thread` wakes. Accounting and perf counters cover
+/// the task's own thread only; a `recorder` gets every thread's samples,
+/// tagged with its index (the task's own thread is 0).
+fn spawn_multi_task(
+    benchmark: &'static str,
+    mechanism: Mechanism,
+    placement: ThreadPlacement,
+    threads: usize,
+    count_per_thread: usize,
+    recorder: Option<&LatencyRecorder>,
+    start: Option<Arc<StartLine>>,
+) -> BenchTask {
+    let recorder = recorder.map(|r| (r.recording.clone(), r.clock));
+    let spec = BenchSpec::new(benchmark, threads * count_per_thread, &placement)
+        .config("threads", ConfigValue::Int(threads as i64))
+        .config(
+            "count_per_thread",
+            ConfigValue::Int(count_per_thread as i64),
+        )
+        .recorder(recorder.as_ref().map(|(recording, _)| recording.clone()))
+        .start_line_in_body(start.clone());
+    let spec = mechanism.annotate(spec);
+    let handle = mechanism.handle;
+
+    spawn_bench_task(placement, spec, move |task| {
+        // Every thread, helpers included, waits at the start line
+        let produce = |index: usize| {
+            if let Some(start) = &start {
+                task.wait_start(start);
+                if index == 0 {
+                    // Measure from the release, not from the task's start
+                    task.begin_measurement();
+                }
+            }
+            let mut sent = 0;
+            for _ in 0..count_per_thread {
+                if task.is_cancelled() {
+                    break;
+                }
+                timed_thread_wake(&recorder, index, || handle.wake());
+                sent += 1;
+            }
+            handle.finish();
+            sent
+        };
+        // Helpers report faults to this thread's task
+        let current = current_task();
+        std::thread::scope(|scope| {
+            let mut helpers = Vec::with_capacity(threads.saturating_sub(1));
+            for index in 1..threads {
+                let current = current.clone();
+                let helper =
+                    placement
+                        .thread("multi", Some(index))
+                        .spawn_scoped(scope, move || {
+                            placement.apply();
+                            match &current {
+                                Some(state) => run_as_task(state, || produce(index)).unwrap_or(0),
+                                None => produce(index),
+                            }
+                        });
+                match helper {
+                    Ok(helper) => helpers.push(helper),
+                    // Short of a thread the run doesn't measure what was
+                    // asked; cancelling also releases helpers at the start line
+                    Err(error) => {
+                        report_os_error(error);
+                        task.cancel();
+                        break;
+                    }
+                }
+            }
+            let own = produce(0);
+            own + helpers
+                .into_iter()
+                .map(|helper| helper.join().unwrap_or(0))
+                .sum::<u64>()
+        })
+    })
+}
+
+/// Run `scenario` against any waker listed by `mechanisms()` and return the
+/// BenchTask for its producer thread. `result().benchmark` is
+/// `"wakeup_<kind>"` and `result().config` records the mechanism and the
+/// scenario's parameters. With a `barrier`, the producer waits at it before
+/// starting; a multi-producer scenario's threads each count as a party.
+#[pyfunction]
+#[pyo3(signature = (waker, scenario, pin_cpu=None, rt_priority=None, rt_policy="fifo", barrier=None))]
+fn run_benchmark(
+    py: Python<'_>,
+    waker: &Bound<'_, PyAny>,
+    scenario: &Scenario,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+    barrier: Option<&StartBarrier>,
+) -> PyResult<BenchTask> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let mechanism = resolve_mechanism(waker)?;
+    let start = barrier.map(|barrier| barrier.line.clone());
+    match &scenario.kind {
+        ScenarioKind::Burst { length } => Ok(spawn_burst(
+            "wakeup_burst",
+            mechanism,
+            *length,
+            placement,
+            start,
+        )),
+        ScenarioKind::Sequence {
+            coordinator,
+            length,
+            recorder,
+            spin_threshold_micros,
+            warmup_iterations,
+            pacing,
+        } => {
+            let recorder = recorder.as_ref().map(|recorder| recorder.borrow(py));
+            Ok(spawn_sequence(
+                "wakeup_sequence",
+                mechanism,
+                &coordinator.borrow(py),
+                *length,
+                recorder.as_deref(),
+                placement,
+                *spin_threshold_micros,
+                *warmup_iterations,
+                *pacing,
+                start,
+            ))
+        }
+        ScenarioKind::Rate {
+            rate_per_sec,
+            duration_secs,
+            seed,
+            spin_threshold_micros,
+            recorder,
+        } => spawn_rate_task(
+            if seed.is_some() {
+                "wakeup_poisson"
+            } else {
+                "wakeup_rate"
+            },
+            mechanism,
+            placement,
+            *rate_per_sec,
+            *duration_secs,
+            *seed,
+            *spin_threshold_micros,
+            recorder
+                .as_ref()
+                .map(|recorder| recorder.borrow(py))
+                .as_deref(),
+            start,
+        ),
+        ScenarioKind::Multi {
+            threads,
+            count_per_thread,
+            recorder,
+        } => Ok(spawn_multi_task(
+            "wakeup_multi",
+            mechanism,
+            placement,
+            *threads,
+            *count_per_thread,
+            recorder
+                .as_ref()
+                .map(|recorder| recorder.borrow(py))
+                .as_deref(),
+            start,
+        )),
+        ScenarioKind::Ramp {
+            rates,
+            step_secs,
+            seed,
+            spin_threshold_micros,
+        } => spawn_ramp_task(
+            "wakeup_ramp",
+            mechanism,
+            placement,
+            rates.clone(),
+            *step_secs,
+            *seed,
+            *spin_threshold_micros,
+            start,
+        ),
+        ScenarioKind::Storm {
+            rate_per_sec,
+            storm_secs,
+            quiet_secs,
+            quiet_rate_per_sec,
+            cycles,
+            seed,
+            spin_threshold_micros,
+            storm_recorder,
+            quiet_recorder,
+        } => {
+            let recording = |recorder: &Option<Py<LatencyRecorder>>| {
+                recorder
+                    .as_ref()
+                    .map(|recorder| recorder.borrow(py).recording.clone())
+            };
+            spawn_storm_task(
+                "wakeup_storm",
+                mechanism,
+                placement,
+                [
+                    StormPhase {
+                        name: "storm",
+                        rate_per_sec: Some(*rate_per_sec),
+                        secs: *storm_secs,
+                        recording: recording(storm_recorder),
+                    },
+                    StormPhase {
+                        name: "quiet",
+                        rate_per_sec: *quiet_rate_per_sec,
+                        secs: *quiet_secs,
+                        recording: recording(quiet_recorder),
+                    },
+                ],
+                *cycles,
+                *seed,
+                *spin_threshold_micros,
+                start,
+            )
+        }
+    }
+}
+
+// =============================================================================
+// Round-trip (ping-pong) benchmark
+// =============================================================================
+
+/// Spawn a thread that performs `iterations` ping-pong rounds: wake Python, wait
+/// for it to call `coordinator.ack()`, record the full round-trip time, repeat.
+/// Stops early if an acknowledgment doesn't arrive within `ack_timeout`.
+fn spawn_roundtrip<F>(
+    placement: ThreadPlacement,
+    wake: F,
+    coordinator: &BenchCoordinator,
+    recorder: &LatencyRecorder,
+    iterations: usize,
+    ack_timeout: Duration,
+) -> std::io::Result<()>
+where
+    F: Fn() + Send + 'static,
+{
+    let counter = coordinator.counter.clone();
+    let acks = coordinator.acks.clone();
+    let clock = recorder.clock;
+    let recorder = Some(recorder.recording.clone());
+
+    spawn_producer(placement, "roundtrip", move |task| {
+        let mut expected = acks.load(Ordering::SeqCst);
+        for _ in 0..iterations {
+            if task.is_cancelled() {
+                return;
+            }
+            expected += 1;
+            let sequence = counter.increment();
+            counter.record_send(sequence);
+            let start = Instant::now();
+            let start_ns = clock.read_ns();
+            wake();
+            while acks.load(Ordering::SeqCst) < expected {
+                if start.elapsed() > ack_timeout || task.is_cancelled() {
+                    return;
+                }
+                std::thread::yield_now();
+            }
+            record_latency(&recorder, clock.read_ns().saturating_sub(start_ns));
+        }
+    })
+}
+
+/// Round-trip benchmark over the pipe: each RTT is recorded into `recorder`.
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (waker, coordinator, iterations, recorder, ack_timeout_ms=1000, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+#[allow(clippy::too_many_arguments)]
+fn fd_wakeup_roundtrip(
+    waker: &FdWaker,
+    coordinator: &BenchCoordinator,
+    iterations: usize,
+    recorder: &LatencyRecorder,
+    ack_timeout_ms: u64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<()> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let handle = waker.handle()?;
+    spawn_roundtrip(
+        placement,
+        move || handle.wake(),
+        coordinator,
+        recorder,
+        iterations,
+        Duration::from_millis(ack_timeout_ms),
+    )?;
+    Ok(())
+}
+
+/// Round-trip benchmark over the eventfd: each RTT is recorded into `recorder`.
+#[cfg(target_os = "linux")]
+#[pyfunction]
+#[pyo3(signature = (waker, coordinator, iterations, recorder, ack_timeout_ms=1000, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+#[allow(clippy::too_many_arguments)]
+fn eventfd_wakeup_roundtrip(
+    waker: &EventFdWaker,
+    coordinator: &BenchCoordinator,
+    iterations: usize,
+    recorder: &LatencyRecorder,
+    ack_timeout_ms: u64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<()> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let handle = waker.handle();
+    spawn_roundtrip(
+        placement,
+        move || handle.wake(),
+        coordinator,
+        recorder,
+        iterations,
+        Duration::from_millis(ack_timeout_ms),
+    )?;
+    Ok(())
+}
+
+/// Round-trip benchmark over the kqueue: each RTT is recorded into `recorder`.
+#[cfg(target_os = "macos")]
+#[pyfunction]
+#[pyo3(signature = (waker, coordinator, iterations, recorder, ack_timeout_ms=1000, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+#[allow(clippy::too_many_arguments)]
+fn kqueue_wakeup_roundtrip(
+    waker: &KqueueUserWaker,
+    coordinator: &BenchCoordinator,
+    iterations: usize,
+    recorder: &LatencyRecorder,
+    ack_timeout_ms: u64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<()> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let handle = waker.handle();
+    spawn_roundtrip(
+        placement,
+        move || handle.wake(),
+        coordinator,
+        recorder,
+        iterations,
+        Duration::from_millis(ack_timeout_ms),
+    )?;
+    Ok(())
+}
+
+/// Round-trip benchmark over the socket pair: each RTT is recorded into `recorder`.
+#[pyfunction]
+#[pyo3(signature = (waker, coordinator, iterations, recorder, ack_timeout_ms=1000, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+#[allow(clippy::too_many_arguments)]
+fn socket_wakeup_roundtrip(
+    waker: &SocketWaker,
+    coordinator: &BenchCoordinator,
+    iterations: usize,
+    recorder: &LatencyRecorder,
+    ack_timeout_ms: u64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<()> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let handle = waker.handle();
+    spawn_roundtrip(
+        placement,
+        move || handle.wake(),
+        coordinator,
+        recorder,
+        iterations,
+        Duration::from_millis(ack_timeout_ms),
+    )?;
+    Ok(())
+}
+
+/// Round-trip benchmark over call_soon_threadsafe: each RTT is recorded into `recorder`.
+#[pyfunction]
+#[pyo3(signature = (waker, coordinator, iterations, recorder, ack_timeout_ms=1000, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+#[allow(clippy::too_many_arguments)]
+fn callback_wakeup_roundtrip(
+    py: Python<'_>,
+    waker: &CallbackWaker,
+    coordinator: &BenchCoordinator,
+    iterations: usize,
+    recorder: &LatencyRecorder,
+    ack_timeout_ms: u64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<()> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let handle = waker.handle(py)?;
+    spawn_roundtrip(
+        placement,
+        move || handle.wake(),
+        coordinator,
+        recorder,
+        iterations,
+        Duration::from_millis(ack_timeout_ms),
+    )?;
+    Ok(())
+}
+
+// =============================================================================
+// Comparison orchestrator: every approach under identical load
 // =============================================================================
 
-/// A waker that uses a raw file descriptor to wake up the Python event loop.
-/// This avoids acquiring the GIL on the Rust side.
-#[pyclass]
-struct FdWaker {
-    write_fd: RawFd,
-    read_fd: RawFd,
-    // Store owned FDs to ensure they're closed on drop
-    #[allow(dead_code)]
-    owned_write: Option<OwnedFd>,
-    #[allow(dead_code)]
-    owned_read: Option<OwnedFd>,
+/// A wake mechanism `run_comparison` knows how to drive
+#[derive(Clone, Copy)]
+enum ComparedApproach {
+    #[cfg(unix)]
+    Fd,
+    #[cfg(target_os = "linux")]
+    EventFd,
+    #[cfg(target_os = "macos")]
+    Kqueue,
+    #[cfg(target_os = "linux")]
+    Futex,
+    Socket,
+    Tcp,
+    Udp,
+    #[cfg(unix)]
+    Dgram,
+    Callback,
+    #[cfg(unix)]
+    Signal,
+    ThreadingEvent,
+    Future,
+    Coroutine,
+}
+
+impl ComparedApproach {
+    const NAMES: [&'static str; 13] = [
+        "fd",
+        "eventfd",
+        "kqueue",
+        "futex",
+        "socket",
+        "tcp",
+        "udp",
+        "dgram",
+        "callback",
+        "signal",
+        "threading_event",
+        "future",
+        "coroutine",
+    ];
+
+    /// Approaches supported on this platform, in reporting order
+    fn available() -> Vec<Self> {
+        vec![
+            #[cfg(unix)]
+            Self::Fd,
+            #[cfg(target_os = "linux")]
+            Self::EventFd,
+            #[cfg(target_os = "macos")]
+            Self::Kqueue,
+            #[cfg(target_os = "linux")]
+            Self::Futex,
+            Self::Socket,
+            Self::Tcp,
+            Self::Udp,
+            #[cfg(unix)]
+            Self::Dgram,
+            Self::Callback,
+            #[cfg(unix)]
+            Self::Signal,
+            Self::ThreadingEvent,
+            Self::Future,
+            Self::Coroutine,
+        ]
+    }
+
+    /// The SocketWaker transport behind a socket approach
+    fn socket_transport(self) -> Option<&'static str> {
+        match self {
+            Self::Socket => Some("pair"),
+            Self::Tcp => Some("tcp"),
+            Self::Udp => Some("udp"),
+            #[cfg(unix)]
+            Self::Dgram => Some("dgram"),
+            _ => None,
+        }
+    }
+
+    /// Whether the reactor consumer can drive this approach: its waker is
+    /// a readable FD that a plain read drains
+    fn consumes_fd(self) -> bool {
+        match self {
+            #[cfg(unix)]
+            Self::Fd => true,
+            #[cfg(target_os = "linux")]
+            Self::EventFd => true,
+            _ => self.socket_transport().is_some(),
+        }
+    }
+
+    /// Create a waker for the reactor consumer
+    #[cfg(unix)]
+    fn reactor_target(self) -> PyResult<ReactorTarget> {
+        match self {
+            Self::Fd => {
+                let waker = create_fd_waker()?;
+                let handle = waker.handle()?;
+                Ok(ReactorTarget {
+                    fd: waker.get_read_fd()?,
+                    wake: Arc::new(move || handle.wake()),
+                    _waker: Box::new(waker),
+                })
+            }
+            #[cfg(target_os = "linux")]
+            Self::EventFd => {
+                let waker = create_eventfd_waker(false)?;
+                let handle = waker.handle();
+                Ok(ReactorTarget {
+                    fd: waker.get_read_fd(),
+                    wake: Arc::new(move || handle.wake()),
+                    _waker: Box::new(waker),
+                })
+            }
+            _ => match self.socket_transport() {
+                Some(transport) => {
+                    let waker = create_socket_waker(transport)?;
+                    let handle = waker.handle();
+                    Ok(ReactorTarget {
+                        fd: waker.get_read_fd(),
+                        wake: Arc::new(move || handle.wake()),
+                        _waker: Box::new(waker),
+                    })
+                }
+                None => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "the {} approach needs an event loop; consumer='reactor' runs \
+                     fd, eventfd and the socket approaches",
+                    self.name()
+                ))),
+            },
+        }
+    }
+
+    /// Whether this approach can run on the calling thread: signal handlers
+    /// can only be installed from the main thread
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    fn runs_here(self, py: Python<'_>) -> PyResult<bool> {
+        match self {
+            #[cfg(unix)]
+            Self::Signal => {
+                let threading = py.import("threading")?;
+                Ok(threading
+                    .call_method0("current_thread")?
+                    .is(&threading.call_method0("main_thread")?))
+            }
+            _ => Ok(true),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            #[cfg(unix)]
+            Self::Fd => "fd",
+            #[cfg(target_os = "linux")]
+            Self::EventFd => "eventfd",
+            #[cfg(target_os = "macos")]
+            Self::Kqueue => "kqueue",
+            #[cfg(target_os = "linux")]
+            Self::Futex => "futex",
+            Self::Socket => "socket",
+            Self::Tcp => "tcp",
+            Self::Udp => "udp",
+            #[cfg(unix)]
+            Self::Dgram => "dgram",
+            Self::Callback => "callback",
+            #[cfg(unix)]
+            Self::Signal => "signal",
+            Self::ThreadingEvent => "threading_event",
+            Self::Future => "future",
+            Self::Coroutine => "coroutine",
+        }
+    }
+
+    fn parse(name: &str) -> PyResult<Self> {
+        if let Some(approach) = Self::available().into_iter().find(|a| a.name() == name) {
+            return Ok(approach);
+        }
+        if Self::NAMES.contains(&name) {
+            return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                format!("the {name} approach is not available on this platform"),
+            ));
+        }
+        let names = Self::NAMES.map(|name| format!("'{name}'")).join(", ");
+        Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "approach must be one of {names}, got {name:?}"
+        )))
+    }
+
+    /// Create a waker on `event_loop` whose wakes complete the pending future
+    fn setup(
+        self,
+        event_loop: &Bound<'_, PyAny>,
+        pending: &PendingFuture,
+    ) -> PyResult<ComparisonHarness> {
+        let py = event_loop.py();
+        let (waker, wake): (Bound<'_, PyAny>, WakeFn) = match self {
+            #[cfg(unix)]
+            Self::Fd => {
+                let waker = create_fd_waker()?;
+                let handle = waker.handle()?;
+                (
+                    Bound::new(py, waker)?.into_any(),
+                    Arc::new(move || handle.wake()),
+                )
+            }
+            #[cfg(target_os = "linux")]
+            Self::EventFd => {
+                let waker = create_eventfd_waker(false)?;
+                let handle = waker.handle();
+                (
+                    Bound::new(py, waker)?.into_any(),
+                    Arc::new(move || handle.wake()),
+                )
+            }
+            #[cfg(target_os = "macos")]
+            Self::Kqueue => {
+                let waker = create_kqueue_waker()?;
+                let handle = waker.handle();
+                (
+                    Bound::new(py, waker)?.into_any(),
+                    Arc::new(move || handle.wake()),
+                )
+            }
+            #[cfg(target_os = "linux")]
+            Self::Futex => {
+                let waker = FutexWaker::new()?;
+                let handle = waker.handle();
+                (
+                    Bound::new(py, waker)?.into_any(),
+                    Arc::new(move || handle.wake()),
+                )
+            }
+            #[cfg(unix)]
+            Self::Dgram => {
+                let waker = create_socket_waker("dgram")?;
+                let handle = waker.handle();
+                (
+                    Bound::new(py, waker)?.into_any(),
+                    Arc::new(move || handle.wake()),
+                )
+            }
+            Self::Socket | Self::Tcp | Self::Udp => {
+                let transport = self.socket_transport().unwrap_or("pair");
+                let waker = create_socket_waker(transport)?;
+                let handle = waker.handle();
+                (
+                    Bound::new(py, waker)?.into_any(),
+                    Arc::new(move || handle.wake()),
+                )
+            }
+            Self::Callback => {
+                let callback = complete_pending(py, pending, None)?;
+                let waker = create_callback_waker(
+                    py,
+                    callback.into_any().unbind(),
+                    event_loop.clone().unbind(),
+                )?;
+                let handle = waker.handle(py)?;
+                return Ok(ComparisonHarness {
+                    wake: Arc::new(move || handle.wake()),
+                    undo: None,
+                    arm: None,
+                });
+            }
+            #[cfg(unix)]
+            Self::Signal => {
+                let waker = create_signal_waker(libc::SIGUSR1)?;
+                let on_signal = complete_pending(py, pending, None)?;
+                event_loop.call_method1("add_signal_handler", (waker.signum, on_signal))?;
+                let handle = waker.handle(py)?;
+                return Ok(ComparisonHarness {
+                    wake: Arc::new(move || handle.wake()),
+                    undo: Some((
+                        "remove_signal_handler",
+                        waker.signum.into_pyobject(py)?.into_any().unbind(),
+                    )),
+                    arm: None,
+                });
+            }
+            Self::Coroutine => {
+                let callback = complete_pending(py, pending, None)?;
+                let waker = CoroutineWaker::new(
+                    py,
+                    callback.into_any().unbind(),
+                    event_loop.clone().unbind(),
+                )?;
+                let handle = waker.handle(py);
+                return Ok(ComparisonHarness {
+                    wake: Arc::new(move || handle.wake()),
+                    undo: None,
+                    arm: None,
+                });
+            }
+            Self::ThreadingEvent => {
+                let waker = Py::new(py, ThreadingEventWaker::new(py, None)?)?;
+                let handle = waker.borrow(py).handle(py);
+                let on_done = complete_pending(py, pending, None)?.unbind();
+                let event_loop = event_loop.clone().unbind();
+                let arm = pyo3::types::PyCFunction::new_closure(
+                    py,
+                    None,
+                    None,
+                    move |args, _kwargs| -> PyResult<()> {
+                        let py = args.py();
+                        let future = waker.borrow(py).submit(event_loop.bind(py))?;
+                        future.call_method1("add_done_callback", (&on_done,))?;
+                        Ok(())
+                    },
+                )?;
+                return Ok(ComparisonHarness {
+                    wake: Arc::new(move || handle.wake()),
+                    undo: None,
+                    arm: Some(arm.into_any().unbind()),
+                });
+            }
+            Self::Future => {
+                let completer = FutureCompleter::new(py)?;
+                let (event_loop, pending) = (event_loop.clone().unbind(), pending.clone());
+                return Ok(ComparisonHarness {
+                    wake: Arc::new(move || {
+                        Python::with_gil(|py| {
+                            // Release the lock before calling into Python, which
+                            // may hand the GIL to the loop thread waiting on it
+                            let future = pending.lock().unwrap().take();
+                            if let Some(future) = future {
+                                let _ = completer.complete(py, &event_loop, future, py.None());
+                            }
+                        })
+                    }),
+                    undo: None,
+                    arm: None,
+                });
+            }
+        };
+
+        let fd = waker.call_method0("get_read_fd")?;
+        let on_readable = complete_pending(py, pending, Some(waker.getattr("drain")?.unbind()))?;
+        event_loop.call_method1("add_reader", (&fd, on_readable))?;
+        Ok(ComparisonHarness {
+            wake,
+            undo: Some(("remove_reader", fd.unbind())),
+            arm: None,
+        })
+    }
+}
+
+/// Future the event loop is currently waiting on, completed by the next wake
+type PendingFuture = Arc<Mutex<Option<PyObject>>>;
+
+/// Sends one wake from a producer thread
+type WakeFn = Arc<dyn Fn() + Send + Sync>;
+
+/// A waker set up on the comparison's event loop
+struct ComparisonHarness {
+    wake: WakeFn,
+    /// Loop method and argument that remove the waker's registration
+    /// (its `add_reader` FD or `add_signal_handler` signal)
+    undo: Option<(&'static str, PyObject)>,
+    /// Called once the pending future is set, before each wake, for
+    /// approaches that must re-submit a waiter per wake
+    arm: Option<PyObject>,
+}
+
+/// Python callable that drains the waker (if given) and completes the pending future
+fn complete_pending<'py>(
+    py: Python<'py>,
+    pending: &PendingFuture,
+    drain: Option<PyObject>,
+) -> PyResult<Bound<'py, pyo3::types::PyCFunction>> {
+    let pending = pending.clone();
+    pyo3::types::PyCFunction::new_closure(py, None, None, move |args, _kwargs| -> PyResult<()> {
+        let py = args.py();
+        if let Some(drain) = &drain {
+            drain.call0(py)?;
+        }
+        if let Some(future) = pending.lock().unwrap().take() {
+            future.call_method1(py, "set_result", (py.None(),))?;
+        }
+        Ok(())
+    })
+}
+
+/// Time `warmup + iterations` wakes of one approach: for each, a producer
+/// thread waits a delay drawn from `delay` and wakes the loop, and the time from spawning it to
+/// the loop returning from the awaited future is recorded
+fn drive_approach(
+    event_loop: &Bound<'_, PyAny>,
+    approach: ComparedApproach,
+    load: &ComparisonLoad,
+    recorder: &LatencyRecorder,
+) -> PyResult<()> {
+    let pending = PendingFuture::default();
+    let harness = approach.setup(event_loop, &pending)?;
+
+    let result = (0..load.warmup + load.iterations).try_for_each(|i| {
+        let future = event_loop.call_method0("create_future")?;
+        *pending.lock().unwrap() = Some(future.clone().unbind());
+        if let Some(arm) = &harness.arm {
+            arm.call0(event_loop.py())?;
+        }
+
+        let (wake, timer) = (harness.wake.clone(), load.timer);
+        let delay = load.delay.draw().duration;
+        let start = monotonic_ns();
+        spawn_producer(load.placement, "compare", move |_| {
+            if !delay.is_zero() {
+                Timer::new(timer, Duration::ZERO).sleep(delay);
+            }
+            wake();
+        })?;
+        event_loop.call_method1("run_until_complete", (future,))?;
+
+        if i >= load.warmup {
+            let _ = recorder
+                .recording
+                .lock()
+                .unwrap()
+                .record(monotonic_ns() - start);
+        }
+        Ok(())
+    });
+
+    if let Some((method, arg)) = &harness.undo {
+        event_loop.call_method1(*method, (arg,))?;
+    }
+    result
+}
+
+/// Who receives the wakes in a comparison
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ComparisonConsumer {
+    /// An asyncio event loop, as in an application
+    Loop,
+    /// The calling thread, polling the waker's read FD with the GIL released
+    #[cfg(unix)]
+    Reactor,
+}
+
+impl ComparisonConsumer {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "loop" => Ok(Self::Loop),
+            #[cfg(unix)]
+            "reactor" => Ok(Self::Reactor),
+            #[cfg(not(unix))]
+            "reactor" => Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                "the reactor consumer is only available on Unix",
+            )),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "consumer must be 'loop' or 'reactor', got {other:?}"
+            ))),
+        }
+    }
+}
+
+/// How long the reactor waits for one wake before giving up
+#[cfg(unix)]
+const REACTOR_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A waker the reactor consumes: the read FD it polls and the wake that makes
+/// it readable. Holds the waker, which owns the FD.
+#[cfg(unix)]
+struct ReactorTarget {
+    fd: RawFd,
+    wake: WakeFn,
+    _waker: Box<dyn std::any::Any + Send>,
+}
+
+/// Wait until `fd` is readable; false on timeout
+#[cfg(unix)]
+fn poll_readable(fd: RawFd, timeout: Duration) -> std::io::Result<bool> {
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout_ms = timeout.as_millis().clamp(1, libc::c_int::MAX as u128) as libc::c_int;
+    loop {
+        match unsafe { libc::poll(&mut pollfd, 1, timeout_ms) } {
+            -1 => {
+                let e = std::io::Error::last_os_error();
+                if e.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(e);
+                }
+            }
+            ready => return Ok(ready > 0),
+        }
+    }
+}
+
+/// As `drive_approach`, with the wakes received by this thread polling the
+/// waker's read FD: no Python code runs between a wake and its receipt, so
+/// the difference from the event loop's figures is the loop's own cost
+#[cfg(unix)]
+fn drive_reactor(
+    py: Python<'_>,
+    target: ReactorTarget,
+    load: &ComparisonLoad,
+    recorder: &LatencyRecorder,
+) -> PyResult<()> {
+    let recording = recorder.recording.clone();
+    py.allow_threads(|| {
+        let mut buf = [0u8; 4096];
+        for i in 0..load.warmup + load.iterations {
+            let (wake, timer) = (target.wake.clone(), load.timer);
+            let delay = load.delay.draw().duration;
+            let start = monotonic_ns();
+            spawn_producer(load.placement, "compare", move |_| {
+                if !delay.is_zero() {
+                    Timer::new(timer, Duration::ZERO).sleep(delay);
+                }
+                wake();
+            })?;
+            if !poll_readable(target.fd, REACTOR_TIMEOUT)? {
+                return Err(PyErr::new::<pyo3::exceptions::PyTimeoutError, _>(
+                    "no wake arrived within a second",
+                ));
+            }
+            let end = monotonic_ns();
+            // One wake is in flight at a time, so one read drains it
+            unsafe { libc::read(target.fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+
+            if i >= load.warmup {
+                let _ = recording.lock().unwrap().record(end - start);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Load applied identically to every approach in a comparison
+struct ComparisonLoad {
+    iterations: usize,
+    warmup: usize,
+    delay: DelaySource,
+    timer: TimerBackend,
+    placement: ThreadPlacement,
+}
+
+/// Short name for an event loop's implementation: `"uvloop"`, `"selector"`,
+/// `"proactor"`, or `module.QualName` of its class for anything else
+fn loop_implementation(event_loop: &Bound<'_, PyAny>) -> PyResult<String> {
+    let py = event_loop.py();
+    let class = event_loop.get_type();
+    let module: String = class.getattr("__module__")?.extract()?;
+    if module == "uvloop" || module.starts_with("uvloop.") {
+        return Ok("uvloop".to_string());
+    }
+    let asyncio = py.import("asyncio")?;
+    if event_loop.is_instance(&asyncio.getattr("SelectorEventLoop")?)? {
+        return Ok("selector".to_string());
+    }
+    if let Ok(proactor) = asyncio.getattr("ProactorEventLoop") {
+        if event_loop.is_instance(&proactor)? {
+            return Ok("proactor".to_string());
+        }
+    }
+    Ok(format!("{module}.{}", class.qualname()?))
+}
+
+/// Which event loop implementation `event_loop` (by default the running loop)
+/// is: `"uvloop"`, `"selector"`, `"proactor"`, or the class's qualified name
+#[pyfunction]
+#[pyo3(signature = (event_loop=None))]
+fn event_loop_implementation(
+    py: Python<'_>,
+    event_loop: Option<Bound<'_, PyAny>>,
+) -> PyResult<String> {
+    let event_loop = match event_loop {
+        Some(event_loop) => event_loop,
+        None => py.import("asyncio")?.call_method0("get_running_loop")?,
+    };
+    loop_implementation(&event_loop)
+}
+
+/// Create the loop a comparison runs on. `loop_factory` is a callable, or one
+/// of `"selector"` (the default), `"proactor"` (Windows) or `"uvloop"`.
+fn new_comparison_loop<'py>(
+    py: Python<'py>,
+    loop_factory: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let asyncio = py.import("asyncio")?;
+    let name = match loop_factory {
+        None => "selector".to_string(),
+        Some(factory) => match factory.downcast::<pyo3::types::PyString>() {
+            Ok(name) => name.to_str()?.to_string(),
+            Err(_) => return factory.call0(),
+        },
+    };
+    match name.as_str() {
+        "selector" => asyncio.getattr("SelectorEventLoop")?.call0(),
+        "proactor" => asyncio
+            .getattr("ProactorEventLoop")
+            .map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+                    "ProactorEventLoop is only available on Windows",
+                )
+            })?
+            .call0(),
+        "uvloop" => py.import("uvloop")?.call_method0("new_event_loop"),
+        other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "loop_factory must be a callable or one of 'selector', 'proactor', 'uvloop', got {other:?}"
+        ))),
+    }
+}
+
+/// Latencies of each approach under the same load, from `run_comparison`.
+/// The first approach is the baseline for relative figures.
+#[pyclass(frozen)]
+struct Comparison {
+    #[pyo3(get)]
+    iterations: usize,
+    delay: DelaySource,
+    /// Event loop implementation the comparison ran on, as from
+    /// `event_loop_implementation()`
+    #[pyo3(get)]
+    event_loop: String,
+    results: Vec<(&'static str, Py<LatencyRecorder>)>,
+    /// The FFI floor from `measure_ffi_baselines`, measured before the
+    /// approaches ran; empty when `baseline_iterations` was 0
+    ffi_baselines: Vec<(&'static str, LatencySummary)>,
 }
 
-#[pymethods]
-impl FdWaker {
-    #[new]
-    fn new() -> PyResult<Self> {
-        let mut fds = [0 as RawFd; 2];
-        let result = unsafe { libc::pipe(fds.as_mut_ptr()) };
-        if result != 0 {
-            return Err(PyErr::new::<pyo3::exceptions::PyOSError, _>(
-                "Failed to create pipe",
-            ));
-        }
+impl Comparison {
+    fn summaries(&self, py: Python<'_>) -> Vec<(&'static str, LatencySummary)> {
+        self.results
+            .iter()
+            .map(|(name, recorder)| {
+                let recording = recorder.borrow(py).recording.clone();
+                let summary = LatencySummary::from_histogram(&recording.lock().unwrap().hist);
+                (*name, summary)
+            })
+            .collect()
+    }
+}
 
-        // Set non-blocking on read end
-        unsafe {
-            let flags = libc::fcntl(fds[0], libc::F_GETFL);
-            libc::fcntl(fds[0], libc::F_SETFL, flags | libc::O_NONBLOCK);
-        }
+#[pymethods]
+impl Comparison {
+    /// Approach names in the order they ran
+    fn approaches(&self) -> Vec<&'static str> {
+        self.results.iter().map(|(name, _)| *name).collect()
+    }
 
-        let owned_read = unsafe { OwnedFd::from_raw_fd(fds[0]) };
-        let owned_write = unsafe { OwnedFd::from_raw_fd(fds[1]) };
+    #[getter]
+    fn baseline(&self) -> &'static str {
+        self.results[0].0
+    }
 
-        Ok(Self {
-            read_fd: fds[0],
-            write_fd: fds[1],
-            owned_read: Some(owned_read),
-            owned_write: Some(owned_write),
-        })
+    /// The `delay_micros` the comparison ran with: an int or a `Delay`
+    #[getter]
+    fn delay_micros(&self, py: Python<'_>) -> PyObject {
+        match &self.delay {
+            DelaySource::Fixed(micros) => micros.into_pyobject(py).unwrap().into_any().unbind(),
+            DelaySource::Drawn(delay) => delay.clone_ref(py).into_any(),
+        }
     }
 
-    /// Get the read file descriptor for registering with the event loop
-    fn get_read_fd(&self) -> RawFd {
-        self.read_fd
+    /// The recorder holding one approach's latencies
+    fn recorder(&self, py: Python<'_>, approach: &str) -> PyResult<Py<LatencyRecorder>> {
+        self.results
+            .iter()
+            .find(|(name, _)| *name == approach)
+            .map(|(_, recorder)| recorder.clone_ref(py))
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>(approach.to_string()))
     }
 
-    /// Drain any pending bytes from the pipe (call this in the callback)
-    fn drain(&self) -> PyResult<()> {
-        let mut buf = [0u8; 64];
-        loop {
-            let result = unsafe {
-                libc::read(self.read_fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len())
-            };
-            if result <= 0 {
-                break;
-            }
+    /// `{name: LatencySummary}` for the no-op baselines, in ns
+    #[getter]
+    fn ffi_baselines<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let baselines = pyo3::types::PyDict::new(py);
+        for (name, summary) in &self.ffi_baselines {
+            baselines.set_item(name, *summary)?;
         }
-        Ok(())
+        Ok(baselines)
     }
-}
 
-/// Holder for the write FD that can be sent across threads
-struct FdWakerHandle {
-    write_fd: RawFd,
-}
+    /// All results as a dict, in the same shape as `to_json()`
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let summaries = self.summaries(py);
+        let baseline = summaries[0].1;
+        let results = pyo3::types::PyList::empty(py);
+        for (name, summary) in &summaries {
+            let result = pyo3::types::PyDict::new(py);
+            result.set_item("approach", name)?;
+            result.set_item("latency_ns", summary.to_dict(py)?)?;
+            result.set_item(
+                "relative_p50",
+                summary.p50 as f64 / baseline.p50.max(1) as f64,
+            )?;
+            result.set_item("relative_mean", summary.mean / baseline.mean.max(1.0))?;
+            results.append(result)?;
+        }
+        let ffi_baselines = pyo3::types::PyDict::new(py);
+        for (name, summary) in &self.ffi_baselines {
+            ffi_baselines.set_item(name, summary.to_dict(py)?)?;
+        }
 
-unsafe impl Send for FdWakerHandle {}
-unsafe impl Sync for FdWakerHandle {}
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("schema_version", RESULT_SCHEMA_VERSION)?;
+        dict.set_item("iterations", self.iterations)?;
+        dict.set_item("delay_micros", self.delay.describe(py)?)?;
+        dict.set_item("event_loop", &self.event_loop)?;
+        dict.set_item("baseline", self.baseline())?;
+        dict.set_item("results", results)?;
+        dict.set_item("ffi_baselines_ns", ffi_baselines)?;
+        Ok(dict)
+    }
 
-impl FdWakerHandle {
-    fn wake(&self) {
-        let buf = [1u8; 1];
-        unsafe {
-            libc::write(self.write_fd, buf.as_ptr() as *const libc::c_void, 1);
+    /// Serialize with sorted keys; `results` keeps the run order
+    #[pyo3(signature = (indent=None))]
+    fn to_json(&self, py: Python<'_>, indent: Option<usize>) -> PyResult<String> {
+        dump_json(self.to_dict(py)?.as_any(), indent)
+    }
+
+    /// Summary table in µs, relative to the baseline's p50
+    fn __str__(&self, py: Python<'_>) -> String {
+        let summaries = self.summaries(py);
+        let baseline = summaries[0].1.p50.max(1) as f64;
+        let mut table = format!(
+            "{:<16} {:>10} {:>10} {:>10} {:>9}\n",
+            "Approach", "P50", "P99", "Mean", "Relative"
+        );
+        for (name, summary) in &summaries {
+            table.push_str(&format!(
+                "{:<16} {:>7.1} µs {:>7.1} µs {:>7.1} µs {:>8.1}x\n",
+                name,
+                summary.p50 as f64 / 1000.0,
+                summary.p99 as f64 / 1000.0,
+                summary.mean / 1000.0,
+                summary.p50 as f64 / baseline
+            ));
+        }
+        if !self.ffi_baselines.is_empty() {
+            table.push_str(&format!(
+                "\n{:<16} {:>10} {:>10}\n",
+                "FFI baseline", "P50", "P99"
+            ));
+            for (name, summary) in &self.ffi_baselines {
+                table.push_str(&format!(
+                    "{:<16} {:>7} ns {:>7} ns\n",
+                    name, summary.p50, summary.p99
+                ));
+            }
         }
+        table
     }
-}
 
-/// Create an FD-based waker
-#[pyfunction]
-fn create_fd_waker() -> PyResult<FdWaker> {
-    FdWaker::new()
+    fn __repr__(&self) -> String {
+        format!(
+            "Comparison(iterations={}, delay_micros={}, event_loop={:?}, approaches={:?})",
+            self.iterations,
+            self.delay,
+            self.event_loop,
+            self.approaches()
+        )
+    }
 }
 
-/// Spawn a Rust OS thread that will wake up Python via the FD after an optional delay.
-/// This does NOT acquire the GIL.
+/// Run the standard wakeup benchmark for each approach on a fresh event loop
+/// and return a Comparison.
+///
+/// Every approach gets the same load: `warmup` untimed wakes, then
+/// `iterations` timed ones, each sent by a new producer thread after
+/// `delay_micros` (default 0), which may be a `Delay` to draw a fresh delay
+/// for every wake. `approaches` defaults to all available ones (`"fd"`,
+/// `"eventfd"`, `"kqueue"`, `"futex"`, `"socket"`, `"tcp"`, `"udp"`, `"dgram"`, `"callback"`, `"signal"` when
+/// called from the main thread, `"threading_event"`, `"future"`, and `"coroutine"`); the first is the baseline. `loop_factory` is a callable returning a new loop, or one of
+/// `"selector"` (the default), `"proactor"` or `"uvloop"`; the loop is closed
+/// afterwards. Must not be called while an event loop is running in this thread.
+/// Before the approaches run, the FFI floor is measured as by
+/// `measure_ffi_baselines(baseline_iterations)`; 0 skips it.
+///
+/// `consumer="reactor"` (Unix) receives the wakes on the calling thread
+/// instead, polling each waker's FD with the GIL released and no event loop,
+/// so only the mechanism's own cost is measured. It runs the FD approaches
+/// (`"fd"`, `"eventfd"` and the socket ones), and the result's `event_loop`
+/// is `"reactor"`.
 #[pyfunction]
-fn fd_wakeup_from_thread(waker: &FdWaker, delay_micros: u64) {
-    let handle = FdWakerHandle {
-        write_fd: waker.write_fd,
+#[pyo3(signature = (iterations, delay_micros=None, approaches=None, warmup=10, loop_factory=None, pin_cpu=None, rt_priority=None, rt_policy="fifo", timer="sleep", baseline_iterations=10_000, consumer="loop"))]
+#[allow(clippy::too_many_arguments)]
+fn run_comparison(
+    py: Python<'_>,
+    iterations: usize,
+    delay_micros: Option<&Bound<'_, PyAny>>,
+    approaches: Option<Vec<String>>,
+    warmup: usize,
+    loop_factory: Option<Bound<'_, PyAny>>,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+    timer: &str,
+    baseline_iterations: usize,
+    consumer: &str,
+) -> PyResult<Comparison> {
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let consumer = ComparisonConsumer::parse(consumer)?;
+    let load = ComparisonLoad {
+        iterations,
+        warmup,
+        delay: delay_micros.map_or(Ok(DelaySource::Fixed(0)), DelaySource::extract)?,
+        timer: TimerBackend::parse(timer)?,
+        placement,
     };
+    let approaches = match approaches {
+        Some(names) => names
+            .iter()
+            .map(|name| ComparedApproach::parse(name))
+            .collect::<PyResult<Vec<_>>>()?,
+        None => {
+            let mut approaches = Vec::new();
+            for approach in ComparedApproach::available() {
+                let consumable = consumer == ComparisonConsumer::Loop || approach.consumes_fd();
+                if consumable && approach.runs_here(py)? {
+                    approaches.push(approach);
+                }
+            }
+            approaches
+        }
+    };
+    if approaches.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "approaches must not be empty",
+        ));
+    }
 
-    std::thread::spawn(move || {
-        if delay_micros > 0 {
-            std::thread::sleep(Duration::from_micros(delay_micros));
+    let ffi_baselines = match baseline_iterations {
+        0 => Vec::new(),
+        n => ffi_baselines(py, n)?,
+    };
+    let (implementation, results) = match consumer {
+        ComparisonConsumer::Loop => {
+            let event_loop = new_comparison_loop(py, loop_factory.as_ref())?;
+            let implementation = loop_implementation(&event_loop);
+            let results = approaches
+                .iter()
+                .map(|&approach| {
+                    let recorder = LatencyRecorder::new(3, ClockSource::Monotonic, false, None)?;
+                    drive_approach(&event_loop, approach, &load, &recorder)?;
+                    Ok((approach.name(), Py::new(py, recorder)?))
+                })
+                .collect::<PyResult<Vec<_>>>();
+            event_loop.call_method0("close")?;
+            (implementation?, results?)
         }
-        handle.wake();
-    });
+        #[cfg(unix)]
+        ComparisonConsumer::Reactor => {
+            let targets = approaches
+                .iter()
+                .map(|approach| approach.reactor_target())
+                .collect::<PyResult<Vec<_>>>()?;
+            let results = approaches
+                .iter()
+                .zip(targets)
+                .map(|(approach, target)| {
+                    let recorder = LatencyRecorder::new(3, ClockSource::Monotonic, false, None)?;
+                    drive_reactor(py, target, &load, &recorder)?;
+                    Ok((approach.name(), Py::new(py, recorder)?))
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            ("reactor".to_string(), results)
+        }
+    };
+
+    Ok(Comparison {
+        iterations,
+        delay: load.delay,
+        event_loop: implementation,
+        results,
+        ffi_baselines,
+    })
 }
 
 // =============================================================================
-// Approach 2: call_soon_threadsafe wakeup (acquires GIL on Rust side)
+// Background noise: threads that compete with the wake path
 // =============================================================================
 
-/// A waker that uses call_soon_threadsafe to wake up the Python event loop.
-/// This DOES acquire the GIL on the Rust side.
+/// State shared between a NoiseThreads handle and its threads
+#[derive(Default)]
+struct NoiseState {
+    stop: AtomicBool,
+    /// Duty cycles completed across all threads
+    cycles: AtomicU64,
+    /// Time spent in the loaded part of each cycle (e.g. holding the GIL)
+    busy_ns: AtomicU64,
+    /// Unit operations performed: GIL acquisitions, allocations or syscalls
+    ops: AtomicU64,
+}
+
+impl NoiseState {
+    fn record_cycle(&self, busy: Duration, ops: u64) {
+        self.cycles.fetch_add(1, Ordering::Relaxed);
+        self.busy_ns
+            .fetch_add(busy.as_nanos() as u64, Ordering::Relaxed);
+        self.ops.fetch_add(ops, Ordering::Relaxed);
+    }
+}
+
+/// Handle to a group of background noise threads. They run until `stop()`
+/// (or the end of a `with` block); dropping the handle also asks them to stop.
 #[pyclass]
-struct CallbackWaker {
-    // Store the Python callback and event loop
-    callback: PyObject,
-    event_loop: PyObject,
+struct NoiseThreads {
+    #[pyo3(get)]
+    kind: &'static str,
+    state: Arc<NoiseState>,
+    threads: Mutex<Vec<std::thread::JoinHandle<()>>>,
 }
 
 #[pymethods]
-impl CallbackWaker {
-    #[new]
-    fn new(callback: PyObject, event_loop: PyObject) -> Self {
-        Self {
-            callback,
-            event_loop,
-        }
+impl NoiseThreads {
+    /// Stop the threads and wait for them to exit, releasing the GIL while blocked
+    fn stop(&self, py: Python<'_>) {
+        self.state.stop.store(true, Ordering::Relaxed);
+        let threads = std::mem::take(&mut *self.threads.lock().unwrap());
+        py.allow_threads(|| {
+            for thread in threads {
+                let _ = thread.join();
+            }
+        });
+    }
+
+    fn is_running(&self) -> bool {
+        !self.state.stop.load(Ordering::Relaxed)
+    }
+
+    /// `{"kind", "cycles", "busy_ns", "ops"}`: duty cycles completed across
+    /// all threads, the time they spent loaded, and the operations (GIL
+    /// acquisitions, allocations or syscalls) they performed
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = pyo3::types::PyDict::new(py);
+        stats.set_item("kind", self.kind)?;
+        stats.set_item("cycles", self.state.cycles.load(Ordering::Relaxed))?;
+        stats.set_item("busy_ns", self.state.busy_ns.load(Ordering::Relaxed))?;
+        stats.set_item("ops", self.state.ops.load(Ordering::Relaxed))?;
+        Ok(stats)
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> bool {
+        self.stop(py);
+        false
     }
 }
 
-/// Holder for the callback waker that can be sent across threads
-struct CallbackWakerHandle {
-    callback: PyObject,
-    event_loop: PyObject,
+impl Drop for NoiseThreads {
+    fn drop(&mut self) {
+        // Joining here could deadlock on the GIL, so only ask them to stop
+        self.state.stop.store(true, Ordering::Relaxed);
+    }
 }
 
-unsafe impl Send for CallbackWakerHandle {}
+/// Start `threads` threads that each run `cycle` until stopped. If one
+/// can't be spawned, those already started are stopped.
+fn spawn_noise<F>(
+    kind: &'static str,
+    threads: usize,
+    placement: ThreadPlacement,
+    cycle: F,
+) -> std::io::Result<NoiseThreads>
+where
+    F: Fn(&NoiseState) + Send + Sync + 'static,
+{
+    let state = Arc::new(NoiseState::default());
+    let cycle = Arc::new(cycle);
+    let handles = (0..threads)
+        .map(|index| {
+            let (state, cycle) = (state.clone(), cycle.clone());
+            let guard = track_thread();
+            placement
+                .thread(&format!("{kind}_noise"), Some(index))
+                .spawn(move || {
+                    placement.apply();
+                    run_as_task(&guard.0, || {
+                        while !state.stop.load(Ordering::Relaxed) {
+                            if guard.0.is_cancelled() {
+                                state.stop.store(true, Ordering::Relaxed);
+                                break;
+                            }
+                            cycle(&state);
+                        }
+                    });
+                })
+        })
+        .collect::<std::io::Result<Vec<_>>>()
+        .inspect_err(|_| state.stop.store(true, Ordering::Relaxed))?;
+    Ok(NoiseThreads {
+        kind,
+        state,
+        threads: Mutex::new(handles),
+    })
+}
 
-impl CallbackWakerHandle {
-    fn wake(&self) {
-        // This ACQUIRES THE GIL from the Rust thread
-        Python::with_gil(|py| {
-            // Call event_loop.call_soon_threadsafe(callback)
-            let _ = self
-                .event_loop
-                .call_method1(py, "call_soon_threadsafe", (&self.callback,));
+/// Busy-wait for `duration`
+fn spin_for(duration: Duration) {
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        std::hint::spin_loop();
+    }
+}
+
+/// Sleep for `duration`, or just yield the CPU if it is zero
+fn idle_for(duration: Duration) {
+    if duration.is_zero() {
+        std::thread::yield_now();
+    } else {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Spawn `n` Rust threads that each repeatedly acquire the GIL, hold it for
+/// `hold_micros` (spinning, so Python code can't run), then release it for
+/// `release_micros`. Use it to measure how each wake mechanism degrades when
+/// the GIL is contended. Returns a NoiseThreads handle; stop it when done.
+#[pyfunction]
+#[pyo3(signature = (n, hold_micros, release_micros, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn spawn_gil_noise_threads(
+    n: usize,
+    hold_micros: u64,
+    release_micros: u64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<NoiseThreads> {
+    if n == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "n must be at least 1",
+        ));
+    }
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    let (hold, release) = (
+        Duration::from_micros(hold_micros),
+        Duration::from_micros(release_micros),
+    );
+    Ok(spawn_noise("gil", n, placement, move |state| {
+        Python::with_gil(|_py| {
+            spin_for(hold);
         });
+        state.record_cycle(hold, 1);
+        idle_for(release);
+    })?)
+}
+
+/// Validated duty cycle shared by the CPU, allocation and syscall noise threads
+fn noise_duty_cycle(
+    n: usize,
+    busy_micros: u64,
+    idle_micros: u64,
+) -> PyResult<(Duration, Duration)> {
+    if n == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "n must be at least 1",
+        ));
     }
+    if busy_micros == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "busy_micros must be at least 1",
+        ));
+    }
+    Ok((
+        Duration::from_micros(busy_micros),
+        Duration::from_micros(idle_micros),
+    ))
 }
 
-/// Create a callback-based waker
+/// Spawn `n` threads that spin on the CPU for `busy_micros`, then idle for
+/// `idle_micros` (by default they never idle). They never touch the GIL, so
+/// they only compete for CPU time and cache.
 #[pyfunction]
-fn create_callback_waker(callback: PyObject, event_loop: PyObject) -> CallbackWaker {
-    CallbackWaker::new(callback, event_loop)
+#[pyo3(signature = (n, busy_micros=1000, idle_micros=0, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn spawn_cpu_noise_threads(
+    n: usize,
+    busy_micros: u64,
+    idle_micros: u64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<NoiseThreads> {
+    let (busy, idle) = noise_duty_cycle(n, busy_micros, idle_micros)?;
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    Ok(spawn_noise("cpu", n, placement, move |state| {
+        spin_for(busy);
+        state.record_cycle(busy, 0);
+        idle_for(idle);
+    })?)
 }
 
-/// Spawn a Rust OS thread that will wake up Python via call_soon_threadsafe after an optional delay.
-/// This ACQUIRES the GIL from the Rust thread.
+/// Spawn `n` threads that churn the allocator for `busy_micros` at a time:
+/// each allocates and touches `alloc_bytes` blocks, freeing them in a
+/// different order from the one they were allocated in so the heap
+/// fragments, then idles for `idle_micros`
 #[pyfunction]
-fn callback_wakeup_from_thread(py: Python<'_>, waker: &CallbackWaker, delay_micros: u64) {
-    let handle = CallbackWakerHandle {
-        callback: waker.callback.clone_ref(py),
-        event_loop: waker.event_loop.clone_ref(py),
-    };
+#[pyo3(signature = (n, busy_micros=1000, idle_micros=0, alloc_bytes=4096, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+#[allow(clippy::too_many_arguments)]
+fn spawn_alloc_noise_threads(
+    n: usize,
+    busy_micros: u64,
+    idle_micros: u64,
+    alloc_bytes: usize,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<NoiseThreads> {
+    // Live blocks per thread; each new block replaces a pseudo-random one
+    const LIVE: usize = 64;
+    let (busy, idle) = noise_duty_cycle(n, busy_micros, idle_micros)?;
+    if alloc_bytes == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "alloc_bytes must be at least 1",
+        ));
+    }
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    Ok(spawn_noise("alloc", n, placement, move |state| {
+        let mut live: Vec<Vec<u8>> = (0..LIVE).map(|_| Vec::new()).collect();
+        let mut slot = 0usize;
+        let mut ops = 0;
+        let start = Instant::now();
+        while start.elapsed() < busy {
+            let mut block = vec![0u8; alloc_bytes];
+            block[alloc_bytes - 1] = ops as u8;
+            slot = slot.wrapping_mul(31).wrapping_add(17) % LIVE;
+            live[slot] = std::hint::black_box(block);
+            ops += 1;
+        }
+        drop(live);
+        state.record_cycle(start.elapsed(), ops);
+        idle_for(idle);
+    })?)
+}
 
-    std::thread::spawn(move || {
-        if delay_micros > 0 {
-            std::thread::sleep(Duration::from_micros(delay_micros));
+/// Spawn `n` threads that make cheap syscalls (`getppid`) back to back for
+/// `busy_micros`, then idle for `idle_micros`, keeping the kernel entry path
+/// and its locks busy
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (n, busy_micros=1000, idle_micros=0, pin_cpu=None, rt_priority=None, rt_policy="fifo"))]
+fn spawn_syscall_noise_threads(
+    n: usize,
+    busy_micros: u64,
+    idle_micros: u64,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    rt_policy: &str,
+) -> PyResult<NoiseThreads> {
+    let (busy, idle) = noise_duty_cycle(n, busy_micros, idle_micros)?;
+    let placement = ThreadPlacement::new(pin_cpu, rt_priority, rt_policy)?;
+    Ok(spawn_noise("syscall", n, placement, move |state| {
+        let mut ops = 0;
+        let start = Instant::now();
+        while start.elapsed() < busy {
+            std::hint::black_box(unsafe { libc::getppid() });
+            ops += 1;
         }
-        handle.wake();
-    });
+        state.record_cycle(start.elapsed(), ops);
+        idle_for(idle);
+    })?)
 }
 
 // =============================================================================
-// Throughput benchmark: measure how many wakeups per second each approach can do
+// Tokio integration: wake from tokio tasks (optional `tokio` feature)
 // =============================================================================
 
-/// Spawn a Rust thread that sends N wakeups as fast as possible via FD.
-/// Returns immediately. Use this for throughput testing.
-#[pyfunction]
-fn fd_wakeup_burst(waker: &FdWaker, count: usize) {
-    let handle = FdWakerHandle {
-        write_fd: waker.write_fd,
-    };
-
-    std::thread::spawn(move || {
-        for _ in 0..count {
-            handle.wake();
-        }
-    });
+/// Shared multi-threaded runtime for tokio-driven wakes
+#[cfg(feature = "tokio")]
+fn tokio_runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_time()
+            .thread_name("wakerbench-tokio")
+            .build()
+            .expect("failed to build tokio runtime")
+    })
 }
 
-/// Spawn a Rust thread that sends N wakeups as fast as possible via call_soon_threadsafe.
-/// Returns immediately. Use this for throughput testing.
+/// Spawn a task on a shared multi-threaded tokio runtime that sends `count`
+/// wakes, sleeping `delay_micros` (via tokio's timer) before each. Accepts any
+/// waker in `mechanisms()`; wakes that call into Python acquire the GIL from
+/// a runtime worker thread, exactly as embedding code would.
+///
+/// tokio's timer has millisecond granularity, so small nonzero delays round up.
+#[cfg(feature = "tokio")]
 #[pyfunction]
-fn callback_wakeup_burst(py: Python<'_>, waker: &CallbackWaker, count: usize) {
-    let handle = CallbackWakerHandle {
-        callback: waker.callback.clone_ref(py),
-        event_loop: waker.event_loop.clone_ref(py),
+#[pyo3(signature = (waker, count, delay_micros=0))]
+fn spawn_tokio_waker_task(
+    waker: &Bound<'_, PyAny>,
+    count: usize,
+    delay_micros: u64,
+) -> PyResult<ProducerProgress> {
+    let wake = resolve_mechanism(waker)?.handle;
+    let counts: Arc<[AtomicU64]> = Arc::new([AtomicU64::new(0)]);
+    let finished = Arc::new(AtomicU64::new(0));
+    let progress = ProducerProgress {
+        counts: counts.clone(),
+        finished: finished.clone(),
     };
 
-    std::thread::spawn(move || {
+    tokio_runtime().spawn(async move {
         for _ in 0..count {
-            handle.wake();
+            if delay_micros > 0 {
+                tokio::time::sleep(Duration::from_micros(delay_micros)).await;
+            }
+            wake.wake();
+            counts[0].fetch_add(1, Ordering::SeqCst);
         }
+        wake.finish();
+        finished.fetch_add(1, Ordering::SeqCst);
     });
+    Ok(progress)
 }
 
 // =============================================================================
-// Latency benchmark helpers
+// Runtime information
 // =============================================================================
 
-/// Shared counter for coordinating benchmark iterations
-#[pyclass]
-struct BenchCoordinator {
-    counter: Arc<AtomicU64>,
-}
+/// Describe the interpreter the module is running in, so benchmark reports can
+/// distinguish the GIL and free-threaded (PEP 703) regimes. On a free-threaded
+/// build `Python::with_gil` only attaches the thread; there is no lock to wait on.
+#[pyfunction]
+fn runtime_info<'py>(py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+    let sys = py.import("sys")?;
+    let sysconfig = py.import("sysconfig")?;
 
-#[pymethods]
-impl BenchCoordinator {
-    #[new]
-    fn new() -> Self {
-        Self {
-            counter: Arc::new(AtomicU64::new(0)),
-        }
-    }
+    // sys._is_gil_enabled() exists from 3.13; older interpreters always have a GIL
+    let gil_enabled = match sys.getattr("_is_gil_enabled") {
+        Ok(is_gil_enabled) => is_gil_enabled.call0()?.extract::<bool>()?,
+        Err(_) => true,
+    };
+    let free_threaded_build = sysconfig
+        .call_method1("get_config_var", ("Py_GIL_DISABLED",))?
+        .extract::<Option<i64>>()?
+        .unwrap_or(0)
+        != 0;
 
-    fn get_count(&self) -> u64 {
-        self.counter.load(Ordering::SeqCst)
-    }
+    let info = pyo3::types::PyDict::new(py);
+    info.set_item("python_version", sys.getattr("version")?)?;
+    info.set_item(
+        "implementation",
+        sys.getattr("implementation")?.getattr("name")?,
+    )?;
+    info.set_item("free_threaded_build", free_threaded_build)?;
+    info.set_item("gil_enabled", gil_enabled)?;
+    info.set_item("platform", std::env::consts::OS)?;
+    info.set_item("arch", std::env::consts::ARCH)?;
+    Ok(info)
+}
 
-    fn reset(&self) {
-        self.counter.store(0, Ordering::SeqCst);
+/// Per-mechanism names from before the generic drivers, as `(alias, driver)`.
+/// Each still works but warns; the driver accepts the same arguments.
+const DEPRECATED_ALIASES: &[(&std::ffi::CStr, &str)] = &[
+    #[cfg(unix)]
+    #[cfg(unix)]
+    (c"fd_wakeup_from_thread", "wakeup_from_thread"),
+    (c"callback_wakeup_from_thread", "wakeup_from_thread"),
+    #[cfg(unix)]
+    (c"fd_wakeup_burst", "wakeup_burst"),
+    (c"callback_wakeup_burst", "wakeup_burst"),
+    #[cfg(unix)]
+    (c"fd_wakeup_sequence", "wakeup_sequence"),
+    (c"callback_wakeup_sequence", "wakeup_sequence"),
+];
+
+/// Add each deprecated alias as a function that emits a `DeprecationWarning`
+/// and forwards its arguments to the generic driver.
+fn add_deprecated_aliases(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    for &(alias, driver) in DEPRECATED_ALIASES {
+        let target = m.getattr(driver)?.unbind();
+        let message = std::ffi::CString::new(format!(
+            "{}() is deprecated; use {driver}(), which accepts any waker",
+            alias.to_string_lossy()
+        ))?;
+        let function = pyo3::types::PyCFunction::new_closure(
+            m.py(),
+            Some(alias),
+            None,
+            move |args, kwargs| -> PyResult<PyObject> {
+                let py = args.py();
+                let category = py.get_type::<pyo3::exceptions::PyDeprecationWarning>();
+                PyErr::warn(py, &category, &message, 1)?;
+                target.call(py, args, kwargs)
+            },
+        )?;
+        m.add(alias.to_str()?, function)?;
     }
+    Ok(())
 }
 
-/// Spawn a thread that will perform `iterations` wakeups with a small delay between each.
-/// Each wakeup increments the coordinator's counter, allowing Python to verify receipt.
-#[pyfunction]
-fn fd_wakeup_sequence(waker: &FdWaker, coordinator: &BenchCoordinator, iterations: usize) {
-    let handle = FdWakerHandle {
-        write_fd: waker.write_fd,
-    };
-    let counter = coordinator.counter.clone();
+// All pyclasses are either immutable after construction or synchronize
+// internally, so the module is safe to run without the GIL.
+#[pymodule(gil_used = false)]
+fn wakerbench(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Lets wakers detect they were inherited across fork()
+    #[cfg(unix)]
+    install_fork_handler();
 
-    std::thread::spawn(move || {
-        for _ in 0..iterations {
-            counter.fetch_add(1, Ordering::SeqCst);
-            handle.wake();
-            // Small delay to allow Python to process
-            std::thread::sleep(Duration::from_micros(100));
-        }
-    });
-}
+    // FDs held open by wakers and timers
+    #[cfg(unix)]
+    m.add_function(wrap_pyfunction!(active_fd_count, m)?)?;
 
-#[pyfunction]
-fn callback_wakeup_sequence(
-    py: Python<'_>,
-    waker: &CallbackWaker,
-    coordinator: &BenchCoordinator,
-    iterations: usize,
-) {
-    let handle = CallbackWakerHandle {
-        callback: waker.callback.clone_ref(py),
-        event_loop: waker.event_loop.clone_ref(py),
-    };
-    let counter = coordinator.counter.clone();
+    // Generic drivers over any registered wake mechanism
+    m.add_function(wrap_pyfunction!(mechanisms, m)?)?;
+    m.add_function(wrap_pyfunction!(wake_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(wakeup_from_thread, m)?)?;
+    m.add_function(wrap_pyfunction!(wakeup_burst, m)?)?;
+    m.add_function(wrap_pyfunction!(wakeup_sequence, m)?)?;
+    m.add_function(wrap_pyfunction!(wakeup_rate, m)?)?;
+    m.add_function(wrap_pyfunction!(wakeup_poisson, m)?)?;
+    m.add_function(wrap_pyfunction!(spawn_periodic, m)?)?;
+    m.add_function(wrap_pyfunction!(wakeup_multi, m)?)?;
+    m.add_class::<Scenario>()?;
+    m.add_class::<Delay>()?;
+    m.add_function(wrap_pyfunction!(run_benchmark, m)?)?;
+    m.add_class::<Waker>()?;
 
-    std::thread::spawn(move || {
-        for _ in 0..iterations {
-            counter.fetch_add(1, Ordering::SeqCst);
-            handle.wake();
-            // Small delay to allow Python to process
-            std::thread::sleep(Duration::from_micros(100));
-        }
-    });
-}
+    // FD-based approach (Unix only)
+    #[cfg(unix)]
+    {
+        m.add_class::<FdWaker>()?;
+        m.add_class::<FullPolicy>()?;
+        m.add_class::<ReaderRegistration>()?;
+        m.add_function(wrap_pyfunction!(create_fd_waker, m)?)?;
+        m.add_function(wrap_pyfunction!(fd_wakeup_burst_batched, m)?)?;
+        m.add_function(wrap_pyfunction!(fd_wakeup_roundtrip, m)?)?;
+    }
 
-#[pymodule]
-fn wakerbench(m: &Bound<'_, PyModule>) -> PyResult<()> {
-    // FD-based approach
-    m.add_class::<FdWaker>()?;
-    m.add_function(wrap_pyfunction!(create_fd_waker, m)?)?;
-    m.add_function(wrap_pyfunction!(fd_wakeup_from_thread, m)?)?;
-    m.add_function(wrap_pyfunction!(fd_wakeup_burst, m)?)?;
-    m.add_function(wrap_pyfunction!(fd_wakeup_sequence, m)?)?;
+    // Payload delivery (Unix only)
+    #[cfg(unix)]
+    {
+        m.add_class::<MpscChannel>()?;
+        m.add_function(wrap_pyfunction!(channel_send_burst, m)?)?;
+    }
+
+    // SPSC ring buffer data plane
+    m.add_class::<SpscRing>()?;
+    #[cfg(unix)]
+    m.add_function(wrap_pyfunction!(fd_ring_burst, m)?)?;
+    m.add_function(wrap_pyfunction!(callback_ring_burst, m)?)?;
 
     // Callback-based approach
     m.add_class::<CallbackWaker>()?;
     m.add_function(wrap_pyfunction!(create_callback_waker, m)?)?;
-    m.add_function(wrap_pyfunction!(callback_wakeup_from_thread, m)?)?;
-    m.add_function(wrap_pyfunction!(callback_wakeup_burst, m)?)?;
-    m.add_function(wrap_pyfunction!(callback_wakeup_sequence, m)?)?;
+    m.add_function(wrap_pyfunction!(callback_wakeup_roundtrip, m)?)?;
+    m.add_function(wrap_pyfunction!(measure_gil_acquire, m)?)?;
+    m.add_function(wrap_pyfunction!(measure_callmethod_overhead, m)?)?;
+    m.add_function(wrap_pyfunction!(baseline_noop, m)?)?;
+    m.add_function(wrap_pyfunction!(baseline_args, m)?)?;
+    m.add_function(wrap_pyfunction!(baseline_return_int, m)?)?;
+    m.add_function(wrap_pyfunction!(baseline_return_tuple, m)?)?;
+    m.add_function(wrap_pyfunction!(measure_ffi_baselines, m)?)?;
+
+    // Batched callback approach
+    m.add_class::<BatchedCallbackWaker>()?;
+
+    // Trio approach
+    m.add_class::<TrioWaker>()?;
+    m.add_function(wrap_pyfunction!(create_trio_waker, m)?)?;
+
+    // gevent approach
+    m.add_class::<GeventWaker>()?;
+    m.add_function(wrap_pyfunction!(create_gevent_waker, m)?)?;
+
+    // signal.set_wakeup_fd approach (Unix only)
+    #[cfg(unix)]
+    {
+        m.add_class::<SignalWaker>()?;
+        m.add_function(wrap_pyfunction!(create_signal_waker, m)?)?;
+    }
+
+    // Py_AddPendingCall approach
+    m.add_class::<PendingCallWaker>()?;
+    m.add_function(wrap_pyfunction!(create_pending_call_waker, m)?)?;
+
+    // Cross-process approach over a Unix datagram socket
+    #[cfg(unix)]
+    {
+        m.add_class::<RemoteWaker>()?;
+        m.add_class::<RemoteWakerClient>()?;
+    }
+
+    // Cross-process approach via shared memory and a named semaphore
+    #[cfg(unix)]
+    {
+        m.add_class::<ShmWaker>()?;
+        m.add_class::<ShmWakerClient>()?;
+    }
+
+    // Priority approach: separate urgent and normal pipes
+    #[cfg(unix)]
+    {
+        m.add_class::<PriorityFdWaker>()?;
+        m.add_function(wrap_pyfunction!(priority_wakeup_inversion, m)?)?;
+    }
+
+    // Futex approach with a forwarder thread (Linux only)
+    #[cfg(target_os = "linux")]
+    {
+        m.add_class::<FutexWaker>()?;
+    }
+
+    // threading.Event baseline bridged by run_in_executor
+    m.add_class::<ThreadingEventWaker>()?;
+
+    // asyncio.Future completion from Rust threads
+    m.add_function(wrap_pyfunction!(complete_future_from_thread, m)?)?;
+
+    // asyncio.run_coroutine_threadsafe
+    m.add_class::<CoroutineWaker>()?;
+
+    // One-shot wake, torn down once delivered
+    m.add_class::<OneShotWaker>()?;
+
+    // eventfd-based approach (Linux only)
+    #[cfg(target_os = "linux")]
+    {
+        m.add_class::<EventFdWaker>()?;
+        m.add_function(wrap_pyfunction!(create_eventfd_waker, m)?)?;
+        m.add_function(wrap_pyfunction!(eventfd_wakeup_roundtrip, m)?)?;
+    }
+
+    // kqueue EVFILT_USER approach (macOS only)
+    #[cfg(target_os = "macos")]
+    {
+        m.add_class::<KqueueUserWaker>()?;
+        m.add_function(wrap_pyfunction!(create_kqueue_waker, m)?)?;
+        m.add_function(wrap_pyfunction!(kqueue_wakeup_roundtrip, m)?)?;
+    }
+
+    // Coalescing FD approach (Unix only)
+    #[cfg(unix)]
+    {
+        m.add_class::<CoalescingFdWaker>()?;
+    }
+
+    // Socket-based approach (cross-platform)
+    m.add_class::<SocketWaker>()?;
+    m.add_function(wrap_pyfunction!(create_socket_waker, m)?)?;
+    m.add_function(wrap_pyfunction!(socket_wakeup_roundtrip, m)?)?;
+
+    // IOCP completion approach (Windows only)
+    #[cfg(windows)]
+    {
+        m.add_class::<IocpWaker>()?;
+    }
 
     // Coordination
     m.add_class::<BenchCoordinator>()?;
+    m.add_function(wrap_pyfunction!(measure_counter_orderings, m)?)?;
+    m.add_class::<StartBarrier>()?;
+    m.add_class::<ProducerProgress>()?;
+    m.add_class::<BenchTask>()?;
+    m.add_class::<ProducerPool>()?;
+    m.add_class::<BenchmarkResult>()?;
+    m.add_class::<RegressionReport>()?;
+    m.add_class::<Comparison>()?;
+
+    // Fan-out to several event loops
+    m.add_class::<WakerGroup>()?;
+
+    // Sequence-numbered wakes
+    m.add_class::<SequenceValidator>()?;
+    m.add_function(wrap_pyfunction!(sequenced_callback_wakeup_burst, m)?)?;
+    m.add_function(wrap_pyfunction!(sequenced_callback_wakeup_multi, m)?)?;
+    #[cfg(unix)]
+    {
+        m.add_class::<SequencedFdWaker>()?;
+        m.add_function(wrap_pyfunction!(sequenced_fd_wakeup_burst, m)?)?;
+        m.add_function(wrap_pyfunction!(sequenced_fd_wakeup_multi, m)?)?;
+    }
+
+    // Thread placement (Linux only)
+    #[cfg(target_os = "linux")]
+    {
+        m.add_function(wrap_pyfunction!(pin_current_thread, m)?)?;
+        m.add_function(wrap_pyfunction!(available_cpus, m)?)?;
+        m.add_function(wrap_pyfunction!(current_cpu, m)?)?;
+    }
+    #[cfg(unix)]
+    m.add_function(wrap_pyfunction!(rt_priority_fallbacks, m)?)?;
+    m.add_function(wrap_pyfunction!(set_thread_options, m)?)?;
+    m.add_function(wrap_pyfunction!(thread_options, m)?)?;
+    m.add_function(wrap_pyfunction!(live_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(shutdown, m)?)?;
+    m.add_function(wrap_pyfunction!(thread_errors, m)?)?;
+    m.add("ProducerPanic", m.py().get_type::<ProducerPanic>())?;
+
+    // Latency recording
+    m.add_class::<LatencyRecorder>()?;
+    m.add_class::<LatencySummary>()?;
+    m.add_class::<SignificanceTest>()?;
+    m.add_function(wrap_pyfunction!(compare_results, m)?)?;
+    m.add_function(wrap_pyfunction!(py_monotonic_ns, m)?)?;
+
+    // Clock sources
+    m.add_class::<ClockSource>()?;
+    m.add_function(wrap_pyfunction!(set_timestamp_clock, m)?)?;
+    m.add_function(wrap_pyfunction!(timestamp_clock, m)?)?;
+    m.add_function(wrap_pyfunction!(now_ns, m)?)?;
+    m.add_function(wrap_pyfunction!(clock_resolution, m)?)?;
+    m.add_class::<ClockCalibration>()?;
+    m.add_class::<TimestampingCallback>()?;
+    m.add_function(wrap_pyfunction!(clock_calibrate, m)?)?;
+
+    // Allocation and syscall accounting
+    m.add_function(wrap_pyfunction!(set_accounting, m)?)?;
+    m.add_function(wrap_pyfunction!(accounting_enabled, m)?)?;
+
+    // Tracing
+    #[cfg(feature = "trace")]
+    {
+        m.add_function(wrap_pyfunction!(start_trace, m)?)?;
+        m.add_function(wrap_pyfunction!(stop_trace, m)?)?;
+    }
+
+    // Performance counters
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    {
+        m.add_class::<PerfCounters>()?;
+        m.add_function(wrap_pyfunction!(set_perf_counters, m)?)?;
+    }
+
+    // Tokio integration
+    #[cfg(feature = "tokio")]
+    m.add_function(wrap_pyfunction!(spawn_tokio_waker_task, m)?)?;
+
+    // Background noise
+    m.add_class::<NoiseThreads>()?;
+    m.add_function(wrap_pyfunction!(spawn_gil_noise_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(spawn_cpu_noise_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(spawn_alloc_noise_threads, m)?)?;
+    #[cfg(unix)]
+    m.add_function(wrap_pyfunction!(spawn_syscall_noise_threads, m)?)?;
+
+    // Runtime information
+    m.add_function(wrap_pyfunction!(run_comparison, m)?)?;
+    m.add_function(wrap_pyfunction!(event_loop_implementation, m)?)?;
+    m.add_function(wrap_pyfunction!(runtime_info, m)?)?;
+
+    // Old per-mechanism names; added last so their drivers exist
+    add_deprecated_aliases(m)?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn claim_new_counts_each_wake_once_when_drains_race() {
+        let seen = AtomicU64::new(0);
+        // The later drain read the larger total but claimed first
+        assert_eq!(claim_new(&seen, 12), 12);
+        assert_eq!(claim_new(&seen, 10), 0);
+        assert_eq!(seen.load(Ordering::Relaxed), 12);
+        assert_eq!(claim_new(&seen, 15), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn wake_many_splits_events_into_pipe_buf_writes() {
+        let waker = create_fd_waker().unwrap();
+        let handle = waker.handle().unwrap();
+        let events = WAKE_MANY_BYTES as u64 * 2 + 7;
+        handle.wake_many(events);
+        assert_eq!(waker.write_stats.writes.load(Ordering::Relaxed), 3);
+        assert_eq!(waker.write_stats.bytes.load(Ordering::Relaxed), events);
+        assert_eq!(waker.drain().unwrap() as u64, events);
+
+        handle.wake_many(0);
+        assert_eq!(waker.write_stats.writes.load(Ordering::Relaxed), 3);
+        assert_eq!(waker.drain().unwrap(), 0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn eventfd_wake_many_adds_every_event_to_the_counter() {
+        let waker = EventFdWaker::new(false).unwrap();
+        let handle = waker.handle();
+        handle.wake_many(1_000);
+        handle.wake();
+        assert_eq!(waker.drain().unwrap(), 1_001);
+        assert_eq!(waker.drain().unwrap(), 0);
+        assert_eq!(waker.errors.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn run_length_takes_exactly_one_bound() {
+        assert!(matches!(
+            RunLength::parse(Some(5), None, "count"),
+            Ok(RunLength::Count(5))
+        ));
+        assert!(matches!(
+            RunLength::parse(None, Some(0.5), "count"),
+            Ok(RunLength::Duration(d)) if d == Duration::from_millis(500)
+        ));
+        assert!(RunLength::parse(None, None, "count").is_err());
+        assert!(RunLength::parse(Some(5), Some(0.5), "count").is_err());
+        assert!(RunLength::parse(None, Some(-1.0), "count").is_err());
+    }
+
+    #[test]
+    fn sequence_validator_classifies_arrivals() {
+        let mut validator = SequenceValidator::new();
+        validator.observe_all(vec![0, 1, 3, 2, 3, 5]).unwrap();
+        assert_eq!(validator.received, 6);
+        assert_eq!(validator.duplicates, 1);
+        assert_eq!(validator.reordered, 1);
+        assert_eq!(validator.highest, Some(5));
+        assert_eq!(validator.missing(None), vec![4]);
+        assert_eq!(validator.missing(Some(8)), vec![4, 6, 7]);
+        assert!(validator.observe(MAX_TRACKED_SEQUENCE).is_err());
+    }
+
+    #[test]
+    fn spsc_ring_drops_when_full() {
+        let ring = SpscRing::new(2).unwrap();
+        assert!(ring.push(1));
+        assert!(ring.push(2));
+        assert!(!ring.push(3));
+        assert_eq!(ring.__len__(), 2);
+        assert_eq!(ring.dropped.load(Ordering::Relaxed), 1);
+
+        // Positions wrap around the slots once the consumer catches up
+        ring.head.store(2, Ordering::Release);
+        assert!(ring.push(4));
+        assert_eq!(ring.slots[0].load(Ordering::Relaxed), 4);
+        assert_eq!(ring.__len__(), 1);
+    }
+
+    #[test]
+    fn poisson_intervals_are_reproducible_and_average_the_mean() {
+        let mean = Duration::from_micros(100);
+        let (mut a, mut b) = (poisson_intervals(mean, 7), poisson_intervals(mean, 7));
+        let gaps: Vec<Duration> = (0..20_000).map(|_| a()).collect();
+        assert!(gaps.iter().all(|&gap| gap == b()));
+        let average = gaps.iter().sum::<Duration>() / gaps.len() as u32;
+        assert!(
+            (95..=105).contains(&average.as_micros()),
+            "average gap {average:?}"
+        );
+    }
+
+    #[test]
+    fn scheduled_wake_times_late_slots_from_their_due_time() {
+        let recording = Recording::shared();
+        let recorder = Some((recording.clone(), ClockSource::Monotonic));
+        scheduled_wake(&recorder, monotonic_ns(), false, || {});
+        scheduled_wake(&recorder, monotonic_ns() - 5_000_000, true, || {});
+
+        let recording = recording.lock().unwrap();
+        assert_eq!(recording.hist.len(), 2);
+        assert_eq!(recording.corrected, 1);
+        assert!(recording.hist.max() >= 5_000_000);
+        assert!(recording.hist.min() < 5_000_000);
+    }
+
+    fn histogram(values: impl IntoIterator<Item = u64>) -> Histogram<u64> {
+        let mut hist = Histogram::new(3).unwrap();
+        values.into_iter().for_each(|v| hist.record(v).unwrap());
+        hist
+    }
+
+    #[test]
+    fn mann_whitney_detects_a_shift_but_not_a_copy() {
+        let a = histogram(1_000..2_000);
+        let (u, z) = mann_whitney(&a, &a);
+        assert_eq!(u, 1_000.0 * 1_000.0 / 2.0);
+        assert!(z.abs() < 0.1, "z = {z}");
+
+        let b = histogram(1_500..2_500);
+        let (u, z) = mann_whitney(&a, &b);
+        assert!(u < 1_000.0 * 1_000.0 / 2.0);
+        assert!(z < -10.0, "z = {z}");
+        assert!(erfc(z.abs() / std::f64::consts::SQRT_2) < 1e-6);
+    }
+
+    #[test]
+    fn bootstrap_median_diffs_bracket_the_shift() {
+        let (a, b) = (histogram(1_000..2_000), histogram(1_500..2_500));
+        let diffs = bootstrap_median_diffs(&a, &b, 500, 3);
+        assert_eq!(diffs, bootstrap_median_diffs(&a, &b, 500, 3));
+        assert!(diffs.windows(2).all(|pair| pair[0] <= pair[1]));
+        let (low, high) = (diffs[12], diffs[487]);
+        assert!(low < 500.0 && 500.0 < high, "[{low}, {high}]");
+    }
+
+    #[test]
+    fn metric_check_applies_tolerance_in_the_worse_direction() {
+        // Throughput: higher is better, so only a fall regresses
+        assert!(!MetricCheck::new("throughput_per_sec", 1000.0, 960.0, true, 5.0).regressed);
+        assert!(MetricCheck::new("throughput_per_sec", 1000.0, 940.0, true, 5.0).regressed);
+        assert!(!MetricCheck::new("throughput_per_sec", 1000.0, 2000.0, true, 5.0).regressed);
+        // Latency: lower is better, so only a rise regresses
+        assert!(MetricCheck::new("p99", 100.0, 106.0, false, 5.0).regressed);
+        assert!(!MetricCheck::new("p99", 100.0, 50.0, false, 5.0).regressed);
+        // No baseline to compare against
+        let check = MetricCheck::new("p99", 0.0, 50.0, false, 5.0);
+        assert_eq!(check.change_pct, None);
+        assert!(!check.regressed);
+    }
+}